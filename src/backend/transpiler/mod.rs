@@ -1,15 +1,50 @@
-use std::{fmt::Debug, process::exit};
+use std::fmt::Debug;
 
-use crate::frontend::types::{Expression, LiteralTypes, Statement, TokenTypes, VariableTypes};
+use crate::frontend::lexer::{parse_int_literal, radix};
+use crate::frontend::types::{
+    ArrayAccess, EnumVariant, Expression, FieldValue, FuncParam, LiteralTypes, Pattern, Start,
+    Statement, StructField, SwitchCase, TokenTypes, VarDeclarationKind, VariableTypes,
+};
+use crate::frontend::visit::{walk_statement, Visitor};
+use crate::{abort, error, set_module_file, warning};
 
 #[derive(Debug)]
 pub struct Transpiler {
     ast: Statement,
     pub c_src_code: String,
     variables: Vec<Variable>,
-    var_names: Vec<String>,
-    functions: Vec<Variable>,
-    func_names: Vec<String>,
+    scopes: Vec<usize>,
+    // variables defined outside of functions, named like functions
+    globals: Vec<Variable>,
+    // globals whose value is known at compile time, their C name is that value
+    constants: Vec<String>,
+    // the values of the i32 constants, so constants using them can be computed too
+    int_constants: Vec<(String, i32)>,
+    // C assignments of the globals whose value is only known once the program runs
+    initializers: String,
+    // C functions (name, code) of lambdas and of functions used as values
+    lambdas: Vec<(String, String)>,
+    functions: Vec<Function>,
+    // module being transpiled, empty for the file given to bline
+    module: String,
+    // (importing module, alias, imported module) of every 'use'
+    imports: Vec<(String, String, String)>,
+    structs: Vec<Struct>,
+    enums: Vec<Enum>,
+    // structs whose C definition is being generated, a struct cannot contain itself
+    defining: Vec<String>,
+    // C type definitions (name, code) in the order they depend on each other
+    definitions: Vec<(String, String)>,
+    return_type: VariableTypes,
+    // enclosing loops and switches, innermost last
+    breakables: Vec<Breakable>,
+    // makes the names of generated C variables and labels unique
+    generated: usize,
+    indentation: usize,
+    // start of the statement being transpiled, used for error messages
+    position: Start,
+    // warnings already given, an expression can be evaluated more than once
+    warnings: Vec<(u32, u32, String)>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -18,10 +53,49 @@ struct Expr {
     literal_type: VariableTypes,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 struct Variable {
     name: String,
     var_type: VariableTypes,
+    kind: VarDeclarationKind,
+    // how the variable is read in C, 'x' or 'x.value' once narrowed
+    c_name: String,
+    // an optional variable known to not be null
+    narrowed: bool,
+    // a copy of a variable of the function enclosing a lambda
+    captured: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Function {
+    // 'foo' or 'module.foo' for functions of imported modules
+    name: String,
+    // builtins can be called from every module
+    builtin: bool,
+    // None means the function takes a format str and then any number of values, like printf
+    params: Option<Vec<VariableTypes>>,
+    return_type: VariableTypes,
+}
+
+#[derive(Debug, Clone)]
+struct Struct {
+    name: String,
+    fields: Vec<StructField>,
+    doc: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Enum {
+    name: String,
+    variants: Vec<EnumVariant>,
+    doc: Option<String>,
+}
+
+#[derive(Debug)]
+enum Breakable {
+    // used is set once a 'brk' inside a C switch has to jump to the label
+    Loop { label: String, used: bool },
+    Switch,
 }
 
 impl Transpiler {
@@ -30,411 +104,415 @@ impl Transpiler {
             ast: tree,
             c_src_code: String::from("#include \"stdlib.h\"\n"),
             variables: Vec::new(),
-            var_names: Vec::new(),
-            functions: Vec::new(),
-            func_names: Vec::new(),
+            scopes: Vec::new(),
+            globals: Vec::new(),
+            constants: Vec::new(),
+            int_constants: Vec::new(),
+            initializers: String::new(),
+            lambdas: Vec::new(),
+            definitions: Vec::new(),
+            structs: Vec::new(),
+            enums: Vec::new(),
+            defining: Vec::new(),
+            module: String::new(),
+            imports: Vec::new(),
+            functions: vec![Function {
+                name: String::from("println"),
+                builtin: true,
+                params: None,
+                return_type: VariableTypes::Nul,
+            }],
+            return_type: VariableTypes::Nul,
+            breakables: Vec::new(),
+            generated: 0,
+            indentation: 0,
+            position: Start { line: 1, column: 0 },
+            warnings: Vec::new(),
         }
     }
 
     pub fn transpile_abstract_syntax_tree(&mut self) {
         let stmts = self.get_body().to_vec();
 
-        let c_code: String = stmts
-            .iter()
-            .map(|st| {
-                //
-                self.transpile_stmt(st)
-            })
-            .collect();
+        // types and functions are declared up front so they can be used before their definition
+        self.each_stmt(&stmts, |t, st| t.declare_import(st));
+        self.each_stmt(&stmts, |t, st| t.declare_type(st));
+        self.each_stmt(&stmts, |t, st| t.declare_function(st));
 
+        // globals are set in the order they are defined, imported modules first
+        let globals = self.each_stmt(&stmts, Self::transpile_global);
+        let initializer = match self.initializers.is_empty() {
+            true => String::new(),
+            false => format!(
+                "static void __init_globals(void) {{\n{}}}\n",
+                self.initializers
+            ),
+        };
+
+        let prototypes = self.each_stmt(&stmts, Self::transpile_prototype);
+        let c_code = self.each_stmt(&stmts, |t, st| match st {
+            Statement::VariableDeclaration { .. } => String::new(),
+            _ => t.transpile_stmt(st),
+        });
+
+        let definitions: String = self.definitions.iter().map(|(_, d)| d.as_str()).collect();
+
+        self.c_src_code.push_str(&definitions);
+        self.c_src_code.push_str(&prototypes);
+        let lambdas: String = self.lambdas.iter().map(|(_, l)| l.as_str()).collect();
+
+        self.c_src_code.push_str(&globals);
+        self.c_src_code.push_str(&lambdas);
+        self.c_src_code.push_str(&initializer);
         self.c_src_code.push_str(&c_code);
     }
 
-    fn transpile_stmt(&mut self, stmt: &Statement) -> String {
-        let mut c_stmt = String::new();
+    /// runs f on every statement, the statements of imported modules inside their own namespace
+    fn each_stmt(&mut self, stmts: &[Statement], f: fn(&mut Self, &Statement) -> String) -> String {
+        let mut c_code = String::new();
 
-        match stmt {
-            Statement::FunctionDeclaration {
-                name,
-                r#type,
-                params,
-                body,
-                ..
-            } => {
-                let c_type = self.get_c_type(r#type);
-                // let c_params = params.iter().for_each(|p| )
+        for stmt in stmts {
+            if let Statement::Module {
+                name, file, body, ..
+            } = stmt
+            {
+                self.module = name.to_owned();
+                set_module_file(Some(file.to_owned()));
+                c_code.push_str(&self.each_stmt(body, f));
+                self.module = String::new();
+                set_module_file(None);
+            } else {
+                c_code.push_str(&f(self, stmt));
+            }
+        }
 
-                let bd: String = if let Some(b) = body {
-                    (*b).iter().map(|st| self.transpile_stmt(st)).collect()
-                } else {
-                    String::new()
-                };
-                let c_body = bd;
+        c_code
+    }
 
-                c_stmt.push_str(&format!("{} {}(){{{}}}", c_type, name, c_body).to_string());
-            }
-            Statement::VariableDeclaration {
-                name,
-                kind,
-                r#type,
-                value,
-                ..
-            } => {
-                ////// TODO:
-                // set variable on variables array on both BOTH!
+    fn declare_import(&mut self, stmt: &Statement) -> String {
+        if let Statement::Use {
+            name,
+            module: Some(module),
+            ..
+        } = stmt
+        {
+            self.imports
+                .push((self.module.to_owned(), name.to_owned(), module.to_owned()));
+        }
+        String::new()
+    }
+
+    fn declare_type(&mut self, stmt: &Statement) -> String {
+        let (Statement::StructDeclaration {
+            start, name, doc, ..
+        }
+        | Statement::EnumDeclaration {
+            start, name, doc, ..
+        }) = stmt
+        else {
+            return String::new();
+        };
+        self.position = start.to_owned();
+        let name = &self.qualify(name);
+
+        if self.get_struct(name).is_some() || self.get_enum(name).is_some() {
+            error(
+                start.line,
+                start.column,
+                format!("Type '{}' is already defined", name),
+            );
+            abort()
+        }
+
+        if let Statement::StructDeclaration { fields, .. } = stmt {
+            for (i, field) in fields.iter().enumerate() {
+                if fields[..i].iter().any(|f| f.name.eq(&field.name)) {
+                    error(
+                        start.line,
+                        start.column,
+                        format!("Field '{}' is already defined in '{}'", field.name, name),
+                    );
+                    abort()
+                }
             }
-            Statement::FunctionCall(fc) => match fc {
-                Expression::Call { name, arguments } => {
-                    let mut c_args = match arguments {
-                        Some(a) => (*a)
-                            .iter()
-                            .map(|e| format!("{},", self.eval_expr(e).value))
-                            .collect(),
-                        None => String::new(),
-                    };
 
-                    c_args.pop();
+            let fields = fields
+                .iter()
+                .map(|f| StructField {
+                    name: f.name.to_owned(),
+                    r#type: self.resolve_type(&f.r#type),
+                    doc: f.doc.to_owned(),
+                })
+                .collect();
+            self.structs.push(Struct {
+                name: name.to_owned(),
+                fields,
+                doc: doc.to_owned(),
+            });
+        }
 
-                    c_stmt.push_str(format!("{}({});", name, c_args).as_str())
+        if let Statement::EnumDeclaration { variants, .. } = stmt {
+            for (i, variant) in variants.iter().enumerate() {
+                if variants[..i].iter().any(|v| v.name.eq(&variant.name)) {
+                    error(
+                        start.line,
+                        start.column,
+                        format!(
+                            "Variant '{}' is already defined in '{}'",
+                            variant.name, name
+                        ),
+                    );
+                    abort()
                 }
-                _ => {}
-            },
-            _ => {}
+            }
+
+            let variants = variants
+                .iter()
+                .map(|v| EnumVariant {
+                    name: v.name.to_owned(),
+                    payload: v
+                        .payload
+                        .as_ref()
+                        .map(|p| p.iter().map(|t| self.resolve_type(t)).collect()),
+                    doc: v.doc.to_owned(),
+                })
+                .collect();
+            self.enums.push(Enum {
+                name: name.to_owned(),
+                variants,
+                doc: doc.to_owned(),
+            });
+        }
+
+        String::new()
+    }
+
+    fn declare_function(&mut self, stmt: &Statement) -> String {
+        if let Statement::FunctionDeclaration {
+            start,
+            name,
+            r#type,
+            params,
+            ..
+        } = stmt
+        {
+            self.position = start.to_owned();
+            let name = &self.qualify(name);
+            if self.get_function(name).is_some() {
+                error(
+                    start.line,
+                    start.column,
+                    format!("Function '{}' is already defined", name),
+                );
+                abort()
+            }
+
+            let function = Function {
+                name: name.to_owned(),
+                builtin: false,
+                params: Some(
+                    params
+                        .iter()
+                        .flatten()
+                        .map(|p| self.resolve_type(&p.r#type))
+                        .collect(),
+                ),
+                return_type: self.resolve_type(r#type),
+            };
+            self.functions.push(function);
+        }
+
+        String::new()
+    }
+
+    /// declares a variable defined outside of functions and returns its C definition,
+    /// constants have none since their value is used directly
+    fn transpile_global(&mut self, stmt: &Statement) -> String {
+        let Statement::VariableDeclaration {
+            start,
+            name: Some(name),
+            kind,
+            r#type: Some(r#type),
+            value,
+            doc,
+        } = stmt
+        else {
+            return String::new();
+        };
+        self.position = start.to_owned();
+        let r#type = &self.resolve_type(r#type);
+        let qualified = self.qualify(name);
+
+        if self.get_global(&qualified).is_some() || self.get_function(&qualified).is_some() {
+            self.error_expr(format!("'{}' is already defined", name).as_str());
+            abort()
+        }
+        if self
+            .module_name(&Expression::Identifier(name.to_owned()))
+            .is_some()
+        {
+            self.error_expr(
+                format!(
+                    "Variable '{}' has the same name as an imported module",
+                    name
+                )
+                .as_str(),
+            );
+            abort()
+        }
+
+        let primitive = matches!(
+            r#type,
+            VariableTypes::Int
+                | VariableTypes::Flo
+                | VariableTypes::Boo
+                | VariableTypes::Str
+                | VariableTypes::Chr
+                | VariableTypes::Nul
+        );
+        let constant = match value {
+            Some(v) => primitive && self.is_constant(v),
+            None => !matches!(r#type, VariableTypes::Arr(_) | VariableTypes::Obj(_)),
+        };
+        let mut c_value = self.eval_initial_value(name, r#type, value);
+        let folded = match value {
+            Some(v) if constant => self.fold_int(v),
+            _ => None,
+        };
+        if let (Some(number), VariableTypes::Int) = (folded, r#type) {
+            c_value = c_int(number);
+            if matches!(kind, VarDeclarationKind::Immutable) {
+                self.int_constants.push((qualified.to_owned(), number));
+            }
+        }
+
+        let mut global = Variable {
+            name: qualified.to_owned(),
+            var_type: r#type.to_owned(),
+            kind: kind.to_owned(),
+            c_name: c_name(&qualified),
+            narrowed: false,
+            captured: false,
+        };
+
+        if constant && matches!(kind, VarDeclarationKind::Immutable) {
+            global.c_name = c_value;
+            self.globals.push(global);
+            self.constants.push(qualified);
+            return String::new();
+        }
+
+        let c_declaration = self.c_declaration(r#type, &global.c_name);
+        let c_global = match constant {
+            true => format!("static {} = {};\n", c_declaration, c_value),
+            false => {
+                self.initializers
+                    .push_str(&format!("    {} = {};\n", global.c_name, c_value));
+                format!("static {};\n", c_declaration)
+            }
         };
+        let c_global = format!("{}{}", c_doc(doc, ""), c_global);
+        self.globals.push(global);
 
-        c_stmt
+        c_global
     }
 
-    fn eval_expr(&self, expr: &Expression) -> Expr {
+    /// if the C compiler can compute the value, so it can be used where C needs a constant
+    fn is_constant(&mut self, expr: &Expression) -> bool {
         match expr {
-            Expression::Literal { r#type, value } => match r#type {
-                LiteralTypes::String => Expr {
-                    value: format!("\"{}\"", value),
-                    literal_type: VariableTypes::Str,
-                },
-                LiteralTypes::Null => Expr {
-                    value: value.to_string(),
-                    literal_type: VariableTypes::Nul,
-                },
-                LiteralTypes::Numeric => {
-                    if value.contains('.') {
-                        Expr {
-                            value: value.to_string(),
-                            literal_type: VariableTypes::Flo,
-                        }
-                    } else {
-                        Expr {
-                            value: value.to_string(),
-                            literal_type: VariableTypes::Int,
-                        }
-                    }
-                }
-                LiteralTypes::Boolean => Expr {
-                    value: value.to_string(),
-                    literal_type: VariableTypes::Boo,
-                },
+            Expression::Literal { .. } => true,
+            Expression::Identifier(name) => self
+                .get_variable(name)
+                .is_some_and(|v| self.constants.contains(&v.name)),
+            Expression::FieldAccess { object, field } => match self.module_name(object) {
+                Some(module) => self.constants.contains(&format!("{}.{}", module, field)),
+                None => false,
             },
-            Expression::ArrayLiteral { elements } => {
-                unimplemented!()
-                // match elements {
-                //     Some(e) => {
-                //         &*e
-                //     }
-                //     None => {
-                //         //
-                //     }
-                // }
-            }
-            Expression::Identifier(name) => {
-                if !&self.var_names.contains(name) {
-                    self.error_expr(
-                        format!("Variable '{}' being used before assigned", name).as_str(),
-                    );
-                    exit(1)
-                } else {
-                    return Expr {
-                        value: name.to_string(),
-                        literal_type: self.get_var_type(&name),
-                    };
-                }
+            Expression::Unary { operand, .. } => self.is_constant(operand),
+            Expression::Ternary {
+                condition,
+                when_true,
+                when_false,
+            } => {
+                self.is_constant(condition)
+                    && self.is_constant(when_true)
+                    && self.is_constant(when_false)
             }
-            Expression::Unary { operator, operand } => match operator {
-                TokenTypes::LogicalNot => {
-                    let right = self.eval_expr(operand);
-                    if right.literal_type.eq(&VariableTypes::Boo) {
-                        return Expr {
-                            value: format!("!{}", right.value),
-                            literal_type: right.literal_type,
-                        };
-                    } else {
-                        self.error_expr("Cannot use '!' on non boolean values");
-                        exit(1)
-                    }
-                }
-                TokenTypes::BinaryMinus => {
-                    let right = self.eval_expr(operand);
-                    if right.literal_type.eq(&VariableTypes::Int)
-                        || right.literal_type.eq(&VariableTypes::Flo)
-                    {
-                        return Expr {
-                            value: format!("-{}", right.value),
-                            literal_type: right.literal_type,
-                        };
-                    } else {
-                        self.error_expr("Cannot use '-' on non-numeric value");
-                        exit(1)
-                    }
-                }
-                _ => {
-                    eprintln!("Unknown error evaluating unary expression");
-                    exit(1)
-                }
-            },
-            Expression::Logical {
+            // strings are concatenated and compared by the runtime, and so is '%' on f64s
+            Expression::Binary {
                 operator,
                 left,
                 right,
             } => {
-                let e_left = self.eval_expr(left);
-                let e_right = self.eval_expr(right);
-
-                match operator {
-                    TokenTypes::LogicalEquals => match e_left.literal_type {
-                        VariableTypes::Str => match e_right.literal_type {
-                            VariableTypes::Str => {
-                                return Expr {
-                                    value: format!(
-                                        "(compare({}, {}) == 0))",
-                                        e_left.value, e_right.value
-                                    ),
-                                    literal_type: VariableTypes::Boo,
-                                };
-                            }
-                            _ => {
-                                return Expr {
-                                    value: String::from("false"),
-                                    literal_type: VariableTypes::Boo,
-                                };
-                            }
-                        },
-                        VariableTypes::Int | VariableTypes::Flo => match e_right.literal_type {
-                            VariableTypes::Int | VariableTypes::Flo => {
-                                return Expr {
-                                    value: format!("({} == {})", e_left.value, e_right.value),
-                                    literal_type: VariableTypes::Boo,
-                                };
-                            }
-                            _ => {
-                                return Expr {
-                                    value: String::from("false"),
-                                    literal_type: VariableTypes::Boo,
-                                };
-                            }
-                        },
-                        VariableTypes::Nul => match e_right.literal_type {
-                            VariableTypes::Nul => {
-                                return Expr {
-                                    value: String::from("true"),
-                                    literal_type: VariableTypes::Boo,
-                                };
-                            }
-                            _ => {
-                                return Expr {
-                                    value: String::from("false"),
-                                    literal_type: VariableTypes::Boo,
-                                };
-                            }
-                        },
-                        VariableTypes::Boo => match e_right.literal_type {
-                            VariableTypes::Boo => {
-                                return Expr {
-                                    value: format!("({} == {})", e_left.value, e_right.value),
-                                    literal_type: VariableTypes::Boo,
-                                };
-                            }
-                            _ => {
-                                return Expr {
-                                    value: String::from("false"),
-                                    literal_type: VariableTypes::Boo,
-                                };
-                            }
-                        },
-                        _ => {
-                            self.error_expr(
-                                format!(
-                                    "Cannot compare '{}' with '{}'",
-                                    e_left.value, e_right.value,
-                                )
-                                .as_str(),
-                            );
-                            exit(1)
-                        }
-                    },
-                    TokenTypes::LogicalDifferent => match e_left.literal_type {
-                        VariableTypes::Str => match e_right.literal_type {
-                            VariableTypes::Str => {
-                                return Expr {
-                                    value: format!(
-                                        "(compare({}, {}) != 0))",
-                                        e_left.value, e_right.value
-                                    ),
-                                    literal_type: VariableTypes::Boo,
-                                };
-                            }
-                            _ => {
-                                return Expr {
-                                    value: String::from("true"),
-                                    literal_type: VariableTypes::Boo,
-                                };
-                            }
-                        },
-                        VariableTypes::Int | VariableTypes::Flo => match e_right.literal_type {
-                            VariableTypes::Int | VariableTypes::Flo => {
-                                return Expr {
-                                    value: format!("({} != {})", e_left.value, e_right.value),
-                                    literal_type: VariableTypes::Boo,
-                                };
-                            }
-                            _ => {
-                                return Expr {
-                                    value: String::from("true"),
-                                    literal_type: VariableTypes::Boo,
-                                };
-                            }
-                        },
-                        VariableTypes::Nul => match e_right.literal_type {
-                            VariableTypes::Nul => {
-                                return Expr {
-                                    value: String::from("false"),
-                                    literal_type: VariableTypes::Boo,
-                                };
-                            }
-                            _ => {
-                                return Expr {
-                                    value: String::from("true"),
-                                    literal_type: VariableTypes::Boo,
-                                };
-                            }
-                        },
-                        VariableTypes::Boo => match e_right.literal_type {
-                            VariableTypes::Boo => {
-                                return Expr {
-                                    value: format!("({} != {})", e_left.value, e_right.value),
-                                    literal_type: VariableTypes::Boo,
-                                };
-                            }
-                            _ => {
-                                return Expr {
-                                    value: String::from("true"),
-                                    literal_type: VariableTypes::Boo,
-                                };
-                            }
-                        },
-                        _ => {
-                            self.error_expr(
-                                format!(
-                                    "Cannot compare '{}' with '{}'",
-                                    e_left.value, e_right.value,
-                                )
-                                .as_str(),
-                            );
-                            exit(1)
-                        }
-                    },
-                    TokenTypes::LogicalSmallerThan
-                    | TokenTypes::LogicalSmallerOrEqualsThan
-                    | TokenTypes::LogicalGreaterThan
-                    | TokenTypes::LogicalGreaterOrEqualsThan => {
-                        // let op: TokenTypes = match operator {
-                        //     TokenTypes::LogicalSmallerThan => TokenTypes::LogicalSmallerThan,
-                        //     TokenTypes::LogicalSmallerOrEqualsThan => {
-                        //         TokenTypes::LogicalSmallerOrEqualsThan
-                        //     }
-                        //     TokenTypes::LogicalGreaterThan => TokenTypes::LogicalGreaterThan,
-                        //     TokenTypes::LogicalGreaterOrEqualsThan => {
-                        //         TokenTypes::LogicalGreaterOrEqualsThan
-                        //     }
-                        //     _ => {
-                        //         eprintln!("Unknown error related to logical expressions");
-                        //         exit(1)
-                        //     }
-                        // };
-
-                        match e_left.literal_type {
-                            VariableTypes::Str => match e_right.literal_type {
-                                VariableTypes::Str => {
-                                    eprintln!(
-                                        "Cannot compare strings with '{}', did you mean '=='?",
-                                        operator
-                                    );
-                                    exit(1)
-                                }
-                                VariableTypes::Int | VariableTypes::Flo => {
-                                    eprintln!(
-                                        "Cannot compare '{}' with '{}', did you mean 'getLen({}) {} {}'?",
-                                        e_left.value,
-                                        e_right.value,
-                                        operator,
-                                        e_left.value,
-                                        e_right.value
-                                    );
-                                    exit(1)
-                                }
-                                _ => {
-                                    eprintln!(
-                                        "Cannot compare '{}' with '{}'",
-                                        e_left.value, e_right.value
-                                    );
-                                    exit(1)
-                                }
-                            },
-                            VariableTypes::Int => match e_right.literal_type {
-                                VariableTypes::Int => Expr {
-                                    value: format!(
-                                        "({} {} {})",
-                                        e_left.value, operator, e_right.value
-                                    ),
-                                    literal_type: VariableTypes::Int,
-                                },
-                                VariableTypes::Flo => Expr {
-                                    value: format!(
-                                        "({} {} {})",
-                                        e_left.value, operator, e_right.value
-                                    ),
-                                    literal_type: VariableTypes::Flo,
-                                },
-                                VariableTypes::Str => {
-                                    eprintln!(
-                                        "Cannot compare '{}' with '{}', did you mean '{} {} getLen({})'?",
-                                        e_left.value,
-                                        e_right.value,
-                                        operator,
-                                        e_left.value,
-                                        e_right.value
-                                    );
-                                    exit(1)
-                                }
-                                _ => {
-                                    eprintln!(
-                                        "Cannot compare '{}' with '{}'",
-                                        e_left.value, e_right.value
-                                    );
-                                    exit(1)
-                                }
-                            },
-                            _ => {
-                                eprintln!(
-                                    "Cannot compare '{}' with '{}'",
-                                    e_left.value, e_right.value
-                                );
-                                exit(1)
-                            }
+                self.is_constant(left)
+                    && self.is_constant(right)
+                    && match (
+                        self.eval_expr(left).literal_type,
+                        self.eval_expr(right).literal_type,
+                    ) {
+                        (VariableTypes::Int, VariableTypes::Int) => true,
+                        (l, r) => {
+                            is_numeric(&l)
+                                && is_numeric(&r)
+                                && !operator.eq(&TokenTypes::BinaryRest)
                         }
                     }
-                    _ => {
-                        eprintln!("Unknown error related to logical expressions");
-                        exit(1)
-                    }
+            }
+            Expression::Logical { left, right, .. } => {
+                self.is_constant(left)
+                    && self.is_constant(right)
+                    && !self.eval_expr(left).literal_type.eq(&VariableTypes::Str)
+            }
+            Expression::Cast { expression, r#type } => {
+                self.is_constant(expression)
+                    && matches!(
+                        (
+                            self.eval_expr(expression).literal_type,
+                            self.resolve_type(r#type)
+                        ),
+                        (
+                            VariableTypes::Int,
+                            VariableTypes::Int | VariableTypes::Flo | VariableTypes::Boo
+                        ) | (VariableTypes::Flo, VariableTypes::Flo | VariableTypes::Boo)
+                            | (
+                                VariableTypes::Boo,
+                                VariableTypes::Int | VariableTypes::Flo | VariableTypes::Boo
+                            )
+                            | (VariableTypes::Str, VariableTypes::Str)
+                            | (VariableTypes::Chr, VariableTypes::Chr | VariableTypes::Int)
+                    )
+            }
+            _ => false,
+        }
+    }
+
+    /// the value of a constant i32, computed here since C leaves overflows and divisions by
+    /// zero undefined, None when some of it is not an i32 the compiler can compute
+    fn fold_int(&mut self, expr: &Expression) -> Option<i32> {
+        let value = match expr {
+            Expression::Literal {
+                r#type: LiteralTypes::Numeric,
+                value,
+            } => parse_int_literal(&value.replace('_', ""))?,
+            Expression::Identifier(name) => {
+                let variable = self.get_variable(name)?.name.to_owned();
+                self.int_constant(&variable)?
+            }
+            Expression::FieldAccess { object, field } => {
+                let module = self.module_name(object)?;
+                self.int_constant(&format!("{}.{}", module, field))?
+            }
+            Expression::Unary { operator, operand } => {
+                if let (TokenTypes::BinaryMinus, Some(number)) =
+                    (operator, negative_int_literal(operand))
+                {
+                    return Some(number);
+                }
+                let operand = self.fold_int(operand)?;
+                match operator {
+                    TokenTypes::BinaryMinus => self.checked(operand.checked_neg()),
+                    TokenTypes::BitwiseNot => !operand,
+                    _ => return None,
                 }
             }
             Expression::Binary {
@@ -442,303 +520,3173 @@ impl Transpiler {
                 left,
                 right,
             } => {
-                let e_right = self.eval_expr(right);
-                let e_left = self.eval_expr(left);
-
+                // both sides are checked even when the other one is not an i32
+                let (left, right) = (self.fold_int(left), self.fold_int(right));
+                let (left, right) = (left?, right?);
+                if right == 0
+                    && matches!(
+                        operator,
+                        TokenTypes::BinaryDivision | TokenTypes::BinaryRest
+                    )
+                {
+                    self.error_expr("Division by zero in a constant");
+                    abort()
+                }
                 match operator {
-                    TokenTypes::BinaryPlus => match e_left.literal_type {
-                        VariableTypes::Str => match e_right.literal_type {
-                            VariableTypes::Str => {
-                                return Expr {
-                                    value: format!("(concat({}, {}))", e_left.value, e_right.value),
-                                    literal_type: VariableTypes::Str,
-                                };
-                            }
-                            VariableTypes::Int | VariableTypes::Flo => {
-                                return Expr {
-                                    value: format!(
-                                        "(concat({}, \"{}\"))",
-                                        e_left.value, e_right.value
-                                    ),
-                                    literal_type: VariableTypes::Str,
-                                };
-                            }
-                            _ => {
-                                self.error_expr(
-                                    format!(
-                                        "Cannot concatenate '{}' with '{}'",
-                                        e_left.value, e_right.value,
-                                    )
-                                    .as_str(),
-                                );
-                                exit(1)
-                            }
-                        },
-                        VariableTypes::Int => match e_right.literal_type {
-                            VariableTypes::Int => {
-                                return Expr {
-                                    value: format!(
-                                        "({} {} {})",
-                                        e_left.value, operator, e_right.value
-                                    ),
-                                    literal_type: VariableTypes::Int,
-                                };
-                            }
-                            VariableTypes::Flo => {
-                                return Expr {
-                                    value: format!(
-                                        "({} {} {})",
-                                        e_left.value, operator, e_right.value
-                                    ),
-                                    literal_type: VariableTypes::Flo,
-                                };
-                            }
-                            VariableTypes::Str => {
-                                return Expr {
-                                    value: format!(
-                                        "(concat(\"{}\", {}))",
-                                        e_left.value, e_right.value
-                                    ),
-                                    literal_type: VariableTypes::Str,
-                                };
-                            }
-                            _ => {
-                                self.error_expr(
-                                    format!(
-                                        "Cannot add '{}' with '{}'",
-                                        e_left.value, e_right.value
-                                    )
-                                    .as_str(),
-                                );
-                                exit(1)
-                            }
-                        },
-                        VariableTypes::Flo => match e_right.literal_type {
-                            VariableTypes::Int | VariableTypes::Flo => {
-                                return Expr {
-                                    value: format!(
-                                        "({} {} {})",
-                                        e_left.value, operator, e_right.value
-                                    ),
-                                    literal_type: VariableTypes::Flo,
-                                };
-                            }
-                            VariableTypes::Str => {
-                                return Expr {
-                                    value: format!(
-                                        "(concat(\"{}\", {}))",
-                                        e_left.value, e_right.value
-                                    ),
-                                    literal_type: VariableTypes::Str,
-                                };
-                            }
-                            _ => {
-                                self.error_expr(
-                                    format!(
-                                        "Cannot add '{}' with '{}'",
-                                        e_left.value, e_right.value,
-                                    )
-                                    .as_str(),
-                                );
-                                exit(1)
-                            }
-                        },
-                        _ => {
-                            self.error_expr(
-                                format!("Cannot add '{}' with '{}'", e_left.value, e_right.value,)
-                                    .as_str(),
-                            );
-                            exit(1)
-                        }
-                    },
-                    TokenTypes::BinaryMinus
-                    | TokenTypes::BinaryDivision
-                    | TokenTypes::BinaryMultiply
-                    | TokenTypes::BinaryRest => match e_left.literal_type {
-                        VariableTypes::Int => match e_right.literal_type {
-                            VariableTypes::Int => {
-                                return Expr {
-                                    value: format!(
-                                        "({} {} {})",
-                                        e_left.value, operator, e_right.value
-                                    ),
-                                    literal_type: VariableTypes::Int,
-                                };
-                            }
-                            VariableTypes::Flo => {
-                                return Expr {
-                                    value: format!(
-                                        "({} {} {})",
-                                        e_left.value, operator, e_right.value
-                                    ),
-                                    literal_type: VariableTypes::Flo,
-                                };
-                            }
-                            _ => match operator {
-                                TokenTypes::BinaryMinus => {
-                                    self.error_expr(
-                                        format!(
-                                            "Cannot subtract '{}' with '{}'",
-                                            e_left.value, e_right.value,
-                                        )
-                                        .as_str(),
-                                    );
-                                    exit(1)
-                                }
-                                TokenTypes::BinaryMultiply => {
-                                    self.error_expr(
-                                        format!(
-                                            "Cannot multiply '{}' with '{}'",
-                                            e_left.value, e_right.value,
-                                        )
-                                        .as_str(),
-                                    );
-                                    exit(1)
-                                }
-                                TokenTypes::BinaryDivision => {
-                                    self.error_expr(
-                                        format!(
-                                            "Cannot divide '{}' with '{}'",
-                                            e_left.value, e_right.value,
-                                        )
-                                        .as_str(),
-                                    );
-                                    exit(1)
-                                }
-                                TokenTypes::BinaryRest => {
-                                    self.error_expr(
-                                        format!(
-                                            "Cannot take modulo of '{}' with '{}'",
-                                            e_left.value, e_right.value,
-                                        )
-                                        .as_str(),
-                                    );
-                                    exit(1)
-                                }
-                                _ => {
-                                    eprintln!(
-                                        "Unknown error related with numeric binary operations"
-                                    );
-                                    exit(1)
-                                }
-                            },
-                        },
-                        VariableTypes::Flo => match e_right.literal_type {
-                            VariableTypes::Int | VariableTypes::Flo => {
-                                return Expr {
-                                    value: format!(
-                                        "({} {} {})",
-                                        e_left.value, operator, e_right.value
-                                    ),
-                                    literal_type: VariableTypes::Flo,
-                                };
-                            }
-                            _ => match operator {
-                                TokenTypes::BinaryMinus => {
-                                    self.error_expr(
-                                        format!(
-                                            "Cannot subtract '{}' with '{}'",
-                                            e_left.value, e_right.value,
-                                        )
-                                        .as_str(),
-                                    );
-                                    exit(1)
-                                }
-                                TokenTypes::BinaryMultiply => {
-                                    self.error_expr(
-                                        format!(
-                                            "Cannot multiply '{}' with '{}'",
-                                            e_left.value, e_right.value,
-                                        )
-                                        .as_str(),
-                                    );
-                                    exit(1)
-                                }
-                                TokenTypes::BinaryDivision => {
-                                    self.error_expr(
-                                        format!(
-                                            "Cannot divide '{}' with '{}'",
-                                            e_left.value, e_right.value,
-                                        )
-                                        .as_str(),
-                                    );
-                                    exit(1)
-                                }
-                                TokenTypes::BinaryRest => {
-                                    self.error_expr(
-                                        format!(
-                                            "Cannot take modulo of '{}' with '{}'",
-                                            e_left.value, e_right.value,
-                                        )
-                                        .as_str(),
-                                    );
-                                    exit(1)
-                                }
-                                _ => {
-                                    eprintln!(
-                                        "Unknown error related with numeric binary operations"
-                                    );
-                                    exit(1)
-                                }
-                            },
-                        },
-                        _ => {
-                            self.error_expr(
-                                format!(
-                                    "Cannot perform this operation '{} {} {}'",
-                                    e_left.value, operator, e_right.value,
-                                )
-                                .as_str(),
-                            );
-                            exit(1)
-                        }
-                    },
-                    _ => {
-                        self.error_expr("Unknown error related to binary operations");
-                        exit(1)
-                    }
+                    TokenTypes::BinaryPlus => self.checked(left.checked_add(right)),
+                    TokenTypes::BinaryMinus => self.checked(left.checked_sub(right)),
+                    TokenTypes::BinaryMultiply => self.checked(left.checked_mul(right)),
+                    TokenTypes::BinaryDivision => self.checked(left.checked_div(right)),
+                    TokenTypes::BinaryRest => self.checked(left.checked_rem(right)),
+                    TokenTypes::BitwiseAnd => left & right,
+                    TokenTypes::BitwiseOr => left | right,
+                    TokenTypes::BitwiseXor => left ^ right,
+                    // like the C code, the amount is taken modulo 32 and '<<' wraps
+                    TokenTypes::BitwiseShiftLeft => ((left as u32) << (right & 31)) as i32,
+                    TokenTypes::BitwiseShiftRight => left >> (right & 31),
+                    _ => return None,
                 }
             }
-            _ => {
-                self.error_expr("Unknown error related to expression evaluation");
-                exit(1)
+            Expression::Ternary {
+                condition,
+                when_true,
+                when_false,
+            } => {
+                for expr in [condition, when_true, when_false] {
+                    self.fold_int(expr);
+                }
+                return None;
             }
-        }
+            Expression::Logical { left, right, .. } => {
+                self.fold_int(left);
+                self.fold_int(right);
+                return None;
+            }
+            Expression::Cast { expression, .. } => {
+                self.fold_int(expression);
+                return None;
+            }
+            _ => return None,
+        };
+        Some(value)
     }
 
-    fn error_expr(&self, message: &str) {
-        eprintln!("\n| Error: {}", message);
+    fn checked(&self, value: Option<i32>) -> i32 {
+        let Some(value) = value else {
+            self.error_expr("Constant does not fit in 'i32'");
+            abort()
+        };
+        value
     }
 
-    fn get_var_type(&self, name: &str) -> VariableTypes {
-        for i in &self.variables {
-            if &i.name == &name {
-                return i.var_type.to_owned();
-            }
-        }
-        eprintln!(
-            "{}",
-            format!("Variable '{}' being used before assigned", name)
-        );
-        exit(1)
+    fn int_constant(&self, name: &str) -> Option<i32> {
+        self.int_constants
+            .iter()
+            .find(|(constant, _)| constant == name)
+            .map(|(_, value)| *value)
     }
 
-    fn get_c_type(&self, bline_type: &VariableTypes) -> String {
-        match bline_type {
-            VariableTypes::Int => String::from("int"),
-            _ => String::from("void"),
+    fn transpile_prototype(&mut self, stmt: &Statement) -> String {
+        match stmt {
+            Statement::FunctionDeclaration {
+                name,
+                r#type,
+                params,
+                ..
+            } => {
+                let name = self.qualify(name);
+                format!("{};\n", self.c_signature(&name, r#type, params))
+            }
+            _ => String::new(),
         }
     }
 
-    fn get_body(&self) -> &Vec<Statement> {
-        match &self.ast {
-            Statement::Program { body, .. } => {
-                //
-                &body
+    fn transpile_stmt(&mut self, stmt: &Statement) -> String {
+        let indent = self.indent();
+        self.position = stmt.start().to_owned();
+
+        match stmt {
+            Statement::FunctionDeclaration {
+                name,
+                r#type,
+                params,
+                body,
+                doc,
+                ..
+            } => {
+                let name = self.qualify(name);
+                self.return_type = self.resolve_type(r#type);
+
+                self.enter_scope();
+                for param in params.iter().flatten() {
+                    let r#type = self.resolve_type(&param.r#type);
+                    self.declare_variable(&param.name, &r#type, &VarDeclarationKind::Immutable);
+                }
+                let mut c_body = self.transpile_block(body, &[]);
+                self.exit_scope();
+
+                if name.eq("main") && !self.initializers.is_empty() {
+                    c_body.insert_str(0, "    __init_globals();\n");
+                }
+
+                format!(
+                    "{}{} {{\n{}}}\n",
+                    c_doc(doc, ""),
+                    self.c_signature(&name, r#type, params),
+                    c_body
+                )
             }
-            _ => exit(1),
-        }
+            Statement::VariableDeclaration { doc, .. } => {
+                format!(
+                    "{}{}{};\n",
+                    c_doc(doc, &indent),
+                    indent,
+                    self.transpile_var_declaration(stmt)
+                )
+            }
+            Statement::StructDeclaration { name, .. } | Statement::EnumDeclaration { name, .. } => {
+                // the C struct goes with the other type definitions
+                let name = self.qualify(name);
+                self.get_c_type(&VariableTypes::Obj(name));
+                String::new()
+            }
+            Statement::VariableAlteration { .. } => {
+                format!("{}{};\n", indent, self.transpile_var_alteration(stmt))
+            }
+            Statement::PlaceAlteration {
+                target,
+                operator,
+                value,
+                ..
+            } => {
+                format!(
+                    "{}{};\n",
+                    indent,
+                    self.transpile_place_alteration(target, operator, value)
+                )
+            }
+            Statement::FunctionCall { call, .. } => {
+                format!("{}{};\n", indent, self.eval_expr(call).value)
+            }
+            Statement::If {
+                condition,
+                block,
+                alternate,
+                ..
+            } => {
+                let c_condition = self.eval_condition(condition);
+                let (when_true, when_false) = self.narrowings(condition);
+                let c_block = self.transpile_block(block, &when_true);
+                let c_alternate = self.transpile_alternate(alternate, &when_false);
+
+                format!(
+                    "{}if ({}) {{\n{}{}}}{}\n",
+                    indent, c_condition, c_block, indent, c_alternate
+                )
+            }
+            Statement::While { test, block, .. } => {
+                let c_test = self.eval_condition(test);
+                let (when_true, _) = self.narrowings(test);
+                self.enter_loop();
+                let c_block = self.transpile_block(block, &when_true);
+                let c_label = self.exit_loop();
+
+                format!(
+                    "{}while ({}) {{\n{}{}}}\n{}",
+                    indent, c_test, c_block, indent, c_label
+                )
+            }
+            Statement::For {
+                variable,
+                test,
+                variable_update,
+                block,
+                ..
+            } => {
+                // the loop variable only lives as long as the loop
+                self.enter_scope();
+
+                let c_variable = match variable {
+                    Some(v) => self.transpile_var_declaration(v),
+                    None => String::new(),
+                };
+                let (c_test, when_true) = match test {
+                    Some(t) => (self.eval_condition(t), self.narrowings(t).0),
+                    None => (String::new(), Vec::new()),
+                };
+                let c_update = match variable_update {
+                    Some(u) => self.transpile_var_alteration(u),
+                    None => String::new(),
+                };
+                self.enter_loop();
+                let c_block = self.transpile_block(block, &when_true);
+                let c_label = self.exit_loop();
+
+                self.exit_scope();
+
+                format!(
+                    "{}for ({}; {}; {}) {{\n{}{}}}\n{}",
+                    indent, c_variable, c_test, c_update, c_block, indent, c_label
+                )
+            }
+            Statement::ForIn {
+                name,
+                values,
+                block,
+                ..
+            } => self.transpile_for_in(name, values, block),
+            Statement::Switch { subject, cases, .. } => self.transpile_switch(subject, cases),
+            Statement::Break { .. } => format!("{}{};\n", indent, self.transpile_break()),
+            Statement::Continue { .. } => format!("{}continue;\n", indent),
+            Statement::Return { expression, .. } => {
+                format!("{}{};\n", indent, self.transpile_return(expression))
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// both forms become an indexed C loop, the variable is set from the index at the start of the block
+    fn transpile_for_in(
+        &mut self,
+        name: &str,
+        values: &Expression,
+        block: &Option<Vec<Statement>>,
+    ) -> String {
+        let indent = self.indent();
+        self.generated += 1;
+        let index = format!("__i{}", self.generated);
+
+        if let Expression::Range {
+            from,
+            to,
+            inclusive,
+        } = values
+        {
+            let end = format!("__end{}", self.generated);
+            let from = self.eval_expr(from);
+            let to = self.eval_expr(to);
+            for bound in [&from, &to] {
+                if !bound.literal_type.eq(&VariableTypes::Int) {
+                    self.error_expr(
+                        format!("Range bounds must be 'i32', found '{}'", bound.literal_type)
+                            .as_str(),
+                    );
+                    abort()
+                }
+            }
+
+            let binding = (name.to_owned(), VariableTypes::Int, index.to_owned());
+            self.enter_loop();
+            let c_block = self.transpile_block_with(block, &[], &[binding]);
+            let c_label = self.exit_loop();
+
+            // an inclusive range up to the largest i32 would overflow an int index
+            let (c_type, comparison) = match inclusive {
+                true => ("long long", "<="),
+                false => ("int", "<"),
+            };
+            return format!(
+                "{}for ({} {} = {}, {} = {}; {} {} {}; {}++) {{\n{}{}}}\n{}",
+                indent,
+                c_type,
+                index,
+                from.value,
+                end,
+                to.value,
+                index,
+                comparison,
+                end,
+                index,
+                c_block,
+                indent,
+                c_label
+            );
+        }
+
+        let vec = self.eval_expr(values);
+        let element_type = match &vec.literal_type {
+            VariableTypes::Arr(t) => *t.to_owned(),
+            t => {
+                self.error_expr(format!("Cannot iterate over '{}'", t).as_str());
+                abort()
+            }
+        };
+        let c_type = self.get_c_type(&element_type);
+
+        // the vec is only evaluated once, a variable can be used directly
+        let is_variable = matches!(values, Expression::Identifier(_));
+        let c_vec = match is_variable {
+            true => vec.value.to_owned(),
+            false => format!("__vec{}", self.generated),
+        };
+
+        // the length is read every time so the block can change the vec
+        let binding = (
+            name.to_owned(),
+            element_type,
+            format!("(({}){}->data)[{}]", c_pointer(&c_type), c_vec, index),
+        );
+        if !is_variable {
+            self.indentation += 1;
+        }
+        let loop_indent = self.indent();
+        self.enter_loop();
+        let c_block = self.transpile_block_with(block, &[], &[binding]);
+        let c_label = self.exit_loop();
+        let c_loop = format!(
+            "{}for (int {} = 0; {} < {}->len; {}++) {{\n{}{}}}\n{}",
+            loop_indent, index, index, c_vec, index, c_block, loop_indent, c_label
+        );
+
+        if is_variable {
+            return c_loop;
+        }
+        self.indentation -= 1;
+        format!(
+            "{}{{\n{}vec *{} = {};\n{}{}}}\n",
+            indent, loop_indent, c_vec, vec.value, c_loop, indent
+        )
+    }
+
+    /// transpiles the elseif/else chain that follows an if statement,
+    /// narrowed are the variables the previous conditions proved to not be null
+    fn transpile_alternate(
+        &mut self,
+        alternate: &Option<Box<Statement>>,
+        narrowed: &[String],
+    ) -> String {
+        let indent = self.indent();
+
+        match alternate.as_deref() {
+            Some(
+                elseif @ Statement::ElseIf {
+                    condition,
+                    block,
+                    alternate,
+                    ..
+                },
+            ) => {
+                self.position = elseif.start().to_owned();
+
+                self.enter_scope();
+                self.narrow(narrowed);
+                let c_condition = self.eval_condition(condition);
+                let (when_true, when_false) = self.narrowings(condition);
+                self.exit_scope();
+
+                let c_block = self.transpile_block(block, &[narrowed, &when_true].concat());
+                let c_alternate =
+                    self.transpile_alternate(alternate, &[narrowed, &when_false].concat());
+
+                format!(
+                    " else if ({}) {{\n{}{}}}{}",
+                    c_condition, c_block, indent, c_alternate
+                )
+            }
+            Some(Statement::Else { block, .. }) => {
+                let c_block = self.transpile_block(block, narrowed);
+
+                format!(" else {{\n{}{}}}", c_block, indent)
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// narrowed are optional variables known to not be null when the block starts
+    fn transpile_block(&mut self, block: &Option<Vec<Statement>>, narrowed: &[String]) -> String {
+        self.transpile_block_with(block, narrowed, &[])
+    }
+
+    /// bindings are (name, type, C value) of immutable variables declared at the start of the block
+    fn transpile_block_with(
+        &mut self,
+        block: &Option<Vec<Statement>>,
+        narrowed: &[String],
+        bindings: &[(String, VariableTypes, String)],
+    ) -> String {
+        self.enter_scope();
+        self.indentation += 1;
+
+        self.narrow(narrowed);
+        let mut c_block = String::new();
+
+        for (name, r#type, value) in bindings {
+            self.declare_variable(name, r#type, &VarDeclarationKind::Immutable);
+            c_block.push_str(&format!(
+                "{}{} = {};\n",
+                self.indent(),
+                self.c_declaration(r#type, &c_name(name)),
+                value
+            ));
+        }
+
+        for stmt in block.iter().flatten() {
+            // once a variable may be set to null again it stops being narrowed,
+            // loops may run it again after the assignment so they widen before
+            let mut assigned: Vec<String> = self
+                .variables
+                .iter()
+                .filter(|v| v.narrowed && assigns(stmt, &v.name))
+                .map(|v| v.name.to_owned())
+                .collect();
+            assigned.dedup();
+            let is_loop = matches!(
+                stmt,
+                Statement::While { .. } | Statement::For { .. } | Statement::ForIn { .. }
+            );
+
+            if is_loop {
+                self.widen(&assigned);
+            }
+            c_block.push_str(&self.transpile_stmt(stmt));
+            if !is_loop {
+                self.widen(&assigned);
+            }
+
+            // 'if x == null { ret; };' proves x is not null for the rest of the block
+            if let Statement::If {
+                condition,
+                block: Some(if_block),
+                alternate: None,
+                ..
+            } = stmt
+            {
+                if if_block.last().is_some_and(is_exit) {
+                    let (_, when_false) = self.narrowings(condition);
+                    self.narrow(&when_false);
+                }
+            }
+        }
+
+        self.indentation -= 1;
+        self.exit_scope();
+
+        c_block
+    }
+
+    fn transpile_switch(&mut self, subject: &Expression, cases: &[SwitchCase]) -> String {
+        let indent = self.indent();
+        let start = self.position.to_owned();
+        let expr = self.eval_expr(subject);
+
+        // the subject is read once per case, so anything but a variable is stored first
+        let mut c_switch = String::new();
+        let value = match subject {
+            Expression::Identifier(_) => expr.value.to_owned(),
+            _ if expr.literal_type.eq(&VariableTypes::Int) => expr.value.to_owned(),
+            _ => {
+                self.generated += 1;
+                let name = format!("__switch{}", self.generated);
+                c_switch.push_str(&format!(
+                    "{}{} = {};\n",
+                    indent,
+                    self.c_declaration(&expr.literal_type, &name),
+                    expr.value
+                ));
+                name
+            }
+        };
+
+        let c_cases = match &expr.literal_type {
+            VariableTypes::Obj(name) if self.get_enum(name).is_some() => {
+                self.transpile_enum_cases(&value, name, cases)
+            }
+            VariableTypes::Int => self.transpile_int_cases(&value, cases),
+            VariableTypes::Str => self.transpile_str_cases(&value, cases),
+            t => {
+                self.error_expr(
+                    format!(
+                        "Cannot switch over '{}', only enums, 'i32' and 'str' can be used",
+                        t
+                    )
+                    .as_str(),
+                );
+                abort()
+            }
+        };
+
+        // only enums can leave out the casenot, when every variant is handled
+        if !cases.iter().any(|c| c.patterns.is_empty())
+            && !matches!(expr.literal_type, VariableTypes::Obj(_))
+        {
+            error(
+                start.line,
+                start.column,
+                format!(
+                    "switch over '{}' must have a casenot for the values it does not handle",
+                    expr.literal_type
+                ),
+            );
+            abort()
+        }
+
+        c_switch.push_str(&c_cases);
+        c_switch
+    }
+
+    fn transpile_enum_cases(&mut self, value: &str, name: &str, cases: &[SwitchCase]) -> String {
+        let indent = self.indent();
+        let start = self.position.to_owned();
+        let r#enum = self.get_enum(name).cloned().unwrap();
+        let mut handled: Vec<String> = Vec::new();
+        let mut c_cases = String::new();
+
+        for case in cases {
+            self.position = case.start.to_owned();
+            let mut labels: Vec<String> = Vec::new();
+            let mut bindings: Vec<(String, VariableTypes, String)> = Vec::new();
+
+            if case.patterns.is_empty() {
+                if handled.len() == r#enum.variants.len() {
+                    self.error_expr(
+                        format!(
+                            "casenot can never run, every variant of '{}' is already handled",
+                            name
+                        )
+                        .as_str(),
+                    );
+                    abort()
+                }
+                labels.push(String::from("default:"));
+            }
+
+            for pattern in &case.patterns {
+                let Pattern::Variant {
+                    r#enum: enum_name,
+                    variant,
+                    bindings: names,
+                } = pattern
+                else {
+                    self.error_expr(format!("Expected a variant of '{}'", name).as_str());
+                    abort()
+                };
+
+                if !self.resolve_name(enum_name).eq(name) {
+                    self.error_expr(
+                        format!("Expected a variant of '{}', found '{}'", name, enum_name).as_str(),
+                    );
+                    abort()
+                }
+                let Some(enum_variant) = r#enum.variants.iter().find(|v| v.name.eq(variant)) else {
+                    self.error_expr(format!("'{}' has no variant '{}'", name, variant).as_str());
+                    abort()
+                };
+                if handled.contains(variant) {
+                    self.error_expr(format!("Variant '{}' is already handled", variant).as_str());
+                    abort()
+                }
+                handled.push(variant.to_owned());
+                labels.push(format!("case {}_{}:", c_name(name), variant));
+
+                let Some(names) = names else {
+                    continue;
+                };
+                if case.patterns.len() > 1 {
+                    self.error_expr("Cases with several patterns cannot bind values");
+                    abort()
+                }
+                let payload = enum_variant.payload.to_owned().unwrap_or_default();
+                if names.len() != payload.len() {
+                    self.error_expr(
+                        format!(
+                            "Variant '{}' holds {} values but {} were bound",
+                            variant,
+                            payload.len(),
+                            names.len()
+                        )
+                        .as_str(),
+                    );
+                    abort()
+                }
+                for (i, (binding, r#type)) in names.iter().zip(payload).enumerate() {
+                    // '_' ignores the value
+                    if binding.eq("_") {
+                        continue;
+                    }
+                    if bindings.iter().any(|(n, _, _)| n.eq(binding)) {
+                        self.error_expr(format!("'{}' is bound more than once", binding).as_str());
+                        abort()
+                    }
+                    let c_value = format!("{}.as.{}._{}", value, c_name(variant), i);
+                    bindings.push((binding.to_owned(), r#type, c_value));
+                }
+            }
+
+            c_cases.push_str(&self.transpile_case(&labels, &case.block, &bindings));
+        }
+
+        let missing: Vec<String> = r#enum
+            .variants
+            .iter()
+            .filter(|v| !handled.contains(&v.name))
+            .map(|v| format!("'{}'", v.name))
+            .collect();
+        if !missing.is_empty() && !cases.iter().any(|c| c.patterns.is_empty()) {
+            self.position = start;
+            self.error_expr(
+                format!(
+                    "switch over '{}' does not handle {}, add the cases or a casenot",
+                    name,
+                    missing.join(", ")
+                )
+                .as_str(),
+            );
+            abort()
+        }
+
+        format!(
+            "{}switch ({}.tag) {{\n{}{}}}\n",
+            indent, value, c_cases, indent
+        )
+    }
+
+    fn transpile_int_cases(&mut self, value: &str, cases: &[SwitchCase]) -> String {
+        let indent = self.indent();
+        let mut handled: Vec<i32> = Vec::new();
+        let mut c_cases = String::new();
+
+        for case in cases {
+            self.position = case.start.to_owned();
+            let mut labels: Vec<String> = Vec::new();
+
+            if case.patterns.is_empty() {
+                labels.push(String::from("default:"));
+            }
+
+            for pattern in &case.patterns {
+                let number = match pattern {
+                    Pattern::Number(n) => match parse_int_literal(&n.replace('_', "")) {
+                        Some(number) => number,
+                        None => {
+                            self.error_expr(format!("Case '{}' is not a valid 'i32'", n).as_str());
+                            abort()
+                        }
+                    },
+                    _ => {
+                        self.error_expr("Expected an 'i32' case");
+                        abort()
+                    }
+                };
+                if handled.contains(&number) {
+                    self.error_expr(format!("Case '{}' is already handled", number).as_str());
+                    abort()
+                }
+                handled.push(number);
+                labels.push(format!("case {}:", c_int(number)));
+            }
+
+            c_cases.push_str(&self.transpile_case(&labels, &case.block, &[]));
+        }
+
+        format!("{}switch ({}) {{\n{}{}}}\n", indent, value, c_cases, indent)
+    }
+
+    /// C cannot switch over strings, so they become an if chain
+    fn transpile_str_cases(&mut self, value: &str, cases: &[SwitchCase]) -> String {
+        let indent = self.indent();
+        let mut handled: Vec<String> = Vec::new();
+        let mut c_cases = String::new();
+
+        for (i, case) in cases.iter().enumerate() {
+            self.position = case.start.to_owned();
+            let mut conditions: Vec<String> = Vec::new();
+
+            for pattern in &case.patterns {
+                let Pattern::String(string) = pattern else {
+                    self.error_expr("Expected a 'str' case");
+                    abort()
+                };
+                if handled.contains(string) {
+                    self.error_expr(format!("Case '{}' is already handled", string).as_str());
+                    abort()
+                }
+                handled.push(string.to_owned());
+                conditions.push(format!("compare({}, {}) == 0", value, c_string(string)));
+            }
+
+            let c_block = self.transpile_block(&case.block, &[]);
+            let keyword = match (i, conditions.is_empty()) {
+                (0, true) => String::from("if (true)"),
+                (0, false) => format!("if ({})", conditions.join(" || ")),
+                (_, true) => String::from(" else"),
+                (_, false) => format!(" else if ({})", conditions.join(" || ")),
+            };
+            c_cases.push_str(&format!("{} {{\n{}{}}}", keyword, c_block, indent));
+        }
+
+        format!("{}{}\n", indent, c_cases)
+    }
+
+    /// a C case that never falls through to the next one
+    fn transpile_case(
+        &mut self,
+        labels: &[String],
+        block: &Option<Vec<Statement>>,
+        bindings: &[(String, VariableTypes, String)],
+    ) -> String {
+        self.indentation += 1;
+        let indent = self.indent();
+
+        self.breakables.push(Breakable::Switch);
+        let c_block = self.transpile_block_with(block, &[], bindings);
+        self.breakables.pop();
+
+        let c_break = match block.iter().flatten().last() {
+            Some(stmt) if is_exit(stmt) => String::new(),
+            _ => format!("{}    break;\n", indent),
+        };
+        let c_case = format!(
+            "{}{} {{\n{}{}{}}}\n",
+            indent,
+            labels.join(" "),
+            c_block,
+            c_break,
+            indent
+        );
+        self.indentation -= 1;
+        c_case
+    }
+
+    fn enter_loop(&mut self) {
+        self.generated += 1;
+        self.breakables.push(Breakable::Loop {
+            label: format!("loop_end{}", self.generated),
+            used: false,
+        });
+    }
+
+    /// returns the label placed after the loop, if a 'brk' needs it
+    fn exit_loop(&mut self) -> String {
+        match self.breakables.pop() {
+            Some(Breakable::Loop { label, used: true }) => {
+                format!("{}{}:;\n", self.indent(), label)
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// inside a C switch 'break' would only leave the switch, so it jumps past the loop instead
+    fn transpile_break(&mut self) -> String {
+        if let Some(Breakable::Loop { .. }) | None = self.breakables.last() {
+            return String::from("break");
+        }
+        for breakable in self.breakables.iter_mut().rev() {
+            if let Breakable::Loop { label, used } = breakable {
+                *used = true;
+                return format!("goto {}", label);
+            }
+        }
+        String::from("break")
+    }
+
+    /// returns the declaration without the trailing ';' so it can be used inside for loops
+    fn transpile_var_declaration(&mut self, stmt: &Statement) -> String {
+        let Statement::VariableDeclaration {
+            name: Some(name),
+            kind,
+            r#type: Some(r#type),
+            value,
+            ..
+        } = stmt
+        else {
+            eprintln!("Unknown error at variable declaration");
+            abort()
+        };
+        let r#type = &self.resolve_type(r#type);
+
+        if self.is_declared_in_scope(name) {
+            self.error_expr(
+                format!("Variable '{}' is already defined in this scope", name).as_str(),
+            );
+            abort()
+        }
+
+        let c_value = self.eval_initial_value(name, r#type, value);
+
+        self.declare_variable(name, r#type, kind);
+
+        format!(
+            "{} = {}",
+            self.c_declaration(r#type, &c_name(name)),
+            c_value
+        )
+    }
+
+    /// C value a variable starts with, value is None for 'mut' variables declared without one
+    fn eval_initial_value(
+        &mut self,
+        name: &str,
+        r#type: &VariableTypes,
+        value: &Option<Expression>,
+    ) -> String {
+        match value {
+            Some(v) => {
+                let expr = self.eval_typed_expr(v, r#type);
+                match self.coerce(&expr, r#type) {
+                    Some(value) => value,
+                    None => {
+                        self.error_expr(
+                            format!(
+                                "Cannot assign '{}' to variable '{}' of type '{}'{}",
+                                expr.literal_type,
+                                name,
+                                r#type,
+                                quotes_hint(r#type, &expr.literal_type)
+                            )
+                            .as_str(),
+                        );
+                        abort()
+                    }
+                }
+            }
+            None => self.default_value(r#type),
+        }
+    }
+
+    /// returns the alteration without the trailing ';' so it can be used inside for loops
+    fn transpile_var_alteration(&mut self, stmt: &Statement) -> String {
+        let Statement::VariableAlteration {
+            name,
+            operator,
+            value,
+            ..
+        } = stmt
+        else {
+            eprintln!("Unknown error at variable alteration");
+            abort()
+        };
+
+        // narrowed variables are assigned with their declared optional type
+        let variable = match self.get_declared_variable(name) {
+            Some(v) => v.to_owned(),
+            None => {
+                self.error_expr(format!("Variable '{}' is not defined", name).as_str());
+                abort()
+            }
+        };
+
+        self.check_mutable(&variable);
+
+        self.transpile_assignment(
+            &variable.c_name,
+            &variable.var_type,
+            &format!("variable '{}'", name),
+            operator,
+            value,
+        )
+    }
+
+    fn transpile_place_alteration(
+        &mut self,
+        target: &Expression,
+        operator: &TokenTypes,
+        value: &Expression,
+    ) -> String {
+        let root = match self.imported_root(target) {
+            Some(global) => global,
+            None => {
+                let Some(root) = root_variable(target) else {
+                    self.error_expr(
+                        "Only variables, their fields and their elements can be assigned",
+                    );
+                    abort()
+                };
+                match self.get_declared_variable(root) {
+                    Some(variable) => variable.to_owned(),
+                    None => {
+                        self.error_expr(format!("Variable '{}' is not defined", root).as_str());
+                        abort()
+                    }
+                }
+            }
+        };
+
+        self.check_mutable(&root);
+
+        if let Expression::ArrayAccess(access) = target {
+            let indexed = match access {
+                ArrayAccess::Access { name, .. } => {
+                    self.eval_expr(&Expression::Identifier(name.to_owned()))
+                }
+                ArrayAccess::NestedAccess { access, .. } => self.eval_array_access(access),
+                ArrayAccess::ObjectAccess { object, .. } => self.eval_expr(object),
+            };
+            if indexed.literal_type.eq(&VariableTypes::Str) {
+                self.error_expr(
+                    "The chars of a 'str' cannot be changed, build a new 'str' instead",
+                );
+                abort()
+            }
+        }
+
+        let description = match target {
+            Expression::FieldAccess { object, field } if self.module_name(object).is_none() => {
+                format!("field '{}'", field)
+            }
+            Expression::ArrayAccess(_) => format!("element of '{}'", root.name),
+            _ => format!("variable '{}'", root.name),
+        };
+        let place = self.eval_expr(target);
+
+        self.transpile_assignment(
+            &place.value,
+            &place.literal_type,
+            &description,
+            operator,
+            value,
+        )
+    }
+
+    fn check_mutable(&self, variable: &Variable) {
+        if variable.captured {
+            self.error_expr(
+                format!(
+                    "Cannot mutate '{}' inside a lambda, it only holds a copy of the variable",
+                    variable.name
+                )
+                .as_str(),
+            );
+            abort()
+        }
+        if let VarDeclarationKind::Immutable = variable.kind {
+            self.error_expr(
+                format!(
+                    "Cannot mutate immutable variable '{}', declare it with 'mut'",
+                    variable.name
+                )
+                .as_str(),
+            );
+            abort()
+        }
+    }
+
+    /// target is the C lvalue being assigned and description names it in errors
+    fn transpile_assignment(
+        &mut self,
+        target: &str,
+        target_type: &VariableTypes,
+        description: &str,
+        operator: &TokenTypes,
+        value: &Expression,
+    ) -> String {
+        let expr = self.eval_typed_expr(value, target_type);
+
+        match (operator, target_type) {
+            (TokenTypes::Assign, _) => match self.coerce(&expr, target_type) {
+                Some(value) => format!("{} = {}", target, value),
+                None => {
+                    self.error_expr(
+                        format!(
+                            "Cannot assign '{}' to {} of type '{}'{}",
+                            expr.literal_type,
+                            description,
+                            target_type,
+                            quotes_hint(target_type, &expr.literal_type)
+                        )
+                        .as_str(),
+                    );
+                    abort()
+                }
+            },
+            (TokenTypes::AssignPlus, VariableTypes::Str) => match self.stringify(&expr) {
+                Some(s) => format!("{} = concat({}, {})", target, target, s),
+                None => {
+                    self.error_expr(
+                        format!("Cannot concatenate 'str' with '{}'", expr.literal_type).as_str(),
+                    );
+                    abort()
+                }
+            },
+            (
+                TokenTypes::BinaryIncrement | TokenTypes::BinaryDecrement,
+                VariableTypes::Int | VariableTypes::Flo,
+            ) => {
+                format!("{}{}", target, operator)
+            }
+            (TokenTypes::AssignRest, VariableTypes::Flo) if is_numeric(&expr.literal_type) => {
+                format!("{} = flo_rest({}, {})", target, target, expr.value)
+            }
+            (
+                TokenTypes::AssignPlus
+                | TokenTypes::AssignMinus
+                | TokenTypes::AssignMultiply
+                | TokenTypes::AssignDivision
+                | TokenTypes::AssignRest,
+                VariableTypes::Int | VariableTypes::Flo,
+            ) if self.is_assignable(target_type, &expr.literal_type) => {
+                format!("{} {} {}", target, operator, expr.value)
+            }
+            _ => {
+                self.error_expr(
+                    format!(
+                        "Cannot use '{}' on '{}' with '{}'",
+                        operator, target_type, expr.literal_type
+                    )
+                    .as_str(),
+                );
+                abort()
+            }
+        }
+    }
+
+    /// returns the return statement without the trailing ';'
+    fn transpile_return(&mut self, expression: &Option<Expression>) -> String {
+        match expression {
+            Some(e) => {
+                let return_type = self.return_type.to_owned();
+                let expr = self.eval_typed_expr(e, &return_type);
+                if return_type.eq(&VariableTypes::Nul) && expr.literal_type.eq(&VariableTypes::Nul)
+                {
+                    // 'ret println("");' still has to print
+                    return match e {
+                        Expression::Literal { .. } => String::from("return"),
+                        _ => format!("{}; return", expr.value),
+                    };
+                }
+                match self.coerce(&expr, &return_type) {
+                    Some(value) => format!("return {}", value),
+                    None => {
+                        self.error_expr(
+                            format!(
+                                "Cannot return '{}' from a function of type '{}'",
+                                expr.literal_type, return_type
+                            )
+                            .as_str(),
+                        );
+                        abort()
+                    }
+                }
+            }
+            None => {
+                if !self.return_type.eq(&VariableTypes::Nul) {
+                    self.error_expr(
+                        format!(
+                            "Missing return value for a function of type '{}'",
+                            self.return_type
+                        )
+                        .as_str(),
+                    );
+                    abort()
+                }
+                String::from("return")
+            }
+        }
+    }
+
+    fn eval_condition(&mut self, condition: &Expression) -> String {
+        let expr = self.eval_expr(condition);
+        if !expr.literal_type.eq(&VariableTypes::Boo) {
+            self.error_expr(
+                format!(
+                    "Condition must be of type 'bool', found '{}'",
+                    expr.literal_type
+                )
+                .as_str(),
+            );
+            abort()
+        }
+        expr.value
+    }
+
+    fn eval_expr(&mut self, expr: &Expression) -> Expr {
+        match expr {
+            Expression::Literal { r#type, value } => match r#type {
+                LiteralTypes::String => Expr {
+                    value: c_string(value),
+                    literal_type: VariableTypes::Str,
+                },
+                LiteralTypes::Null => Expr {
+                    value: String::from("NULL"),
+                    literal_type: VariableTypes::Nul,
+                },
+                LiteralTypes::Numeric => {
+                    // '_' separators are not valid in C
+                    let digits = value.replace('_', "");
+                    if radix(&digits) == 10 && digits.contains(['.', 'e', 'E']) {
+                        return Expr {
+                            value: digits,
+                            literal_type: VariableTypes::Flo,
+                        };
+                    }
+                    // the parser checks this too, trees from JSON are only checked here
+                    let Some(number) = parse_int_literal(&digits) else {
+                        self.error_expr(
+                            format!("Number literal '{}' does not fit in 32 bits", value).as_str(),
+                        );
+                        abort()
+                    };
+                    Expr {
+                        value: c_int(number),
+                        literal_type: VariableTypes::Int,
+                    }
+                }
+                // the unicode code point
+                LiteralTypes::Character => Expr {
+                    value: value.chars().next().map(u32::from).unwrap_or(0).to_string(),
+                    literal_type: VariableTypes::Chr,
+                },
+                LiteralTypes::Boolean => Expr {
+                    value: value.to_string(),
+                    literal_type: VariableTypes::Boo,
+                },
+            },
+            Expression::ArrayLiteral { elements } => self.eval_array_literal(elements, None),
+            Expression::ArrayAccess(access) => self.eval_array_access(access),
+            Expression::Identifier(name) => match self.get_variable(name) {
+                Some(variable) => Expr {
+                    value: variable.c_name.to_owned(),
+                    literal_type: variable.var_type.to_owned(),
+                },
+                None if self.get_function(&self.qualify(name)).is_some() => {
+                    let function = self.get_function(&self.qualify(name)).cloned().unwrap();
+                    self.eval_function_value(&function)
+                }
+                None => {
+                    self.error_expr(
+                        format!("Variable '{}' being used before assigned", name).as_str(),
+                    );
+                    abort()
+                }
+            },
+            Expression::Call { name, arguments } => {
+                if let Some(Variable {
+                    c_name,
+                    var_type: VariableTypes::Func { params, r#type },
+                    ..
+                }) = self.get_variable(name).cloned()
+                {
+                    let closure = Function {
+                        name: name.to_owned(),
+                        builtin: false,
+                        params: Some(params),
+                        return_type: *r#type,
+                    };
+                    let mut values = vec![format!("{}.env", c_name)];
+                    values.extend(self.eval_arguments(&closure, arguments));
+                    return Expr {
+                        value: format!("{}.call({})", c_name, values.join(", ")),
+                        literal_type: closure.return_type,
+                    };
+                }
+
+                // functions of the module itself take priority over builtins
+                let function = self
+                    .get_function(&self.qualify(name))
+                    .or(self.get_function(name).filter(|f| f.builtin))
+                    .cloned();
+                match function {
+                    Some(function) => self.eval_call(&function, arguments),
+                    None => {
+                        self.error_expr(format!("Function '{}' is not defined", name).as_str());
+                        abort()
+                    }
+                }
+            }
+            Expression::StructLiteral { name, fields } => {
+                let name = self.resolve_name(name);
+                self.eval_struct_literal(&name, fields)
+            }
+            Expression::FieldAccess { object, field } => match self.enum_name(object) {
+                Some(name) => self.eval_variant(&name, field, &None),
+                None if self.module_name(object).is_some() => {
+                    let module = self.module_name(object).unwrap();
+                    let name = format!("{}.{}", module, field);
+                    match self.get_global(&name) {
+                        Some(global) => Expr {
+                            value: global.c_name.to_owned(),
+                            literal_type: global.var_type.to_owned(),
+                        },
+                        None if self.get_function(&name).is_some() => {
+                            let function = self.get_function(&name).cloned().unwrap();
+                            self.eval_function_value(&function)
+                        }
+                        None => {
+                            self.error_expr(
+                                format!("'{}' is not defined in '{}'", field, module).as_str(),
+                            );
+                            abort()
+                        }
+                    }
+                }
+                None => {
+                    let object = self.eval_expr(object);
+                    self.eval_field_access(object, field)
+                }
+            },
+            Expression::MethodCall {
+                object,
+                name,
+                arguments,
+            } => match self.enum_name(object) {
+                Some(r#enum) => self.eval_variant(&r#enum, name, arguments),
+                None if self.module_name(object).is_some() => {
+                    let module = self.module_name(object).unwrap();
+                    let Some(function) =
+                        self.get_function(&format!("{}.{}", module, name)).cloned()
+                    else {
+                        self.error_expr(
+                            format!("Function '{}' is not defined in '{}'", name, module).as_str(),
+                        );
+                        abort()
+                    };
+                    self.eval_call(&function, arguments)
+                }
+                None => self.eval_method_call(object, name, arguments),
+            },
+            Expression::Cast { expression, r#type } => {
+                let expr = self.eval_expr(expression);
+                let r#type = self.resolve_type(r#type);
+                self.eval_cast(expr, &r#type)
+            }
+            Expression::Ternary {
+                condition,
+                when_true,
+                when_false,
+            } => self.eval_ternary(condition, when_true, when_false, None),
+            Expression::Lambda {
+                params,
+                r#type,
+                body,
+            } => self.eval_lambda(params, r#type, body),
+            Expression::Range { .. } => {
+                self.error_expr("Ranges can only be used in for loops");
+                abort()
+            }
+            Expression::Interpolation(parts) => {
+                let mut value: Option<String> = None;
+                for part in parts {
+                    let expr = self.eval_expr(part);
+                    let Some(text) = self.stringify(&expr) else {
+                        self.error_expr(
+                            format!(
+                                "Cannot put '{}' in a string, only 'i32', 'f64', 'bool', 'char' and 'str'",
+                                expr.literal_type
+                            )
+                            .as_str(),
+                        );
+                        abort()
+                    };
+                    value = Some(match value {
+                        Some(v) => format!("concat({}, {})", v, text),
+                        None => text,
+                    });
+                }
+                Expr {
+                    value: value.unwrap_or(String::from("\"\"")),
+                    literal_type: VariableTypes::Str,
+                }
+            }
+            Expression::Unary { operator, operand } => match operator {
+                TokenTypes::LogicalNot => {
+                    let right = self.eval_expr(operand);
+                    if right.literal_type.eq(&VariableTypes::Boo) {
+                        Expr {
+                            value: format!("!{}", right.value),
+                            literal_type: right.literal_type,
+                        }
+                    } else {
+                        self.error_expr("Cannot use '!' on non boolean values");
+                        abort()
+                    }
+                }
+                TokenTypes::BitwiseNot => {
+                    let right = self.eval_expr(operand);
+                    if right.literal_type.eq(&VariableTypes::Int) {
+                        Expr {
+                            value: format!("(~{})", right.value),
+                            literal_type: right.literal_type,
+                        }
+                    } else {
+                        self.error_expr(
+                            format!("Cannot use '~' on '{}', only on 'i32'", right.literal_type)
+                                .as_str(),
+                        );
+                        abort()
+                    }
+                }
+                TokenTypes::BinaryMinus => {
+                    if let Some(number) = negative_int_literal(operand) {
+                        return Expr {
+                            value: c_int(number),
+                            literal_type: VariableTypes::Int,
+                        };
+                    }
+                    let right = self.eval_expr(operand);
+                    if is_numeric(&right.literal_type) {
+                        Expr {
+                            value: format!("(-{})", right.value),
+                            literal_type: right.literal_type,
+                        }
+                    } else {
+                        self.error_expr("Cannot use '-' on non-numeric value");
+                        abort()
+                    }
+                }
+                _ => {
+                    eprintln!("Unknown error evaluating unary expression");
+                    abort()
+                }
+            },
+            Expression::Logical {
+                operator,
+                left,
+                right,
+            } => {
+                let e_left = self.eval_expr(left);
+
+                // the right side only runs when the left side allows it to
+                let narrowed = match operator {
+                    TokenTypes::LogicalAnd => self.narrowings(left).0,
+                    TokenTypes::LogicalOr => self.narrowings(left).1,
+                    _ => Vec::new(),
+                };
+                self.enter_scope();
+                self.narrow(&narrowed);
+                let e_right = self.eval_expr(right);
+                self.exit_scope();
+
+                self.eval_logical(operator, e_left, e_right)
+            }
+            Expression::Binary {
+                operator: operator @ (TokenTypes::BitwiseAnd | TokenTypes::BitwiseOr),
+                left,
+                right,
+            } if self.eval_expr(left).literal_type.eq(&VariableTypes::Boo) => {
+                // '&' and '|' were the logical operators before '&&' and '||' existed
+                let (old, operator) = match operator {
+                    TokenTypes::BitwiseAnd => ("&", TokenTypes::LogicalAnd),
+                    _ => ("|", TokenTypes::LogicalOr),
+                };
+                self.warning_expr(
+                    format!(
+                        "'{}' between bools is deprecated, use '{}' instead",
+                        old, operator
+                    )
+                    .as_str(),
+                );
+                self.eval_expr(&Expression::Logical {
+                    operator,
+                    left: left.to_owned(),
+                    right: right.to_owned(),
+                })
+            }
+            Expression::Binary {
+                operator,
+                left,
+                right,
+            } => {
+                let e_left = self.eval_expr(left);
+                let e_right = self.eval_expr(right);
+
+                self.eval_binary(operator, e_left, e_right)
+            }
+        }
+    }
+
+    /// evaluates an expression knowing the type it will be stored as,
+    /// so literals like '[]' or '[1, 2]' in a vec<f64> get the right element type
+    fn eval_typed_expr(&mut self, expr: &Expression, expected: &VariableTypes) -> Expr {
+        match (expr, expected) {
+            (Expression::ArrayLiteral { elements }, VariableTypes::Arr(r#type)) => {
+                self.eval_array_literal(elements, Some(r#type))
+            }
+            (
+                Expression::Ternary {
+                    condition,
+                    when_true,
+                    when_false,
+                },
+                _,
+            ) => self.eval_ternary(condition, when_true, when_false, Some(expected)),
+            _ => self.eval_expr(expr),
+        }
+    }
+
+    /// both branches are converted to the type they have in common
+    fn eval_ternary(
+        &mut self,
+        condition: &Expression,
+        when_true: &Expression,
+        when_false: &Expression,
+        expected: Option<&VariableTypes>,
+    ) -> Expr {
+        let c_condition = self.eval_condition(condition);
+        let (narrowed_true, narrowed_false) = self.narrowings(condition);
+
+        let mut branches: Vec<Expr> = Vec::new();
+        for (branch, narrowed) in [(when_true, narrowed_true), (when_false, narrowed_false)] {
+            self.enter_scope();
+            self.narrow(&narrowed);
+            branches.push(match expected {
+                Some(t) => self.eval_typed_expr(branch, t),
+                None => self.eval_expr(branch),
+            });
+            self.exit_scope();
+        }
+
+        let Some(r#type) = common_type(&branches[0].literal_type, &branches[1].literal_type) else {
+            self.error_expr(
+                format!(
+                    "Both sides of '?' must have the same type, found '{}' and '{}'",
+                    branches[0].literal_type, branches[1].literal_type
+                )
+                .as_str(),
+            );
+            abort()
+        };
+
+        let mut values: Vec<String> = Vec::new();
+        for (branch, expr) in [when_true, when_false].into_iter().zip(&branches) {
+            // '[]' only gets its element type from the other side
+            let expr = match branch {
+                Expression::ArrayLiteral { elements: None } => {
+                    self.eval_typed_expr(branch, &r#type)
+                }
+                _ => expr.to_owned(),
+            };
+            values.push(self.coerce(&expr, &r#type).unwrap_or(expr.value));
+        }
+
+        Expr {
+            value: format!("({} ? {} : {})", c_condition, values[0], values[1]),
+            literal_type: r#type,
+        }
+    }
+
+    fn eval_array_literal(
+        &mut self,
+        elements: &Option<Vec<Expression>>,
+        expected: Option<&VariableTypes>,
+    ) -> Expr {
+        let mut values: Vec<String> = Vec::new();
+        // the type of '[]' is unknown until it is stored somewhere
+        let mut element_type = expected.cloned().unwrap_or(VariableTypes::Nul);
+
+        for (i, element) in elements.iter().flatten().enumerate() {
+            let expr = match expected {
+                Some(t) => self.eval_typed_expr(element, t),
+                None => self.eval_expr(element),
+            };
+
+            if let Some(t) = expected {
+                match self.coerce(&expr, t) {
+                    Some(value) => values.push(value),
+                    None => {
+                        self.error_expr(
+                            format!("Cannot store '{}' in a 'vec<{}>'", expr.literal_type, t)
+                                .as_str(),
+                        );
+                        abort()
+                    }
+                }
+                continue;
+            }
+
+            element_type = match i {
+                0 => expr.literal_type.to_owned(),
+                _ => match unify_types(&element_type, &expr.literal_type) {
+                    Some(t) => t,
+                    None => {
+                        self.error_expr(
+                            format!(
+                                "vec elements must have the same type, found '{}' and '{}'",
+                                element_type, expr.literal_type
+                            )
+                            .as_str(),
+                        );
+                        abort()
+                    }
+                },
+            };
+
+            values.push(expr.value);
+        }
+
+        let value = if values.is_empty() {
+            String::from("vec_new(0, 0, NULL)")
+        } else {
+            let c_type = self.get_c_type(&element_type);
+            format!(
+                "vec_new({}, sizeof({}), ({}[]){{{}}})",
+                values.len(),
+                c_type,
+                c_type,
+                values.join(", ")
+            )
+        };
+
+        Expr {
+            value,
+            literal_type: VariableTypes::Arr(Box::new(element_type)),
+        }
+    }
+
+    fn eval_array_access(&mut self, access: &ArrayAccess) -> Expr {
+        let (array, index) = match access {
+            ArrayAccess::Access { name, index } => (
+                self.eval_expr(&Expression::Identifier(name.to_owned())),
+                index,
+            ),
+            ArrayAccess::NestedAccess { access, index } => (self.eval_array_access(access), index),
+            ArrayAccess::ObjectAccess { object, index } => (self.eval_expr(object), index),
+        };
+
+        let element_type = match &array.literal_type {
+            VariableTypes::Arr(t) => *t.to_owned(),
+            VariableTypes::Str => VariableTypes::Chr,
+            t => {
+                self.error_expr(format!("Cannot index into '{}'", t).as_str());
+                abort()
+            }
+        };
+
+        let index = self.eval_expr(index);
+        if !index.literal_type.eq(&VariableTypes::Int) {
+            self.error_expr(
+                format!(
+                    "{} index must be 'i32', found '{}'",
+                    array.literal_type, index.literal_type
+                )
+                .as_str(),
+            );
+            abort()
+        }
+
+        // the index is in bytes, like the ones str.len() and str.find() give
+        if array.literal_type.eq(&VariableTypes::Str) {
+            return Expr {
+                value: format!("str_char_at({}, {})", array.value, index.value),
+                literal_type: VariableTypes::Chr,
+            };
+        }
+
+        let c_type = self.get_c_type(&element_type);
+
+        Expr {
+            value: format!(
+                "(*({})vec_at({}, {}, sizeof({})))",
+                c_pointer(&c_type),
+                array.value,
+                index.value,
+                c_type
+            ),
+            literal_type: element_type,
+        }
+    }
+
+    /// methods built into str and vec, the receiver is the first argument of the C function
+    fn eval_method_call(
+        &mut self,
+        object: &Expression,
+        name: &str,
+        arguments: &Option<Vec<Expression>>,
+    ) -> Expr {
+        let receiver = self.eval_expr(object);
+        let Some((params, return_type)) = builtin_method(&receiver.literal_type, name) else {
+            self.error_expr(
+                format!("'{}' has no method '{}'", receiver.literal_type, name).as_str(),
+            );
+            abort()
+        };
+
+        // changing a vec is changing the variable holding it
+        if matches!(name, "push" | "pop" | "clear") {
+            let root = match self.imported_root(object) {
+                Some(global) => Some(global),
+                None => root_variable(object).and_then(|r| self.get_declared_variable(r).cloned()),
+            };
+            if let Some(root) = root {
+                self.check_mutable(&root);
+            }
+        }
+
+        let method = Function {
+            name: format!("{}.{}", receiver.literal_type, name),
+            builtin: true,
+            params: Some(params),
+            return_type,
+        };
+        let values = self.eval_arguments(&method, arguments);
+
+        let value = match &receiver.literal_type {
+            VariableTypes::Arr(element_type) => {
+                let c_type = self.get_c_type(element_type);
+                match name {
+                    "len" => format!("{}->len", receiver.value),
+                    "push" => format!(
+                        "vec_push({}, ({}[]){{{}}}, sizeof({}))",
+                        receiver.value, c_type, values[0], c_type
+                    ),
+                    "pop" => format!(
+                        "(*({})vec_pop({}, sizeof({})))",
+                        c_pointer(&c_type),
+                        receiver.value,
+                        c_type
+                    ),
+                    "slice" => format!(
+                        "vec_slice({}, {}, {}, sizeof({}))",
+                        receiver.value, values[0], values[1], c_type
+                    ),
+                    _ => format!("vec_{}({})", name, receiver.value),
+                }
+            }
+            _ => format!(
+                "str_{}({})",
+                name,
+                [vec![receiver.value], values].concat().join(", ")
+            ),
+        };
+
+        Expr {
+            value,
+            literal_type: method.return_type,
+        }
+    }
+
+    fn eval_call(&mut self, function: &Function, arguments: &Option<Vec<Expression>>) -> Expr {
+        let values = self.eval_arguments(function, arguments);
+        // builtins are defined by the runtime under their own name
+        let c_function = match function.builtin {
+            true => function.name.to_owned(),
+            false => c_name(&function.name),
+        };
+
+        Expr {
+            value: format!("{}({})", c_function, values.join(", ")),
+            literal_type: function.return_type.to_owned(),
+        }
+    }
+
+    /// C values of the arguments, checked against the parameters of the function
+    fn eval_arguments(
+        &mut self,
+        function: &Function,
+        arguments: &Option<Vec<Expression>>,
+    ) -> Vec<String> {
+        let name = &function.name;
+        let args: Vec<Expression> = arguments.iter().flatten().cloned().collect();
+
+        let values: Vec<String> = match &function.params {
+            None => self.eval_format_arguments(name, &args),
+            Some(params) => {
+                if params.len() != args.len() {
+                    self.error_expr(
+                        format!(
+                            "Function '{}' takes {} arguments but {} were given",
+                            name,
+                            params.len(),
+                            args.len()
+                        )
+                        .as_str(),
+                    );
+                    abort()
+                }
+                let mut values = Vec::new();
+                for (i, (param, arg)) in params.iter().zip(&args).enumerate() {
+                    let expr = self.eval_typed_expr(arg, param);
+                    match self.coerce(&expr, param) {
+                        Some(value) => values.push(value),
+                        None => {
+                            self.error_expr(
+                                format!(
+                                    "Argument {} of '{}' must be of type '{}', found '{}'{}",
+                                    i + 1,
+                                    name,
+                                    param,
+                                    expr.literal_type,
+                                    quotes_hint(param, &expr.literal_type)
+                                )
+                                .as_str(),
+                            );
+                            abort()
+                        }
+                    }
+                }
+                values
+            }
+        };
+
+        values
+    }
+
+    /// C values of the arguments of a printf like function, the values after the format
+    /// can only be types C's printf can show, chars are shown as strs
+    fn eval_format_arguments(&mut self, name: &str, args: &[Expression]) -> Vec<String> {
+        let Some((format, args)) = args.split_first() else {
+            self.error_expr(
+                format!(
+                    "Function '{}' needs a format 'str' as its first argument",
+                    name
+                )
+                .as_str(),
+            );
+            abort()
+        };
+        let format = self.eval_expr(format);
+        if !format.literal_type.eq(&VariableTypes::Str) {
+            self.error_expr(
+                format!(
+                    "The format of '{}' must be of type 'str', found '{}'{}",
+                    name,
+                    format.literal_type,
+                    quotes_hint(&VariableTypes::Str, &format.literal_type)
+                )
+                .as_str(),
+            );
+            abort()
+        }
+
+        let mut values = vec![format.value];
+        for (i, arg) in args.iter().enumerate() {
+            let expr = self.eval_expr(arg);
+            let value = match expr.literal_type {
+                VariableTypes::Int
+                | VariableTypes::Flo
+                | VariableTypes::Boo
+                | VariableTypes::Str => expr.value,
+                VariableTypes::Chr => format!("char_to_str({})", expr.value),
+                VariableTypes::Optional(_) => {
+                    self.error_expr(
+                        format!(
+                            "Argument {} of '{}' is '{}', compare it with null first",
+                            i + 2,
+                            name,
+                            expr.literal_type
+                        )
+                        .as_str(),
+                    );
+                    abort()
+                }
+                _ => {
+                    self.error_expr(
+                        format!(
+                            "Argument {} of '{}' must be 'i32', 'f64', 'bool', 'char' or 'str', found '{}'",
+                            i + 2,
+                            name,
+                            expr.literal_type
+                        )
+                        .as_str(),
+                    );
+                    abort()
+                }
+            };
+            values.push(value);
+        }
+        values
+    }
+
+    /// lowers the lambda to a C function taking a copy of the variables it uses,
+    /// the closure is the pair of that copy and the function
+    fn eval_lambda(
+        &mut self,
+        params: &Option<Vec<FuncParam>>,
+        r#type: &VariableTypes,
+        body: &Option<Vec<Statement>>,
+    ) -> Expr {
+        self.generated += 1;
+        let name = format!("__lambda{}", self.generated);
+        let env = format!("__env{}", self.generated);
+
+        let return_type = self.resolve_type(r#type);
+        let param_types: Vec<VariableTypes> = params
+            .iter()
+            .flatten()
+            .map(|p| self.resolve_type(&p.r#type))
+            .collect();
+        let closure_type = VariableTypes::Func {
+            params: param_types.to_owned(),
+            r#type: Box::new(return_type.to_owned()),
+        };
+        let c_closure = self.get_c_type(&closure_type);
+
+        // every variable the lambda could use, as the enclosing function sees it right now
+        let mut visible: Vec<Variable> = Vec::new();
+        for variable in self.variables.iter().rev() {
+            if !visible.iter().any(|v| v.name.eq(&variable.name)) {
+                visible.push(variable.to_owned());
+            }
+        }
+        let captures: Vec<Variable> = visible
+            .iter()
+            .map(|v| Variable {
+                name: v.name.to_owned(),
+                var_type: v.var_type.to_owned(),
+                kind: VarDeclarationKind::Immutable,
+                c_name: format!("__env->{}", c_name(&v.name)),
+                narrowed: false,
+                captured: true,
+            })
+            .collect();
+
+        let variables = std::mem::replace(&mut self.variables, captures);
+        let scopes = std::mem::take(&mut self.scopes);
+        let breakables = std::mem::take(&mut self.breakables);
+        let indentation = std::mem::replace(&mut self.indentation, 0);
+        let outer_return_type = std::mem::replace(&mut self.return_type, return_type.to_owned());
+        let position = self.position.to_owned();
+
+        self.enter_scope();
+        for (param, r#type) in params.iter().flatten().zip(&param_types) {
+            self.declare_variable(&param.name, r#type, &VarDeclarationKind::Immutable);
+        }
+        let mut c_body = self.transpile_block(body, &[]);
+        self.exit_scope();
+
+        self.variables = variables;
+        self.scopes = scopes;
+        self.breakables = breakables;
+        self.indentation = indentation;
+        self.return_type = outer_return_type;
+        self.position = position;
+
+        // only the variables the body reads are copied
+        let used: Vec<&Variable> = visible
+            .iter()
+            .filter(|v| uses(&c_body, &format!("__env->{}", c_name(&v.name))))
+            .collect();
+
+        let mut c_lambda = String::new();
+        let c_env = if used.is_empty() {
+            String::from("NULL")
+        } else {
+            let fields: String = used
+                .iter()
+                .map(|v| {
+                    format!(
+                        "    {};\n",
+                        self.c_declaration(&v.var_type, &c_name(&v.name))
+                    )
+                })
+                .collect();
+            c_lambda.push_str(&format!("typedef struct {{\n{}}} {};\n", fields, env));
+            c_body.insert_str(0, &format!("    {} *__env = __data;\n", env));
+
+            let values: Vec<String> = used
+                .iter()
+                .map(|v| format!(".{} = {}", c_name(&v.name), v.c_name))
+                .collect();
+            format!(
+                "env_new(&({}){{{}}}, sizeof({}))",
+                env,
+                values.join(", "),
+                env
+            )
+        };
+
+        let mut c_params = vec![String::from("void *__data")];
+        for (param, r#type) in params.iter().flatten().zip(&param_types) {
+            c_params.push(self.c_declaration(r#type, &c_name(&param.name)));
+        }
+        let c_return = match return_type {
+            VariableTypes::Nul => String::from("void"),
+            _ => self.get_c_type(&return_type),
+        };
+        c_lambda.push_str(&format!(
+            "static {} {}({}) {{\n{}}}\n",
+            c_return,
+            name,
+            c_params.join(", "),
+            c_body
+        ));
+        self.lambdas.push((name.to_owned(), c_lambda));
+
+        Expr {
+            value: format!("(({}){{{}, {}}})", c_closure, c_env, name),
+            literal_type: closure_type,
+        }
+    }
+
+    /// a function used as a value is wrapped in a C function that takes a closure's copy
+    fn eval_function_value(&mut self, function: &Function) -> Expr {
+        let Some(params) = function.params.to_owned() else {
+            self.error_expr(
+                format!(
+                    "Builtin function '{}' can only be called, not used as a value",
+                    function.name
+                )
+                .as_str(),
+            );
+            abort()
+        };
+        let closure_type = VariableTypes::Func {
+            params: params.to_owned(),
+            r#type: Box::new(function.return_type.to_owned()),
+        };
+        let c_closure = self.get_c_type(&closure_type);
+        let name = format!("__function_{}", c_name(&function.name));
+
+        if !self.lambdas.iter().any(|(n, _)| n.eq(&name)) {
+            let mut c_params = vec![String::from("void *__data")];
+            let mut arguments: Vec<String> = Vec::new();
+            for (i, r#type) in params.iter().enumerate() {
+                c_params.push(self.c_declaration(r#type, &format!("_{}", i)));
+                arguments.push(format!("_{}", i));
+            }
+            let call = format!("{}({})", c_name(&function.name), arguments.join(", "));
+            let (c_return, c_body) = match function.return_type {
+                VariableTypes::Nul => (String::from("void"), call),
+                _ => (
+                    self.get_c_type(&function.return_type),
+                    format!("return {}", call),
+                ),
+            };
+            self.lambdas.push((
+                name.to_owned(),
+                format!(
+                    "static {} {}({}) {{\n    {};\n}}\n",
+                    c_return,
+                    name,
+                    c_params.join(", "),
+                    c_body
+                ),
+            ));
+        }
+
+        Expr {
+            value: format!("(({}){{NULL, {}}})", c_closure, name),
+            literal_type: closure_type,
+        }
+    }
+
+    fn eval_struct_literal(&mut self, name: &str, fields: &Option<Vec<FieldValue>>) -> Expr {
+        let r#struct = match self.get_struct(name) {
+            Some(s) => s.to_owned(),
+            None => {
+                self.error_expr(format!("Struct '{}' is not defined", name).as_str());
+                abort()
+            }
+        };
+
+        let given: Vec<FieldValue> = fields.iter().flatten().cloned().collect();
+        let mut values: Vec<String> = Vec::new();
+
+        for (i, field_value) in given.iter().enumerate() {
+            let Some(field) = r#struct
+                .fields
+                .iter()
+                .find(|f| f.name.eq(&field_value.name))
+            else {
+                self.error_expr(format!("'{}' has no field '{}'", name, field_value.name).as_str());
+                abort()
+            };
+            if given[..i].iter().any(|f| f.name.eq(&field_value.name)) {
+                self.error_expr(
+                    format!("Field '{}' is set more than once", field_value.name).as_str(),
+                );
+                abort()
+            }
+
+            let expr = self.eval_typed_expr(&field_value.value, &field.r#type);
+            match self.coerce(&expr, &field.r#type) {
+                Some(value) => values.push(format!(".{} = {}", c_name(&field.name), value)),
+                None => {
+                    self.error_expr(
+                        format!(
+                            "Cannot assign '{}' to field '{}' of type '{}'",
+                            expr.literal_type, field.name, field.r#type
+                        )
+                        .as_str(),
+                    );
+                    abort()
+                }
+            }
+        }
+
+        // optional fields can be left out and start as null
+        for field in &r#struct.fields {
+            if given.iter().any(|f| f.name.eq(&field.name)) {
+                continue;
+            }
+            if let VariableTypes::Optional(_) = field.r#type {
+                values.push(format!(".{} = {{0}}", c_name(&field.name)));
+                continue;
+            }
+            self.error_expr(format!("Missing field '{}' in '{}'", field.name, name).as_str());
+            abort()
+        }
+
+        Expr {
+            value: format!("(({}){{{}}})", c_name(name), values.join(", ")),
+            literal_type: VariableTypes::Obj(name.to_owned()),
+        }
+    }
+
+    /// the enum an expression like 'Shape' or 'module.Shape' names, variables take priority
+    fn enum_name(&self, expr: &Expression) -> Option<String> {
+        let name = match expr {
+            Expression::Identifier(name) if self.get_variable(name).is_none() => self.qualify(name),
+            Expression::FieldAccess { object, field } => {
+                format!("{}.{}", self.module_name(object)?, field)
+            }
+            _ => return None,
+        };
+        self.get_enum(&name).map(|_| name)
+    }
+
+    /// the global of an imported module a place like 'math.origin.x' belongs to
+    fn imported_root(&self, expr: &Expression) -> Option<Variable> {
+        match expr {
+            Expression::FieldAccess { object, field } => match self.module_name(object) {
+                Some(module) => self.get_global(&format!("{}.{}", module, field)).cloned(),
+                None => self.imported_root(object),
+            },
+            _ => None,
+        }
+    }
+
+    /// the module an expression like 'math' names, variables take priority
+    fn module_name(&self, expr: &Expression) -> Option<String> {
+        match expr {
+            Expression::Identifier(alias) if self.get_variable(alias).is_none() => self
+                .imports
+                .iter()
+                .find(|(m, a, _)| m.eq(&self.module) && a.eq(alias))
+                .map(|(_, _, module)| module.to_owned()),
+            _ => None,
+        }
+    }
+
+    /// 'Point' or 'math.Point' as declared inside the module being transpiled
+    fn qualify(&self, name: &str) -> String {
+        match self.module.is_empty() {
+            true => name.to_owned(),
+            false => format!("{}.{}", self.module, name),
+        }
+    }
+
+    /// the declared name of a type written as 'Point' or 'alias.Point'
+    fn resolve_name(&self, name: &str) -> String {
+        let Some((alias, name)) = name.split_once('.') else {
+            return self.qualify(name);
+        };
+        match self
+            .imports
+            .iter()
+            .find(|(m, a, _)| m.eq(&self.module) && a.eq(alias))
+        {
+            Some((_, _, module)) => format!("{}.{}", module, name),
+            None => {
+                self.error_expr(format!("Module '{}' is not imported", alias).as_str());
+                abort()
+            }
+        }
+    }
+
+    fn resolve_type(&self, r#type: &VariableTypes) -> VariableTypes {
+        match r#type {
+            VariableTypes::Obj(name) => VariableTypes::Obj(self.resolve_name(name)),
+            VariableTypes::Arr(t) => VariableTypes::Arr(Box::new(self.resolve_type(t))),
+            VariableTypes::Optional(t) => VariableTypes::Optional(Box::new(self.resolve_type(t))),
+            VariableTypes::Func { params, r#type } => VariableTypes::Func {
+                params: params.iter().map(|p| self.resolve_type(p)).collect(),
+                r#type: Box::new(self.resolve_type(r#type)),
+            },
+            t => t.to_owned(),
+        }
+    }
+
+    fn eval_variant(
+        &mut self,
+        name: &str,
+        variant: &str,
+        arguments: &Option<Vec<Expression>>,
+    ) -> Expr {
+        let r#enum = self.get_enum(name).cloned().unwrap();
+        let Some(enum_variant) = r#enum.variants.iter().find(|v| v.name.eq(variant)) else {
+            self.error_expr(format!("'{}' has no variant '{}'", name, variant).as_str());
+            abort()
+        };
+
+        let payload = enum_variant.payload.to_owned().unwrap_or_default();
+        let args: Vec<Expression> = arguments.iter().flatten().cloned().collect();
+        if payload.len() != args.len() {
+            self.error_expr(
+                format!(
+                    "Variant '{}' holds {} values but {} were given",
+                    variant,
+                    payload.len(),
+                    args.len()
+                )
+                .as_str(),
+            );
+            abort()
+        }
+
+        let mut values: Vec<String> = Vec::new();
+        for (i, (r#type, arg)) in payload.iter().zip(&args).enumerate() {
+            let expr = self.eval_typed_expr(arg, r#type);
+            match self.coerce(&expr, r#type) {
+                Some(value) => values.push(value),
+                None => {
+                    self.error_expr(
+                        format!(
+                            "Value {} of '{}' must be of type '{}', found '{}'",
+                            i + 1,
+                            variant,
+                            r#type,
+                            expr.literal_type
+                        )
+                        .as_str(),
+                    );
+                    abort()
+                }
+            }
+        }
+
+        let c_type = self.get_c_type(&VariableTypes::Obj(name.to_owned()));
+        let value = if values.is_empty() {
+            format!("(({}){{.tag = {}_{}}})", c_type, c_type, variant)
+        } else {
+            format!(
+                "(({}){{.tag = {}_{}, .as.{} = {{{}}}}})",
+                c_type,
+                c_type,
+                variant,
+                c_name(variant),
+                values.join(", ")
+            )
+        };
+
+        Expr {
+            value,
+            literal_type: VariableTypes::Obj(name.to_owned()),
+        }
+    }
+
+    fn eval_field_access(&mut self, object: Expr, field: &str) -> Expr {
+        let r#struct = match &object.literal_type {
+            VariableTypes::Obj(name) => self.get_struct(name).cloned(),
+            VariableTypes::Optional(t) if matches!(t.as_ref(), VariableTypes::Obj(_)) => {
+                self.error_expr(
+                    format!(
+                        "Cannot access field '{}' of '{}', compare it with null first",
+                        field, object.literal_type
+                    )
+                    .as_str(),
+                );
+                abort()
+            }
+            _ => None,
+        };
+
+        match r#struct
+            .iter()
+            .flat_map(|s| s.fields.iter())
+            .find(|f| f.name.eq(field))
+        {
+            Some(f) => Expr {
+                value: format!("{}.{}", object.value, c_name(field)),
+                literal_type: f.r#type.to_owned(),
+            },
+            None => {
+                self.error_expr(
+                    format!("'{}' has no field '{}'", object.literal_type, field).as_str(),
+                );
+                abort()
+            }
+        }
+    }
+
+    /// every pair of primitive types either has a well defined conversion or is rejected here
+    fn eval_cast(&mut self, expr: Expr, target: &VariableTypes) -> Expr {
+        let value = match (&expr.literal_type, target) {
+            (from, to) if from.eq(to) => expr.value,
+            (VariableTypes::Int, VariableTypes::Flo) => format!("((double){})", expr.value),
+            // truncates towards zero, saturating at the i32 limits, NaN becomes 0
+            (VariableTypes::Flo, VariableTypes::Int) => format!("flo_to_int({})", expr.value),
+            (VariableTypes::Int | VariableTypes::Flo, VariableTypes::Boo) => {
+                format!("({} != 0)", expr.value)
+            }
+            (VariableTypes::Boo, VariableTypes::Int) => format!("((int){})", expr.value),
+            (VariableTypes::Boo, VariableTypes::Flo) => format!("((double){})", expr.value),
+            (
+                VariableTypes::Int | VariableTypes::Flo | VariableTypes::Boo | VariableTypes::Chr,
+                VariableTypes::Str,
+            ) => self.stringify(&expr).unwrap_or_default(),
+            (VariableTypes::Chr, VariableTypes::Int) => expr.value,
+            // numbers that are not unicode code points become U+FFFD
+            (VariableTypes::Int, VariableTypes::Chr) => format!("int_to_char({})", expr.value),
+            (VariableTypes::Str, VariableTypes::Chr) => {
+                self.error_expr("Cannot cast 'str' to 'char', index it with '[0]' instead");
+                abort()
+            }
+            // strings that are not numbers become 0
+            (VariableTypes::Str, VariableTypes::Int) => format!("str_to_int({})", expr.value),
+            (VariableTypes::Str, VariableTypes::Flo) => format!("str_to_flo({})", expr.value),
+            (VariableTypes::Str, VariableTypes::Boo) => {
+                self.error_expr("Cannot cast 'str' to 'bool', compare it with '==' instead");
+                abort()
+            }
+            (from, to) => {
+                self.error_expr(format!("Cannot cast '{}' to '{}'", from, to).as_str());
+                abort()
+            }
+        };
+
+        Expr {
+            value,
+            literal_type: target.to_owned(),
+        }
+    }
+
+    fn eval_logical(&mut self, operator: &TokenTypes, e_left: Expr, e_right: Expr) -> Expr {
+        let value = match operator {
+            TokenTypes::LogicalEquals | TokenTypes::LogicalDifferent => {
+                match (&e_left.literal_type, &e_right.literal_type) {
+                    (VariableTypes::Str, VariableTypes::Str) => format!(
+                        "(compare({}, {}) {} 0)",
+                        e_left.value, e_right.value, operator
+                    ),
+                    (VariableTypes::Boo, VariableTypes::Boo)
+                    | (VariableTypes::Chr, VariableTypes::Chr) => {
+                        format!("({} {} {})", e_left.value, operator, e_right.value)
+                    }
+                    (l, r) if is_numeric(l) && is_numeric(r) => {
+                        format!("({} {} {})", e_left.value, operator, e_right.value)
+                    }
+                    (VariableTypes::Optional(_), VariableTypes::Nul)
+                    | (VariableTypes::Nul, VariableTypes::Optional(_)) => {
+                        let optional = match e_left.literal_type {
+                            VariableTypes::Optional(_) => &e_left.value,
+                            _ => &e_right.value,
+                        };
+                        match operator {
+                            TokenTypes::LogicalEquals => format!("(!{}.some)", optional),
+                            _ => format!("{}.some", optional),
+                        }
+                    }
+                    (VariableTypes::Optional(_), _) | (_, VariableTypes::Optional(_)) => {
+                        self.error_expr(
+                            format!(
+                                "Cannot compare '{}' with '{}', compare it with null first",
+                                e_left.literal_type, e_right.literal_type
+                            )
+                            .as_str(),
+                        );
+                        abort()
+                    }
+                    (
+                        VariableTypes::Arr(_) | VariableTypes::Obj(_) | VariableTypes::Func { .. },
+                        _,
+                    )
+                    | (
+                        _,
+                        VariableTypes::Arr(_) | VariableTypes::Obj(_) | VariableTypes::Func { .. },
+                    ) => {
+                        self.error_expr(
+                            format!(
+                                "Cannot compare '{}' with '{}'",
+                                e_left.literal_type, e_right.literal_type
+                            )
+                            .as_str(),
+                        );
+                        abort()
+                    }
+                    // values of different types are never equal
+                    (l, r) => {
+                        let equals = l.eq(r);
+                        match operator {
+                            TokenTypes::LogicalEquals => equals.to_string(),
+                            _ => (!equals).to_string(),
+                        }
+                    }
+                }
+            }
+            TokenTypes::LogicalSmallerThan
+            | TokenTypes::LogicalSmallerOrEqualsThan
+            | TokenTypes::LogicalGreaterThan
+            | TokenTypes::LogicalGreaterOrEqualsThan => {
+                match (&e_left.literal_type, &e_right.literal_type) {
+                    (l, r) if is_numeric(l) && is_numeric(r) => {
+                        format!("({} {} {})", e_left.value, operator, e_right.value)
+                    }
+                    // by code point
+                    (VariableTypes::Chr, VariableTypes::Chr) => {
+                        format!("({} {} {})", e_left.value, operator, e_right.value)
+                    }
+                    (VariableTypes::Str, VariableTypes::Str) => {
+                        self.error_expr(
+                            format!(
+                                "Cannot compare strings with '{}', did you mean '=='?",
+                                operator
+                            )
+                            .as_str(),
+                        );
+                        abort()
+                    }
+                    (l, r) => {
+                        self.error_expr(format!("Cannot compare '{}' with '{}'", l, r).as_str());
+                        abort()
+                    }
+                }
+            }
+            TokenTypes::LogicalAnd | TokenTypes::LogicalOr => {
+                match (&e_left.literal_type, &e_right.literal_type) {
+                    (VariableTypes::Boo, VariableTypes::Boo) => {
+                        let c_operator = match operator {
+                            TokenTypes::LogicalAnd => "&&",
+                            _ => "||",
+                        };
+                        format!("({} {} {})", e_left.value, c_operator, e_right.value)
+                    }
+                    _ => {
+                        self.error_expr(
+                            format!("Cannot use '{}' on non boolean values", operator).as_str(),
+                        );
+                        abort()
+                    }
+                }
+            }
+            _ => {
+                eprintln!("Unknown error related to logical expressions");
+                abort()
+            }
+        };
+
+        Expr {
+            value,
+            literal_type: VariableTypes::Boo,
+        }
+    }
+
+    fn eval_binary(&mut self, operator: &TokenTypes, e_left: Expr, e_right: Expr) -> Expr {
+        let l_type = &e_left.literal_type;
+        let r_type = &e_right.literal_type;
+
+        if let TokenTypes::BitwiseAnd
+        | TokenTypes::BitwiseOr
+        | TokenTypes::BitwiseXor
+        | TokenTypes::BitwiseShiftLeft
+        | TokenTypes::BitwiseShiftRight = operator
+        {
+            if !l_type.eq(&VariableTypes::Int) || !r_type.eq(&VariableTypes::Int) {
+                self.error_expr(
+                    format!(
+                        "Cannot use '{}' on '{}' and '{}', only on 'i32'",
+                        operator, l_type, r_type
+                    )
+                    .as_str(),
+                );
+                abort()
+            }
+            // the amount is taken modulo 32, shifting by more than the bits of an int is undefined in C
+            let value = match operator {
+                TokenTypes::BitwiseShiftLeft => format!(
+                    "((int)((unsigned){} << ({} & 31)))",
+                    e_left.value, e_right.value
+                ),
+                TokenTypes::BitwiseShiftRight => {
+                    format!("({} >> ({} & 31))", e_left.value, e_right.value)
+                }
+                _ => format!("({} {} {})", e_left.value, operator, e_right.value),
+            };
+            return Expr {
+                value,
+                literal_type: VariableTypes::Int,
+            };
+        }
+
+        // numbers and chars are implicitly concatenated, other types must be cast with 'as str'
+        if operator.eq(&TokenTypes::BinaryPlus)
+            && (l_type.eq(&VariableTypes::Str) || r_type.eq(&VariableTypes::Str))
+        {
+            return match (l_type, r_type) {
+                (
+                    VariableTypes::Str
+                    | VariableTypes::Int
+                    | VariableTypes::Flo
+                    | VariableTypes::Chr,
+                    VariableTypes::Str
+                    | VariableTypes::Int
+                    | VariableTypes::Flo
+                    | VariableTypes::Chr,
+                ) => Expr {
+                    value: format!(
+                        "concat({}, {})",
+                        self.stringify(&e_left).unwrap_or_default(),
+                        self.stringify(&e_right).unwrap_or_default()
+                    ),
+                    literal_type: VariableTypes::Str,
+                },
+                _ => {
+                    self.error_expr(
+                        format!("Cannot concatenate '{}' with '{}'", l_type, r_type).as_str(),
+                    );
+                    abort()
+                }
+            };
+        }
+
+        if !is_numeric(l_type) || !is_numeric(r_type) {
+            let verb = match operator {
+                TokenTypes::BinaryPlus => "add",
+                TokenTypes::BinaryMinus => "subtract",
+                TokenTypes::BinaryMultiply => "multiply",
+                TokenTypes::BinaryDivision => "divide",
+                TokenTypes::BinaryRest => "take modulo of",
+                _ => {
+                    self.error_expr("Unknown error related to binary operations");
+                    abort()
+                }
+            };
+            self.error_expr(format!("Cannot {} '{}' with '{}'", verb, l_type, r_type).as_str());
+            abort()
+        }
+
+        let literal_type = if l_type.eq(&VariableTypes::Int) && r_type.eq(&VariableTypes::Int) {
+            VariableTypes::Int
+        } else {
+            VariableTypes::Flo
+        };
+
+        let value = match (operator, &literal_type) {
+            // C has no '%' for doubles
+            (TokenTypes::BinaryRest, VariableTypes::Flo) => {
+                format!("flo_rest({}, {})", e_left.value, e_right.value)
+            }
+            _ => format!("({} {} {})", e_left.value, operator, e_right.value),
+        };
+
+        Expr {
+            value,
+            literal_type,
+        }
+    }
+
+    /// converts a value to its C string representation
+    fn stringify(&self, expr: &Expr) -> Option<String> {
+        match expr.literal_type {
+            VariableTypes::Str => Some(expr.value.to_owned()),
+            VariableTypes::Int => Some(format!("int_to_str({})", expr.value)),
+            VariableTypes::Flo => Some(format!("flo_to_str({})", expr.value)),
+            VariableTypes::Boo => Some(format!("bool_to_str({})", expr.value)),
+            VariableTypes::Chr => Some(format!("char_to_str({})", expr.value)),
+            _ => None,
+        }
+    }
+
+    fn is_assignable(&self, target: &VariableTypes, value: &VariableTypes) -> bool {
+        match (target, value) {
+            (t, v) if t.eq(v) => true,
+            (VariableTypes::Flo, VariableTypes::Int) => true,
+            (VariableTypes::Optional(_), VariableTypes::Nul) => true,
+            (VariableTypes::Optional(t), v) => self.is_assignable(t, v),
+            _ => false,
+        }
+    }
+
+    /// converts a value so it can be stored as the target type, None if it cannot be
+    fn coerce(&mut self, expr: &Expr, target: &VariableTypes) -> Option<String> {
+        match (target, &expr.literal_type) {
+            (t, v) if t.eq(v) => Some(expr.value.to_owned()),
+            (VariableTypes::Flo, VariableTypes::Int) => Some(expr.value.to_owned()),
+            (VariableTypes::Optional(_), VariableTypes::Nul) => {
+                Some(format!("({}){{0}}", self.get_c_type(target)))
+            }
+            (VariableTypes::Optional(t), _) => {
+                let value = self.coerce(expr, t)?;
+                Some(format!("({}){{true, {}}}", self.get_c_type(target), value))
+            }
+            _ => None,
+        }
+    }
+
+    /// value of a 'mut' variable declared without one, only optionals start as null
+    fn default_value(&mut self, r#type: &VariableTypes) -> String {
+        match r#type {
+            VariableTypes::Str => String::from("\"\""),
+            VariableTypes::Arr(_) => String::from("vec_new(0, 0, NULL)"),
+            VariableTypes::Nul => String::from("NULL"),
+            VariableTypes::Optional(_) => String::from("{0}"),
+            VariableTypes::Obj(name) if self.get_enum(name).is_some() => {
+                self.error_expr(
+                    format!("Variables of enum type '{}' must be given a value", name).as_str(),
+                );
+                abort()
+            }
+            VariableTypes::Func { .. } => {
+                self.error_expr(
+                    format!("Variables of type '{}' must be given a value", r#type).as_str(),
+                );
+                abort()
+            }
+            VariableTypes::Obj(name) => {
+                let fields = match self.get_struct(name) {
+                    Some(s) => s.fields.to_owned(),
+                    None => Vec::new(),
+                };
+                let values: Vec<String> = fields
+                    .iter()
+                    .map(|f| format!(".{} = {}", c_name(&f.name), self.default_value(&f.r#type)))
+                    .collect();
+                format!("(({}){{{}}})", self.get_c_type(r#type), values.join(", "))
+            }
+            _ => String::from("0"),
+        }
+    }
+
+    /// names of the optional variables the condition proves to not be null,
+    /// when it is true and when it is false
+    fn narrowings(&self, condition: &Expression) -> (Vec<String>, Vec<String>) {
+        match condition {
+            Expression::Logical {
+                operator: operator @ (TokenTypes::LogicalEquals | TokenTypes::LogicalDifferent),
+                left,
+                right,
+            } => {
+                let name = match (left.as_ref(), right.as_ref()) {
+                    (
+                        Expression::Identifier(name),
+                        Expression::Literal {
+                            r#type: LiteralTypes::Null,
+                            ..
+                        },
+                    )
+                    | (
+                        Expression::Literal {
+                            r#type: LiteralTypes::Null,
+                            ..
+                        },
+                        Expression::Identifier(name),
+                    ) => name,
+                    _ => return (Vec::new(), Vec::new()),
+                };
+
+                match self.get_variable(name) {
+                    Some(Variable {
+                        var_type: VariableTypes::Optional(_),
+                        ..
+                    }) => match operator {
+                        TokenTypes::LogicalDifferent => (vec![name.to_owned()], Vec::new()),
+                        _ => (Vec::new(), vec![name.to_owned()]),
+                    },
+                    _ => (Vec::new(), Vec::new()),
+                }
+            }
+            Expression::Logical {
+                operator: TokenTypes::LogicalAnd,
+                left,
+                right,
+            }
+            | Expression::Binary {
+                operator: TokenTypes::BitwiseAnd,
+                left,
+                right,
+            } => (
+                [self.narrowings(left).0, self.narrowings(right).0].concat(),
+                Vec::new(),
+            ),
+            Expression::Logical {
+                operator: TokenTypes::LogicalOr,
+                left,
+                right,
+            }
+            | Expression::Binary {
+                operator: TokenTypes::BitwiseOr,
+                left,
+                right,
+            } => (
+                Vec::new(),
+                [self.narrowings(left).1, self.narrowings(right).1].concat(),
+            ),
+            Expression::Unary {
+                operator: TokenTypes::LogicalNot,
+                operand,
+            } => {
+                let (when_true, when_false) = self.narrowings(operand);
+                (when_false, when_true)
+            }
+            _ => (Vec::new(), Vec::new()),
+        }
+    }
+
+    /// shadows optional variables with their non null value in the current scope
+    fn narrow(&mut self, names: &[String]) {
+        for name in names {
+            // any function call may set a mut global to null again
+            if !self.variables.iter().any(|v| v.name.eq(name))
+                && self
+                    .get_variable(name)
+                    .is_some_and(|v| matches!(v.kind, VarDeclarationKind::Mutable))
+            {
+                continue;
+            }
+            if let Some(Variable {
+                var_type: VariableTypes::Optional(r#type),
+                kind,
+                c_name,
+                captured,
+                ..
+            }) = self.get_variable(name).cloned()
+            {
+                self.variables.push(Variable {
+                    name: name.to_owned(),
+                    var_type: *r#type,
+                    kind,
+                    c_name: format!("{}.value", c_name),
+                    narrowed: true,
+                    captured,
+                });
+            }
+        }
+    }
+
+    /// makes narrowed variables optional again until the end of the current scope
+    fn widen(&mut self, names: &[String]) {
+        for name in names {
+            if let Some(declared) = self.get_declared_variable(name).cloned() {
+                self.variables.push(Variable {
+                    name: name.to_owned(),
+                    narrowed: true,
+                    ..declared
+                });
+            }
+        }
+    }
+
+    fn error_expr(&self, message: &str) {
+        error(
+            self.position.line,
+            self.position.column,
+            message.to_string(),
+        );
+    }
+
+    fn warning_expr(&mut self, message: &str) {
+        let warning_at = (
+            self.position.line,
+            self.position.column,
+            message.to_string(),
+        );
+        if self.warnings.contains(&warning_at) {
+            return;
+        }
+        warning(
+            self.position.line,
+            self.position.column,
+            message.to_string(),
+        );
+        self.warnings.push(warning_at);
+    }
+
+    fn enter_scope(&mut self) {
+        self.scopes.push(self.variables.len());
+    }
+
+    fn exit_scope(&mut self) {
+        if let Some(len) = self.scopes.pop() {
+            self.variables.truncate(len);
+        }
+    }
+
+    fn declare_variable(&mut self, name: &str, r#type: &VariableTypes, kind: &VarDeclarationKind) {
+        if self
+            .module_name(&Expression::Identifier(name.to_owned()))
+            .is_some()
+        {
+            self.error_expr(
+                format!(
+                    "Variable '{}' has the same name as an imported module",
+                    name
+                )
+                .as_str(),
+            );
+            abort()
+        }
+        self.variables.push(Variable {
+            name: name.to_owned(),
+            var_type: r#type.to_owned(),
+            kind: kind.to_owned(),
+            c_name: c_name(name),
+            narrowed: false,
+            captured: false,
+        });
+    }
+
+    fn is_declared_in_scope(&self, name: &str) -> bool {
+        let scope_start = self.scopes.last().copied().unwrap_or(0);
+        self.variables[scope_start..]
+            .iter()
+            .any(|v| v.name == name && !v.narrowed)
+    }
+
+    fn get_variable(&self, name: &str) -> Option<&Variable> {
+        self.variables
+            .iter()
+            .rev()
+            .find(|v| v.name == name)
+            .or_else(|| self.get_global(&self.qualify(name)))
+    }
+
+    /// the variable as it was declared, ignoring narrowing
+    fn get_declared_variable(&self, name: &str) -> Option<&Variable> {
+        self.variables
+            .iter()
+            .rev()
+            .find(|v| v.name == name && !v.narrowed)
+            .or_else(|| self.get_global(&self.qualify(name)))
+    }
+
+    fn get_global(&self, name: &str) -> Option<&Variable> {
+        self.globals.iter().find(|g| g.name == name)
+    }
+
+    fn get_struct(&self, name: &str) -> Option<&Struct> {
+        self.structs.iter().find(|s| s.name == name)
+    }
+
+    fn get_enum(&self, name: &str) -> Option<&Enum> {
+        self.enums.iter().find(|e| e.name == name)
+    }
+
+    fn get_function(&self, name: &str) -> Option<&Function> {
+        self.functions.iter().find(|f| f.name == name)
+    }
+
+    fn indent(&self) -> String {
+        "    ".repeat(self.indentation)
+    }
+
+    fn c_signature(
+        &mut self,
+        name: &str,
+        r#type: &VariableTypes,
+        params: &Option<Vec<FuncParam>>,
+    ) -> String {
+        let c_params = match params {
+            Some(p) => p
+                .iter()
+                .map(|param| {
+                    let r#type = self.resolve_type(&param.r#type);
+                    self.c_declaration(&r#type, &c_name(&param.name))
+                })
+                .collect::<Vec<String>>()
+                .join(", "),
+            None => String::from("void"),
+        };
+
+        let c_type = match r#type {
+            VariableTypes::Nul => String::from("void"),
+            _ => {
+                let r#type = self.resolve_type(r#type);
+                self.get_c_type(&r#type)
+            }
+        };
+
+        format!("{} {}({})", c_type, c_name(name), c_params)
+    }
+
+    /// 'int foo' or 'char *foo'
+    fn c_declaration(&mut self, bline_type: &VariableTypes, name: &str) -> String {
+        let c_type = self.get_c_type(bline_type);
+        if c_type.ends_with('*') {
+            format!("{}{}", c_type, name)
+        } else {
+            format!("{} {}", c_type, name)
+        }
+    }
+
+    fn get_c_type(&mut self, bline_type: &VariableTypes) -> String {
+        match bline_type {
+            VariableTypes::Int => String::from("int"),
+            VariableTypes::Flo => String::from("double"),
+            VariableTypes::Str => String::from("char *"),
+            // the unicode code point, strs hold it as 1 to 4 UTF-8 bytes
+            VariableTypes::Chr => String::from("int"),
+            VariableTypes::Boo => String::from("bool"),
+            VariableTypes::Nul => String::from("void *"),
+            VariableTypes::Arr(_) => String::from("vec *"),
+            VariableTypes::Optional(r#type) => {
+                let name = format!("opt_{}", mangle(r#type));
+                if !self.definitions.iter().any(|(n, _)| n.eq(&name)) {
+                    let value = self.c_declaration(r#type, "value");
+                    self.definitions.push((
+                        name.to_owned(),
+                        format!(
+                            "typedef struct {{\n    bool some;\n    {};\n}} {};\n",
+                            value, name
+                        ),
+                    ));
+                }
+                name
+            }
+            VariableTypes::Func { params, r#type } => {
+                let name = mangle(bline_type);
+                if !self.definitions.iter().any(|(n, _)| n.eq(&name)) {
+                    let mut c_params = vec![String::from("void *")];
+                    for param in params {
+                        c_params.push(self.get_c_type(param));
+                    }
+                    let c_return = match r#type.as_ref() {
+                        VariableTypes::Nul => String::from("void"),
+                        t => self.get_c_type(t),
+                    };
+                    self.definitions.push((
+                        name.to_owned(),
+                        format!(
+                            "typedef struct {{\n    void *env;\n    {} (*call)({});\n}} {};\n",
+                            c_return,
+                            c_params.join(", "),
+                            name
+                        ),
+                    ));
+                }
+                name
+            }
+            VariableTypes::Obj(name) if self.get_enum(name).is_some() => {
+                if !self.definitions.iter().any(|(n, _)| n.eq(name)) {
+                    let r#enum = self.get_enum(name).cloned().unwrap();
+                    if self.defining.contains(name) {
+                        self.error_expr(
+                            format!(
+                                "Enum '{}' cannot contain itself, store it in a vec instead",
+                                name
+                            )
+                            .as_str(),
+                        );
+                        abort()
+                    }
+
+                    self.defining.push(name.to_owned());
+                    let tags: Vec<String> = r#enum
+                        .variants
+                        .iter()
+                        .map(|v| format!("{}_{}", c_name(name), v.name))
+                        .collect();
+                    let mut payloads = String::new();
+                    for variant in &r#enum.variants {
+                        let values: String = variant
+                            .payload
+                            .iter()
+                            .flatten()
+                            .enumerate()
+                            .map(|(i, t)| {
+                                format!("{}; ", self.c_declaration(t, &format!("_{}", i)))
+                            })
+                            .collect();
+                        if !values.is_empty() {
+                            payloads.push_str(&format!(
+                                "        struct {{ {}}} {};\n",
+                                values,
+                                c_name(&variant.name)
+                            ));
+                        }
+                    }
+                    self.defining.pop();
+
+                    let union = match payloads.is_empty() {
+                        true => String::new(),
+                        false => format!("    union {{\n{}    }} as;\n", payloads),
+                    };
+                    self.definitions.push((
+                        name.to_owned(),
+                        format!(
+                            "{}enum {{ {} }};\ntypedef struct {{\n    int tag;\n{}}} {};\n",
+                            c_doc(&r#enum.doc, ""),
+                            tags.join(", "),
+                            union,
+                            c_name(name)
+                        ),
+                    ));
+                }
+                c_name(name)
+            }
+            VariableTypes::Obj(name) => {
+                if !self.definitions.iter().any(|(n, _)| n.eq(name)) {
+                    let Some(r#struct) = self.get_struct(name).cloned() else {
+                        self.error_expr(format!("Unknown type '{}'", name).as_str());
+                        abort()
+                    };
+                    if self.defining.contains(name) {
+                        self.error_expr(
+                            format!(
+                                "Struct '{}' cannot contain itself, store it in a vec instead",
+                                name
+                            )
+                            .as_str(),
+                        );
+                        abort()
+                    }
+
+                    self.defining.push(name.to_owned());
+                    let fields: String = r#struct
+                        .fields
+                        .iter()
+                        .map(|f| {
+                            format!(
+                                "{}    {};\n",
+                                c_doc(&f.doc, "    "),
+                                self.c_declaration(&f.r#type, &c_name(&f.name))
+                            )
+                        })
+                        .collect();
+                    self.defining.pop();
+
+                    self.definitions.push((
+                        name.to_owned(),
+                        format!(
+                            "{}typedef struct {{\n{}}} {};\n",
+                            c_doc(&r#struct.doc, ""),
+                            fields,
+                            c_name(name)
+                        ),
+                    ));
+                }
+                c_name(name)
+            }
+        }
+    }
+
+    fn get_body(&self) -> &Vec<Statement> {
+        match &self.ast {
+            Statement::Program { body, .. } => body,
+            _ => abort(),
+        }
+    }
+}
+
+fn is_numeric(r#type: &VariableTypes) -> bool {
+    matches!(r#type, VariableTypes::Int | VariableTypes::Flo)
+}
+
+/// the common type of two vec elements, i32 widens to f64 and '[]' takes the other type
+fn unify_types(left: &VariableTypes, right: &VariableTypes) -> Option<VariableTypes> {
+    match (left, right) {
+        (l, r) if l.eq(r) => Some(l.to_owned()),
+        (VariableTypes::Int, VariableTypes::Flo) | (VariableTypes::Flo, VariableTypes::Int) => {
+            Some(VariableTypes::Flo)
+        }
+        (VariableTypes::Arr(l), VariableTypes::Arr(r)) => match (l.as_ref(), r.as_ref()) {
+            (VariableTypes::Nul, _) => Some(VariableTypes::Arr(r.to_owned())),
+            (_, VariableTypes::Nul) => Some(VariableTypes::Arr(l.to_owned())),
+            _ if l.eq(r) => Some(VariableTypes::Arr(l.to_owned())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// (parameters, return type) of a method built into str or vec
+fn builtin_method(
+    receiver: &VariableTypes,
+    name: &str,
+) -> Option<(Vec<VariableTypes>, VariableTypes)> {
+    match (receiver, name) {
+        (VariableTypes::Str, "len") => Some((vec![], VariableTypes::Int)),
+        (VariableTypes::Str, "upper" | "lower" | "trim") => Some((vec![], VariableTypes::Str)),
+        (VariableTypes::Str, "contains" | "starts_with" | "ends_with") => {
+            Some((vec![VariableTypes::Str], VariableTypes::Boo))
+        }
+        (VariableTypes::Str, "find") => Some((vec![VariableTypes::Str], VariableTypes::Int)),
+        (VariableTypes::Str, "slice") => Some((
+            vec![VariableTypes::Int, VariableTypes::Int],
+            VariableTypes::Str,
+        )),
+        (VariableTypes::Str, "split") => Some((
+            vec![VariableTypes::Str],
+            VariableTypes::Arr(Box::new(VariableTypes::Str)),
+        )),
+        (VariableTypes::Str, "replace") => Some((
+            vec![VariableTypes::Str, VariableTypes::Str],
+            VariableTypes::Str,
+        )),
+        (VariableTypes::Str, "chars") => {
+            Some((vec![], VariableTypes::Arr(Box::new(VariableTypes::Chr))))
+        }
+        (VariableTypes::Arr(_), "len") => Some((vec![], VariableTypes::Int)),
+        (VariableTypes::Arr(t), "push") => Some((vec![*t.to_owned()], VariableTypes::Nul)),
+        (VariableTypes::Arr(t), "pop") => Some((vec![], *t.to_owned())),
+        (VariableTypes::Arr(_), "slice") => Some((
+            vec![VariableTypes::Int, VariableTypes::Int],
+            receiver.to_owned(),
+        )),
+        (VariableTypes::Arr(_), "clear") => Some((vec![], VariableTypes::Nul)),
+        _ => None,
+    }
+}
+
+/// the type both sides of a ternary can be converted to, null makes the other side optional
+fn common_type(left: &VariableTypes, right: &VariableTypes) -> Option<VariableTypes> {
+    if let Some(r#type) = unify_types(left, right) {
+        return Some(r#type);
+    }
+    match (left, right) {
+        (VariableTypes::Nul, VariableTypes::Optional(_)) => Some(right.to_owned()),
+        (VariableTypes::Optional(_), VariableTypes::Nul) => Some(left.to_owned()),
+        (VariableTypes::Nul, t) | (t, VariableTypes::Nul) => {
+            Some(VariableTypes::Optional(Box::new(t.to_owned())))
+        }
+        (VariableTypes::Optional(l), VariableTypes::Optional(r)) => {
+            Some(VariableTypes::Optional(Box::new(unify_types(l, r)?)))
+        }
+        (VariableTypes::Optional(o), t) | (t, VariableTypes::Optional(o)) => {
+            Some(VariableTypes::Optional(Box::new(unify_types(o, t)?)))
+        }
+        _ => None,
+    }
+}
+
+/// unique name of a type that can be used inside C identifiers
+fn mangle(r#type: &VariableTypes) -> String {
+    match r#type {
+        VariableTypes::Arr(t) => format!("vec_{}", mangle(t)),
+        VariableTypes::Optional(t) => format!("opt_{}", mangle(t)),
+        VariableTypes::Obj(name) => c_name(name),
+        VariableTypes::Func { params, r#type } => {
+            let params: Vec<String> = params.iter().map(mangle).collect();
+            format!(
+                "func{}_{}_{}",
+                params.len(),
+                params.join("_"),
+                mangle(r#type)
+            )
+        }
+        t => t.to_string(),
+    }
+}
+
+/// if the C code reads the C name, 'x' is not read by 'x2'
+fn uses(c_code: &str, c_name: &str) -> bool {
+    c_code.match_indices(c_name).any(|(i, _)| {
+        !c_code[i + c_name.len()..].starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// the value of '-' in front of an integer literal, -2147483648 only fits in an i32 with its '-'
+fn negative_int_literal(operand: &Expression) -> Option<i32> {
+    match operand {
+        Expression::Literal {
+            r#type: LiteralTypes::Numeric,
+            value,
+        } => parse_int_literal(&format!("-{}", value.replace('_', ""))),
+        _ => None,
+    }
+}
+
+/// an i32 as a C expression, the lowest one cannot be written as a literal
+fn c_int(number: i32) -> String {
+    match number {
+        i32::MIN => String::from("(-2147483647 - 1)"),
+        n if n < 0 => format!("({})", n),
+        n => n.to_string(),
+    }
+}
+
+/// user names are prefixed so they cannot clash with C keywords or the runtime,
+/// 'math.Point' is 'bl_math__Point' in C
+fn c_name(name: &str) -> String {
+    match name {
+        "main" => name.to_owned(),
+        _ => format!("bl_{}", name.replace('.', "__")),
+    }
+}
+
+/// the str as a C string literal, anything that is not printable ASCII is written as an octal
+/// escape so the C file does not depend on its encoding
+fn c_string(value: &str) -> String {
+    let mut c_value = String::from("\"");
+    let mut previous = 0;
+    for byte in value.bytes() {
+        match byte {
+            b'"' => c_value.push_str("\\\""),
+            b'\\' => c_value.push_str("\\\\"),
+            b'\n' => c_value.push_str("\\n"),
+            b'\r' => c_value.push_str("\\r"),
+            b'\t' => c_value.push_str("\\t"),
+            // '??' followed by some characters is a trigraph in older C standards
+            b'?' if previous == b'?' => c_value.push_str("\\?"),
+            b' '..=b'~' => c_value.push(byte as char),
+            // always 3 digits so a digit after the escape is not read as part of it
+            _ => c_value.push_str(&format!("\\{:03o}", byte)),
+        }
+        previous = byte;
+    }
+    c_value.push('"');
+    c_value
+}
+
+/// the doc comment as C line comments, so it stays next to what it documents in the C code
+fn c_doc(doc: &Option<String>, indent: &str) -> String {
+    doc.iter()
+        .flat_map(|d| d.lines())
+        // a backslash at the end of a line would continue the C comment onto the next one,
+        // even with spaces after it
+        .map(|line| line.trim_end_matches(|c: char| c == '\\' || c.is_whitespace()))
+        .map(|line| format!("{}// {}", indent, line))
+        .map(|line| format!("{}\n", line.trim_end()))
+        .collect()
+}
+
+/// 'a' is a char now, so code that used single quotes for a one character str needs a hint
+fn quotes_hint(expected: &VariableTypes, found: &VariableTypes) -> &'static str {
+    let expects_str = match expected {
+        VariableTypes::Optional(t) => t.as_ref().eq(&VariableTypes::Str),
+        t => t.eq(&VariableTypes::Str),
+    };
+    match expects_str && found.eq(&VariableTypes::Chr) {
+        true => ", single quotes make a 'char', use double quotes for a 'str'",
+        false => "",
+    }
+}
+
+/// if the statement may change the value of the variable
+fn assigns(stmt: &Statement, name: &str) -> bool {
+    struct Assigns<'a> {
+        name: &'a str,
+        found: bool,
+    }
+
+    impl Visitor for Assigns<'_> {
+        fn visit_statement(&mut self, stmt: &Statement) {
+            match stmt {
+                Statement::VariableAlteration { name, .. } if name == self.name => {
+                    self.found = true
+                }
+                _ => walk_statement(self, stmt),
+            }
+        }
+
+        // a lambda can't assign the variables it captures
+        fn visit_expression(&mut self, _: &Expression) {}
+    }
+
+    let mut visitor = Assigns { name, found: false };
+    visitor.visit_statement(stmt);
+    visitor.found
+}
+
+/// the variable a place like 'foo.bar[0].baz' belongs to
+fn root_variable(expr: &Expression) -> Option<&String> {
+    match expr {
+        Expression::Identifier(name) => Some(name),
+        Expression::FieldAccess { object, .. } => root_variable(object),
+        Expression::ArrayAccess(access) => {
+            let mut access = access;
+            loop {
+                match access {
+                    ArrayAccess::Access { name, .. } => return Some(name),
+                    ArrayAccess::NestedAccess { access: a, .. } => access = a,
+                    ArrayAccess::ObjectAccess { object, .. } => return root_variable(object),
+                }
+            }
+        }
+        _ => None,
+    }
+}
+
+/// if the statement never lets the code after it run
+fn is_exit(stmt: &Statement) -> bool {
+    matches!(
+        stmt,
+        Statement::Return { .. } | Statement::Break { .. } | Statement::Continue { .. }
+    )
+}
+
+/// 'int *' or 'char **'
+fn c_pointer(c_type: &str) -> String {
+    if c_type.ends_with('*') {
+        format!("{}*", c_type)
+    } else {
+        format!("{} *", c_type)
     }
 }
-// I'M SO SORRY I DID NOT DISPLAY COL AND LINE ON ERRORS AT THIS FILE, my code before this was
-// GARBAGE and i cannot fix it now
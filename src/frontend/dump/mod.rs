@@ -0,0 +1,465 @@
+use crate::{
+    frontend::types::{
+        ArrayAccess, EnumVariant, Expression, FuncParam, LiteralTypes, Pattern, Start, Statement,
+        StructField, SwitchCase, Token, VarDeclarationKind, VariableTypes,
+    },
+    Diagnostic,
+};
+
+/// one token per line as line:column, type and value, up to the first error
+pub fn tokens(
+    tokens: impl Iterator<Item = Result<Token, Diagnostic>>,
+) -> Result<String, Diagnostic> {
+    let mut out = String::new();
+    for token in tokens {
+        let token = token?;
+        let position = format!("{}:{}", token.line_number, token.column_number);
+        out.push_str(&format!(
+            "{:<8} {:<16} {:?}\n",
+            position,
+            token.token_type.to_string(),
+            token.token_value
+        ));
+    }
+    Ok(out)
+}
+
+/// the tree as an S-expression, statements start a new line and expressions stay on one
+pub fn tree(program: &Statement) -> String {
+    let mut out = String::new();
+    statement(program).write(0, &mut out);
+    out.push('\n');
+    out
+}
+
+enum Sexp {
+    Atom(String),
+    List(Vec<Sexp>),
+    // a list that always gets its own line
+    Statement(Vec<Sexp>),
+}
+
+impl Sexp {
+    fn breaks(&self) -> bool {
+        match self {
+            Sexp::Atom(_) => false,
+            Sexp::List(items) | Sexp::Statement(items) => items
+                .iter()
+                .any(|item| matches!(item, Sexp::Statement(_)) || item.breaks()),
+        }
+    }
+
+    fn write(&self, indentation: usize, out: &mut String) {
+        let items = match self {
+            Sexp::Atom(text) => return out.push_str(text),
+            Sexp::List(items) | Sexp::Statement(items) => items,
+        };
+        out.push('(');
+        // the items before the first one that breaks stay on the line of the parenthesis
+        let mut own_line = false;
+        for (i, item) in items.iter().enumerate() {
+            own_line = own_line || matches!(item, Sexp::Statement(_)) || item.breaks();
+            if own_line {
+                out.push('\n');
+                out.push_str(&" ".repeat(indentation + 2));
+            } else if i > 0 {
+                out.push(' ');
+            }
+            item.write(indentation + 2, out);
+        }
+        out.push(')');
+    }
+}
+
+fn atom(text: impl ToString) -> Sexp {
+    Sexp::Atom(text.to_string())
+}
+
+fn position(start: &Start) -> Sexp {
+    atom(format!("{}:{}", start.line, start.column))
+}
+
+fn quoted(text: &str) -> Sexp {
+    atom(format!("{:?}", text))
+}
+
+fn doc(doc: &Option<String>) -> Option<Sexp> {
+    doc.as_ref()
+        .map(|doc| Sexp::List(vec![atom("doc"), quoted(doc)]))
+}
+
+fn r#type(r#type: &Option<VariableTypes>) -> Sexp {
+    match r#type {
+        Some(r#type) => atom(r#type),
+        None => atom("_"),
+    }
+}
+
+fn block(name: &str, block: &Option<Vec<Statement>>) -> Sexp {
+    let mut items = vec![atom(name)];
+    items.extend(block.iter().flatten().map(statement));
+    Sexp::List(items)
+}
+
+fn params(params: &Option<Vec<FuncParam>>) -> Sexp {
+    let mut items = vec![atom("params")];
+    items.extend(
+        params
+            .iter()
+            .flatten()
+            .map(|param| Sexp::List(vec![atom(&param.name), atom(&param.r#type)])),
+    );
+    Sexp::List(items)
+}
+
+fn field(field: &StructField) -> Sexp {
+    let mut items = vec![atom("field"), atom(&field.name), atom(&field.r#type)];
+    items.extend(doc(&field.doc));
+    Sexp::Statement(items)
+}
+
+fn variant(variant: &EnumVariant) -> Sexp {
+    let mut items = vec![atom("variant"), atom(&variant.name)];
+    items.extend(variant.payload.iter().flatten().map(atom));
+    items.extend(doc(&variant.doc));
+    Sexp::Statement(items)
+}
+
+fn case(case: &SwitchCase) -> Sexp {
+    let mut items = if case.patterns.is_empty() {
+        vec![atom("casenot"), position(&case.start)]
+    } else {
+        vec![atom("case"), position(&case.start)]
+    };
+    items.extend(case.patterns.iter().map(pattern));
+    items.push(block("block", &case.block));
+    Sexp::Statement(items)
+}
+
+fn pattern(pattern: &Pattern) -> Sexp {
+    match pattern {
+        Pattern::Number(number) => atom(number),
+        Pattern::String(string) => quoted(string),
+        Pattern::Variant {
+            r#enum,
+            variant,
+            bindings,
+        } => {
+            let mut items = vec![atom(format!("{}.{}", r#enum, variant))];
+            items.extend(bindings.iter().flatten().map(atom));
+            Sexp::List(items)
+        }
+    }
+}
+
+fn optional_statement(optional: &Option<Box<Statement>>) -> Sexp {
+    match optional {
+        Some(optional) => statement(optional),
+        None => atom("_"),
+    }
+}
+
+fn statement(statement: &Statement) -> Sexp {
+    let items = match statement {
+        Statement::Program { start, body } => {
+            let mut items = vec![atom("program"), position(start)];
+            items.extend(body.iter().map(self::statement));
+            items
+        }
+        Statement::Module {
+            start,
+            name,
+            file,
+            body,
+        } => {
+            let mut items = vec![atom("module"), position(start), atom(name), quoted(file)];
+            items.extend(body.iter().map(self::statement));
+            items
+        }
+        Statement::Use {
+            start,
+            name,
+            path,
+            module,
+        } => {
+            let mut items = vec![atom("use"), position(start), atom(name), quoted(path)];
+            items.extend(module.iter().map(atom));
+            items
+        }
+        Statement::VariableDeclaration {
+            start,
+            name,
+            kind,
+            r#type,
+            value,
+            doc,
+        } => {
+            let kind = match kind {
+                VarDeclarationKind::Immutable => "let",
+                VarDeclarationKind::Mutable => "mut",
+            };
+            let mut items = vec![
+                atom(kind),
+                position(start),
+                atom(name.as_deref().unwrap_or("_")),
+                self::r#type(r#type),
+            ];
+            items.extend(value.iter().map(expression));
+            items.extend(self::doc(doc));
+            items
+        }
+        Statement::FunctionDeclaration {
+            start,
+            name,
+            r#type,
+            params,
+            body,
+            doc,
+        } => {
+            let mut items = vec![
+                atom("func"),
+                position(start),
+                atom(name),
+                self::params(params),
+                atom(r#type),
+            ];
+            items.extend(self::doc(doc));
+            items.push(block("body", body));
+            items
+        }
+        Statement::StructDeclaration {
+            start,
+            name,
+            fields,
+            doc,
+        } => {
+            let mut items = vec![atom("obj"), position(start), atom(name)];
+            items.extend(self::doc(doc));
+            items.extend(fields.iter().map(field));
+            items
+        }
+        Statement::EnumDeclaration {
+            start,
+            name,
+            variants,
+            doc,
+        } => {
+            let mut items = vec![atom("enum"), position(start), atom(name)];
+            items.extend(self::doc(doc));
+            items.extend(variants.iter().map(variant));
+            items
+        }
+        Statement::If {
+            start,
+            condition,
+            block,
+            alternate,
+        }
+        | Statement::ElseIf {
+            start,
+            condition,
+            block,
+            alternate,
+        } => {
+            let name = match statement {
+                Statement::If { .. } => "if",
+                _ => "elseif",
+            };
+            let mut items = vec![
+                atom(name),
+                position(start),
+                expression(condition),
+                self::block("block", block),
+            ];
+            items.extend(alternate.iter().map(|alternate| self::statement(alternate)));
+            items
+        }
+        Statement::Else { start, block } => {
+            vec![atom("else"), position(start), self::block("block", block)]
+        }
+        Statement::While { start, test, block } => vec![
+            atom("while"),
+            position(start),
+            expression(test),
+            self::block("block", block),
+        ],
+        Statement::For {
+            start,
+            variable,
+            test,
+            variable_update,
+            block,
+        } => vec![
+            atom("for"),
+            position(start),
+            optional_statement(variable),
+            test.as_ref().map_or(atom("_"), expression),
+            optional_statement(variable_update),
+            self::block("block", block),
+        ],
+        Statement::ForIn {
+            start,
+            name,
+            values,
+            block,
+        } => vec![
+            atom("for-in"),
+            position(start),
+            atom(name),
+            expression(values),
+            self::block("block", block),
+        ],
+        Statement::Switch {
+            start,
+            subject,
+            cases,
+        } => {
+            let mut items = vec![atom("switch"), position(start), expression(subject)];
+            items.extend(cases.iter().map(case));
+            items
+        }
+        Statement::Break { start } => vec![atom("brk"), position(start)],
+        Statement::Continue { start } => vec![atom("cnt"), position(start)],
+        Statement::Return { start, expression } => {
+            let mut items = vec![atom("ret"), position(start)];
+            items.extend(expression.iter().map(self::expression));
+            items
+        }
+        Statement::VariableAlteration {
+            start,
+            name,
+            operator,
+            value,
+        } => vec![
+            atom(operator),
+            position(start),
+            atom(name),
+            expression(value),
+        ],
+        Statement::FunctionCall { start, call } => {
+            vec![atom("expr"), position(start), expression(call)]
+        }
+        Statement::PlaceAlteration {
+            start,
+            target,
+            operator,
+            value,
+        } => vec![
+            atom(operator),
+            position(start),
+            expression(target),
+            expression(value),
+        ],
+    };
+    Sexp::Statement(items)
+}
+
+fn arguments(head: Vec<Sexp>, arguments: &Option<Vec<Expression>>) -> Sexp {
+    let mut items = head;
+    items.extend(arguments.iter().flatten().map(expression));
+    Sexp::List(items)
+}
+
+fn array_access(access: &ArrayAccess) -> Sexp {
+    match access {
+        ArrayAccess::Access { name, index } => {
+            Sexp::List(vec![atom("index"), atom(name), expression(index)])
+        }
+        ArrayAccess::NestedAccess { access, index } => {
+            Sexp::List(vec![atom("index"), array_access(access), expression(index)])
+        }
+        ArrayAccess::ObjectAccess { object, index } => {
+            Sexp::List(vec![atom("index"), expression(object), expression(index)])
+        }
+    }
+}
+
+fn expression(expression: &Expression) -> Sexp {
+    match expression {
+        Expression::Identifier(name) => atom(name),
+        Expression::Binary {
+            operator,
+            left,
+            right,
+        }
+        | Expression::Logical {
+            operator,
+            left,
+            right,
+        } => Sexp::List(vec![
+            atom(operator),
+            self::expression(left),
+            self::expression(right),
+        ]),
+        Expression::Unary { operator, operand } => {
+            Sexp::List(vec![atom(operator), self::expression(operand)])
+        }
+        Expression::Literal { r#type, value } => match r#type {
+            LiteralTypes::String => quoted(value),
+            LiteralTypes::Character => atom(format!("{:?}", value.chars().next().unwrap_or(' '))),
+            LiteralTypes::Numeric | LiteralTypes::Boolean | LiteralTypes::Null => atom(value),
+        },
+        Expression::ArrayLiteral { elements } => arguments(vec![atom("vec")], elements),
+        Expression::ArrayAccess(access) => array_access(access),
+        Expression::Call { name, arguments } => {
+            self::arguments(vec![atom("call"), atom(name)], arguments)
+        }
+        Expression::Cast { expression, r#type } => {
+            Sexp::List(vec![atom("as"), self::expression(expression), atom(r#type)])
+        }
+        Expression::StructLiteral { name, fields } => {
+            let mut items = vec![atom("new"), atom(name)];
+            items.extend(
+                fields.iter().flatten().map(|field| {
+                    Sexp::List(vec![atom(&field.name), self::expression(&field.value)])
+                }),
+            );
+            Sexp::List(items)
+        }
+        Expression::FieldAccess { object, field } => {
+            Sexp::List(vec![atom("."), self::expression(object), atom(field)])
+        }
+        Expression::MethodCall {
+            object,
+            name,
+            arguments,
+        } => self::arguments(
+            vec![atom("method"), self::expression(object), atom(name)],
+            arguments,
+        ),
+        Expression::Ternary {
+            condition,
+            when_true,
+            when_false,
+        } => Sexp::List(vec![
+            atom("?"),
+            self::expression(condition),
+            self::expression(when_true),
+            self::expression(when_false),
+        ]),
+        Expression::Lambda {
+            params,
+            r#type,
+            body,
+        } => Sexp::List(vec![
+            atom("lambda"),
+            self::params(params),
+            atom(r#type),
+            block("body", body),
+        ]),
+        Expression::Interpolation(parts) => {
+            let mut items = vec![atom("interpolation")];
+            items.extend(parts.iter().map(self::expression));
+            Sexp::List(items)
+        }
+        Expression::Range {
+            from,
+            to,
+            inclusive,
+        } => Sexp::List(vec![
+            atom(if *inclusive { "..=" } else { ".." }),
+            self::expression(from),
+            self::expression(to),
+        ]),
+    }
+}
@@ -0,0 +1,484 @@
+use crate::{
+    frontend::{
+        lexer::Lexer,
+        types::{Token, TokenTypes as TType, TriviaKind},
+    },
+    Diagnostic,
+};
+
+/// the source in the style of docs/conventions, comments and line breaks are kept,
+/// only the spaces between tokens, the indentation and the blank lines change
+pub fn format(source: &str) -> Result<String, Diagnostic> {
+    let tokens = Lexer::lossless(source).collect::<Result<Vec<Token>, Diagnostic>>()?;
+    let mut formatter = Formatter {
+        tokens: &tokens,
+        lines: Vec::new(),
+        line: Line::default(),
+        open: Vec::new(),
+        leading: true,
+        previous: None,
+        after_comment: false,
+        operand: false,
+        unary: false,
+        generics: 0,
+        generic: Generic::None,
+        ternaries: vec![0],
+        in_function: false,
+    };
+    for index in 0..tokens.len() {
+        formatter.token(index);
+    }
+    Ok(formatter.finish())
+}
+
+#[derive(Default)]
+struct Line {
+    level: usize,
+    text: String,
+    blank_before: bool,
+    // only comments, they stay with the declaration below them
+    comment: bool,
+    // the line a top level function starts or ends at
+    function_start: bool,
+    function_end: bool,
+}
+
+#[derive(PartialEq)]
+enum Generic {
+    None,
+    // the '<' of vec<T>
+    Open,
+    // the '>' closing it
+    Close,
+}
+
+struct Formatter<'a> {
+    tokens: &'a [Token],
+    lines: Vec<Line>,
+    line: Line,
+    // the brackets not closed yet and the line each was opened at
+    open: Vec<(TType, usize)>,
+    // nothing but closing brackets on the line so far, they move the line back out
+    leading: bool,
+    // the last token on the current line
+    previous: Option<usize>,
+    after_comment: bool,
+    // the last token ends an operand, so a '-' after it subtracts instead of negating
+    operand: bool,
+    // the last token is a '-', '+', '!' or '~' in front of its operand
+    unary: bool,
+    // the vec< not closed yet
+    generics: usize,
+    generic: Generic,
+    // the '?' of ternaries waiting for their ':', one count for every bracket
+    ternaries: Vec<usize>,
+    in_function: bool,
+}
+
+impl Formatter<'_> {
+    fn token(&mut self, index: usize) {
+        let tokens = self.tokens;
+        let token = &tokens[index];
+        let mut new_lines = 0;
+        for trivia in &token.trivia {
+            match trivia.kind {
+                TriviaKind::NewLine => new_lines += 1,
+                TriviaKind::Whitespace => {}
+                TriviaKind::Comment | TriviaKind::BlockComment => {
+                    if new_lines > 0 || self.line.text.is_empty() {
+                        self.break_line(new_lines);
+                        self.start_line();
+                        self.line.comment = true;
+                    } else {
+                        self.line.text.push(' ');
+                    }
+                    self.line.text.push_str(&trivia.text);
+                    self.after_comment = true;
+                    new_lines = 0;
+                }
+            }
+        }
+        if token.token_type == TType::EOF {
+            return;
+        }
+        if new_lines > 0 {
+            self.break_line(new_lines);
+        }
+
+        let generic = self.generic_of(index);
+        if self.line.text.is_empty() {
+            self.start_line();
+            self.line.comment = token.token_type == TType::DocComment;
+        } else if self.after_comment || self.space_before(index, &generic) {
+            self.line.text.push(' ');
+        }
+        self.line.text.push_str(&token.text);
+        if token.token_type != TType::DocComment {
+            self.line.comment = false;
+        }
+        self.previous = Some(index);
+        self.after_comment = false;
+        self.brackets(index);
+        self.statements(index);
+
+        self.unary = match token.token_type {
+            TType::LogicalNot | TType::BitwiseNot => true,
+            TType::BinaryMinus | TType::BinaryPlus => !self.operand,
+            _ => false,
+        };
+        self.operand = generic == Generic::Close
+            || matches!(
+                token.token_type,
+                TType::Identifier
+                    | TType::NumberLiteral
+                    | TType::StringLiteral
+                    | TType::CharLiteral
+                    | TType::StringEnd
+                    | TType::True
+                    | TType::False
+                    | TType::Null
+                    | TType::RightParenthesis
+                    | TType::RightSquareBracket
+                    | TType::RightCurlyBrace
+                    | TType::Int
+                    | TType::Flo
+                    | TType::Str
+                    | TType::Chr
+                    | TType::Boo
+            );
+        self.generic = generic;
+    }
+
+    /// ends the current line, a blank line follows when the source had one
+    fn break_line(&mut self, new_lines: usize) {
+        if !self.line.text.is_empty() {
+            let line = std::mem::take(&mut self.line);
+            self.lines.push(line);
+        }
+        self.line.blank_before |= new_lines >= 2;
+        self.previous = None;
+        self.after_comment = false;
+    }
+
+    fn start_line(&mut self) {
+        self.line.level = self.level();
+        self.leading = true;
+    }
+
+    /// one level for every earlier line with brackets still open
+    fn level(&self) -> usize {
+        let mut level = 0;
+        let mut last_line = None;
+        for (_, line) in &self.open {
+            if last_line != Some(*line) {
+                level += 1;
+                last_line = Some(*line);
+            }
+        }
+        level
+    }
+
+    fn brackets(&mut self, index: usize) {
+        let token_type = &self.tokens[index].token_type;
+        match token_type {
+            TType::LeftParenthesis | TType::LeftSquareBracket | TType::LeftCurlyBrace => {
+                self.open.push((token_type.clone(), self.lines.len()));
+                self.ternaries.push(0);
+                self.leading = false;
+            }
+            TType::RightParenthesis | TType::RightSquareBracket | TType::RightCurlyBrace => {
+                let opened = self.open.pop();
+                if self.ternaries.len() > 1 {
+                    self.ternaries.pop();
+                }
+                // back to the line the bracket was opened at, a lambda's '}' inside a call
+                // lines up with the call and not with its arguments
+                if let (true, Some((_, line))) = (self.leading, opened) {
+                    self.line.level = self.lines.get(line).map_or(self.line.level, |l| l.level);
+                }
+            }
+            _ => self.leading = false,
+        }
+    }
+
+    /// where top level functions start and end, and where ternaries are waiting for a ':'
+    fn statements(&mut self, index: usize) {
+        let waiting = *self.ternaries.last().unwrap();
+        match self.tokens[index].token_type {
+            TType::QuestionMark if !self.is_optional(index) => {
+                *self.ternaries.last_mut().unwrap() += 1
+            }
+            TType::Colon if waiting > 0 => *self.ternaries.last_mut().unwrap() -= 1,
+            TType::Semicolon => {
+                *self.ternaries.last_mut().unwrap() = 0;
+                if self.open.is_empty()
+                    && self.in_function
+                    && self.tokens[index - 1].token_type == TType::RightCurlyBrace
+                {
+                    self.line.function_end = true;
+                    self.in_function = false;
+                }
+            }
+            TType::Function
+                if self.open.is_empty()
+                    && self.tokens.get(index + 1).map(|token| &token.token_type)
+                        == Some(&TType::Identifier) =>
+            {
+                self.line.function_start = true;
+                self.in_function = true;
+            }
+            _ => {}
+        }
+    }
+
+    /// whether the token is the '<' or '>' of a vec<T>
+    fn generic_of(&mut self, index: usize) -> Generic {
+        let token_type = &self.tokens[index].token_type;
+        let after_vec = self
+            .previous_token()
+            .is_some_and(|previous| previous.token_type == TType::Arr);
+        match token_type {
+            TType::LogicalSmallerThan if after_vec => {
+                self.generics += 1;
+                Generic::Open
+            }
+            TType::LogicalGreaterThan if self.generics > 0 => {
+                self.generics -= 1;
+                Generic::Close
+            }
+            TType::BitwiseShiftRight if self.generics > 1 => {
+                self.generics -= 2;
+                Generic::Close
+            }
+            _ => Generic::None,
+        }
+    }
+
+    fn previous_token(&self) -> Option<&Token> {
+        self.previous.map(|previous| &self.tokens[previous])
+    }
+
+    /// a '?' right after a type, i32? or Point?, instead of the one of a ternary
+    fn is_optional(&self, index: usize) -> bool {
+        self.tokens.get(index + 1).is_some_and(|next| {
+            matches!(
+                next.token_type,
+                TType::Assign
+                    | TType::Comma
+                    | TType::RightParenthesis
+                    | TType::Semicolon
+                    | TType::LogicalGreaterThan
+                    | TType::BitwiseShiftRight
+                    | TType::LeftCurlyBrace
+                    | TType::RightCurlyBrace
+                    | TType::SmallFunction
+            )
+        })
+    }
+
+    fn space_before(&self, index: usize, generic: &Generic) -> bool {
+        let Some(previous) = self.previous_token() else {
+            return false;
+        };
+        let token = &self.tokens[index];
+        let space = match (&previous.token_type, &token.token_type) {
+            (TType::StringStart | TType::StringMiddle, _)
+            | (_, TType::StringMiddle | TType::StringEnd) => false,
+            _ if self.unary || *generic != Generic::None || self.generic == Generic::Open => false,
+            (
+                _,
+                TType::Comma
+                | TType::Semicolon
+                | TType::RightParenthesis
+                | TType::RightSquareBracket
+                | TType::Dot
+                | TType::Range
+                | TType::RangeInclusive
+                | TType::BinaryIncrement
+                | TType::BinaryDecrement,
+            ) => false,
+            (
+                TType::LeftParenthesis
+                | TType::LeftSquareBracket
+                | TType::Dot
+                | TType::Range
+                | TType::RangeInclusive,
+                _,
+            ) => false,
+            (_, TType::Colon) => self.ternaries.last().is_some_and(|count| *count > 0),
+            (TType::Identifier | TType::Function, TType::LeftParenthesis) => false,
+            (
+                TType::Identifier
+                | TType::RightSquareBracket
+                | TType::RightParenthesis
+                | TType::StringLiteral
+                | TType::StringEnd,
+                TType::LeftSquareBracket,
+            ) => false,
+            (TType::LeftCurlyBrace, TType::RightCurlyBrace) => false,
+            (_, TType::QuestionMark) => !self.is_optional(index),
+            _ => true,
+        };
+        space || merges(&previous.text, &token.text)
+    }
+
+    /// the lines with the blank lines around top level functions added, indented with 4 spaces
+    fn finish(mut self) -> String {
+        self.break_line(0);
+        let mut lines = self.lines;
+        for index in 0..lines.len() {
+            if lines[index].function_start {
+                // the comments right above a function belong to it
+                let mut first = index;
+                while first > 0 && lines[first - 1].comment && !lines[first].blank_before {
+                    first -= 1;
+                }
+                lines[first].blank_before = true;
+            }
+            if lines[index].function_end && index + 1 < lines.len() {
+                lines[index + 1].blank_before = true;
+            }
+        }
+
+        let mut out = String::new();
+        for (index, line) in lines.iter().enumerate() {
+            if index > 0 && line.blank_before {
+                out.push('\n');
+            }
+            out.push_str(&"    ".repeat(line.level));
+            out.push_str(line.text.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// whether two tokens written without a space would be read as different tokens, - -x or a b
+fn merges(previous: &str, next: &str) -> bool {
+    let (Some(last), Some(first)) = (previous.chars().last(), next.chars().next()) else {
+        return false;
+    };
+    let word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    if word(last) && word(first) {
+        return true;
+    }
+    // 0.1.len() would be read as the number '0.1.'
+    if previous.starts_with(|c: char| c.is_ascii_digit()) && next == "." {
+        return true;
+    }
+    matches!(
+        format!("{}{}", last, first).as_str(),
+        "++" | "--"
+            | "+="
+            | "-="
+            | "*="
+            | "/="
+            | "%="
+            | "//"
+            | "/*"
+            | "*/"
+            | "&&"
+            | "||"
+            | "=="
+            | "!="
+            | "<="
+            | ">="
+            | "<<"
+            | ">>"
+            | "=>"
+            | ".."
+    )
+}
+
+/// the changes formatting makes to a file as a unified diff
+pub fn diff(name: &str, old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.split_inclusive('\n').collect();
+    let new: Vec<&str> = new.split_inclusive('\n').collect();
+
+    // the lines both have at the start and at the end are kept as they are
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_middle, new_middle) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    // longest common subsequence of the rest, common[i][j] is the one of old[i..] and new[j..]
+    let width = new_middle.len() + 1;
+    let mut common = vec![0u32; (old_middle.len() + 1) * width];
+    for i in (0..old_middle.len()).rev() {
+        for j in (0..new_middle.len()).rev() {
+            common[i * width + j] = match old_middle[i] == new_middle[j] {
+                true => common[(i + 1) * width + j + 1] + 1,
+                false => common[(i + 1) * width + j].max(common[i * width + j + 1]),
+            };
+        }
+    }
+
+    // ' ', '-' or '+' with the line and the position in old and new before it
+    let mut edits: Vec<(char, &str, usize, usize)> = Vec::new();
+    for (i, line) in old.iter().enumerate().take(prefix) {
+        edits.push((' ', line, i, i));
+    }
+    let (mut i, mut j) = (0, 0);
+    while i < old_middle.len() || j < new_middle.len() {
+        let (old_line, new_line) = (prefix + i, prefix + j);
+        if i < old_middle.len() && j < new_middle.len() && old_middle[i] == new_middle[j] {
+            edits.push((' ', old_middle[i], old_line, new_line));
+            i += 1;
+            j += 1;
+        } else if j == new_middle.len()
+            || (i < old_middle.len() && common[(i + 1) * width + j] >= common[i * width + j + 1])
+        {
+            edits.push(('-', old_middle[i], old_line, new_line));
+            i += 1;
+        } else {
+            edits.push(('+', new_middle[j], old_line, new_line));
+            j += 1;
+        }
+    }
+    for k in 0..suffix {
+        let (old_line, new_line) = (old.len() - suffix + k, new.len() - suffix + k);
+        edits.push((' ', old[old_line], old_line, new_line));
+    }
+
+    // every change with up to 3 lines around it, changes closer than that share a hunk
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (index, edit) in edits.iter().enumerate() {
+        if edit.0 == ' ' {
+            continue;
+        }
+        let (start, end) = (index.saturating_sub(3), (index + 4).min(edits.len()));
+        match hunks.last_mut() {
+            Some(hunk) if hunk.1 >= start => hunk.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", name, name);
+    for (start, end) in hunks {
+        let edits = &edits[start..end];
+        let old_count = edits.iter().filter(|edit| edit.0 != '+').count();
+        let new_count = edits.iter().filter(|edit| edit.0 != '-').count();
+        // an empty side is numbered by the line before it
+        let old_start = edits[0].2 + usize::from(old_count > 0);
+        let new_start = edits[0].3 + usize::from(new_count > 0);
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start, old_count, new_start, new_count
+        ));
+        for (kind, line, _, _) in edits {
+            out.push(*kind);
+            out.push_str(line);
+            if !line.ends_with('\n') {
+                out.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+    }
+    out
+}
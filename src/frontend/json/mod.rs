@@ -0,0 +1,1471 @@
+use crate::{
+    frontend::lexer::Lexer,
+    frontend::types::{
+        ArrayAccess, EnumVariant, Expression, FieldValue, FuncParam, LiteralTypes, Pattern, Start,
+        Statement, StructField, SwitchCase, TokenTypes, VarDeclarationKind, VariableTypes,
+    },
+    Diagnostic,
+};
+
+/// the version of the schema in docs/ast-json/ast-json.md, bumped on every change to it
+pub const VERSION: u32 = 2;
+
+const BINARY_OPERATORS: [TokenTypes; 10] = [
+    TokenTypes::BinaryPlus,
+    TokenTypes::BinaryMinus,
+    TokenTypes::BinaryMultiply,
+    TokenTypes::BinaryDivision,
+    TokenTypes::BinaryRest,
+    TokenTypes::BitwiseAnd,
+    TokenTypes::BitwiseOr,
+    TokenTypes::BitwiseXor,
+    TokenTypes::BitwiseShiftLeft,
+    TokenTypes::BitwiseShiftRight,
+];
+
+const LOGICAL_OPERATORS: [TokenTypes; 8] = [
+    TokenTypes::LogicalAnd,
+    TokenTypes::LogicalOr,
+    TokenTypes::LogicalEquals,
+    TokenTypes::LogicalDifferent,
+    TokenTypes::LogicalSmallerThan,
+    TokenTypes::LogicalGreaterThan,
+    TokenTypes::LogicalSmallerOrEqualsThan,
+    TokenTypes::LogicalGreaterOrEqualsThan,
+];
+
+const UNARY_OPERATORS: [TokenTypes; 3] = [
+    TokenTypes::BinaryMinus,
+    TokenTypes::LogicalNot,
+    TokenTypes::BitwiseNot,
+];
+
+const ASSIGN_OPERATORS: [TokenTypes; 8] = [
+    TokenTypes::Assign,
+    TokenTypes::AssignPlus,
+    TokenTypes::AssignMinus,
+    TokenTypes::AssignMultiply,
+    TokenTypes::AssignDivision,
+    TokenTypes::AssignRest,
+    TokenTypes::BinaryIncrement,
+    TokenTypes::BinaryDecrement,
+];
+
+/// the program as {"version": VERSION, "program": ...}
+pub fn to_json(program: &Statement) -> String {
+    let mut out = String::new();
+    object(vec![
+        ("version", Value::Number(VERSION).into()),
+        ("program", statement(program)),
+    ])
+    .write(0, &mut out);
+    out.push('\n');
+    out
+}
+
+/// reads what to_json wrote, errors point at the JSON text
+pub fn from_json(text: &str) -> Result<Statement, Diagnostic> {
+    let root = Reader::new(text).document()?;
+
+    let version = root.field("version")?.number()?;
+    if version != VERSION {
+        return Err(root.field("version")?.error(format!(
+            "The AST is version {}, this bline reads version {}",
+            version, VERSION
+        )));
+    }
+
+    let program = root.field("program")?;
+    match read_statement(program)? {
+        Statement::Program { start, body } => Ok(Statement::Program { start, body }),
+        _ => Err(program.error("The program must be a 'Program' statement".to_owned())),
+    }
+}
+
+#[derive(Debug)]
+enum Value {
+    Null,
+    Boolean(bool),
+    Number(u32),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+// a value and where it starts in the text it was read from, 0:0 for values that are written
+#[derive(Debug)]
+struct Json {
+    value: Value,
+    line: u32,
+    column: u32,
+}
+
+impl From<Value> for Json {
+    fn from(value: Value) -> Self {
+        Json {
+            value,
+            line: 0,
+            column: 0,
+        }
+    }
+}
+
+fn object(members: Vec<(&str, Json)>) -> Json {
+    Value::Object(
+        members
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value))
+            .collect(),
+    )
+    .into()
+}
+
+fn string(text: &str) -> Json {
+    Value::String(text.to_owned()).into()
+}
+
+fn array<T>(items: &[T], write: impl Fn(&T) -> Json) -> Json {
+    Value::Array(items.iter().map(write).collect()).into()
+}
+
+fn optional<T>(value: Option<T>, write: impl Fn(T) -> Json) -> Json {
+    match value {
+        Some(value) => write(value),
+        None => Value::Null.into(),
+    }
+}
+
+// writing
+
+impl Json {
+    fn is_scalar(&self) -> bool {
+        !matches!(self.value, Value::Array(_) | Value::Object(_))
+    }
+
+    /// objects and arrays of scalars stay on one line, the rest get a line per member
+    fn write(&self, indentation: usize, out: &mut String) {
+        match &self.value {
+            Value::Null => out.push_str("null"),
+            Value::Boolean(boolean) => out.push_str(&boolean.to_string()),
+            Value::Number(number) => out.push_str(&number.to_string()),
+            Value::String(text) => write_string(text, out),
+            Value::Array(items) if items.is_empty() => out.push_str("[]"),
+            Value::Array(items) if items.iter().all(Json::is_scalar) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    item.write(indentation, out);
+                }
+                out.push(']');
+            }
+            Value::Array(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    new_line(indentation + 2, out);
+                    item.write(indentation + 2, out);
+                }
+                new_line(indentation, out);
+                out.push(']');
+            }
+            Value::Object(members) if members.iter().all(|(_, value)| value.is_scalar()) => {
+                out.push('{');
+                for (i, (name, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    write_string(name, out);
+                    out.push_str(": ");
+                    value.write(indentation, out);
+                }
+                out.push('}');
+            }
+            Value::Object(members) => {
+                out.push('{');
+                for (i, (name, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    new_line(indentation + 2, out);
+                    write_string(name, out);
+                    out.push_str(": ");
+                    value.write(indentation + 2, out);
+                }
+                new_line(indentation, out);
+                out.push('}');
+            }
+        }
+    }
+}
+
+fn new_line(indentation: usize, out: &mut String) {
+    out.push('\n');
+    out.push_str(&" ".repeat(indentation));
+}
+
+fn write_string(text: &str, out: &mut String) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn kind(kind: &str, mut members: Vec<(&str, Json)>) -> Json {
+    members.insert(0, ("kind", string(kind)));
+    object(members)
+}
+
+fn start(start: &Start) -> Json {
+    object(vec![
+        ("line", Value::Number(start.line).into()),
+        ("column", Value::Number(start.column).into()),
+    ])
+}
+
+fn block(block: &Option<Vec<Statement>>) -> Json {
+    optional(block.as_ref(), |block| array(block, statement))
+}
+
+fn params(params: &Option<Vec<FuncParam>>) -> Json {
+    optional(params.as_ref(), |params| {
+        array(params, |param| {
+            object(vec![
+                ("name", string(&param.name)),
+                ("type", variable_type(&param.r#type)),
+            ])
+        })
+    })
+}
+
+fn variable_type(r#type: &VariableTypes) -> Json {
+    match r#type {
+        VariableTypes::Int => kind("i32", vec![]),
+        VariableTypes::Flo => kind("f64", vec![]),
+        VariableTypes::Str => kind("str", vec![]),
+        VariableTypes::Chr => kind("char", vec![]),
+        VariableTypes::Nul => kind("null", vec![]),
+        VariableTypes::Boo => kind("bool", vec![]),
+        VariableTypes::Arr(element) => kind("vec", vec![("element", variable_type(element))]),
+        VariableTypes::Optional(r#type) => kind("optional", vec![("type", variable_type(r#type))]),
+        VariableTypes::Obj(name) => kind("obj", vec![("name", string(name))]),
+        VariableTypes::Func { params, r#type } => kind(
+            "func",
+            vec![
+                ("params", array(params, variable_type)),
+                ("type", variable_type(r#type)),
+            ],
+        ),
+    }
+}
+
+fn statement(statement: &Statement) -> Json {
+    match statement {
+        Statement::Program { start, body } => kind(
+            "Program",
+            vec![
+                ("start", self::start(start)),
+                ("body", array(body, self::statement)),
+            ],
+        ),
+        Statement::Module {
+            start,
+            name,
+            file,
+            body,
+        } => kind(
+            "Module",
+            vec![
+                ("start", self::start(start)),
+                ("name", string(name)),
+                ("file", string(file)),
+                ("body", array(body, self::statement)),
+            ],
+        ),
+        Statement::Use {
+            start,
+            name,
+            path,
+            module,
+        } => kind(
+            "Use",
+            vec![
+                ("start", self::start(start)),
+                ("name", string(name)),
+                ("path", string(path)),
+                ("module", optional(module.as_deref(), string)),
+            ],
+        ),
+        Statement::VariableDeclaration {
+            start,
+            name,
+            kind,
+            r#type,
+            value,
+            doc,
+        } => self::kind(
+            "VariableDeclaration",
+            vec![
+                ("start", self::start(start)),
+                ("name", optional(name.as_deref(), string)),
+                // not "kind", that names the node
+                (
+                    "mutable",
+                    Value::Boolean(matches!(kind, VarDeclarationKind::Mutable)).into(),
+                ),
+                ("type", optional(r#type.as_ref(), variable_type)),
+                ("value", optional(value.as_ref(), expression)),
+                ("doc", optional(doc.as_deref(), string)),
+            ],
+        ),
+        Statement::FunctionDeclaration {
+            start,
+            name,
+            r#type,
+            params,
+            body,
+            doc,
+        } => kind(
+            "FunctionDeclaration",
+            vec![
+                ("start", self::start(start)),
+                ("name", string(name)),
+                ("type", variable_type(r#type)),
+                ("params", self::params(params)),
+                ("body", block(body)),
+                ("doc", optional(doc.as_deref(), string)),
+            ],
+        ),
+        Statement::StructDeclaration {
+            start,
+            name,
+            fields,
+            doc,
+        } => kind(
+            "StructDeclaration",
+            vec![
+                ("start", self::start(start)),
+                ("name", string(name)),
+                ("fields", array(fields, struct_field)),
+                ("doc", optional(doc.as_deref(), string)),
+            ],
+        ),
+        Statement::EnumDeclaration {
+            start,
+            name,
+            variants,
+            doc,
+        } => kind(
+            "EnumDeclaration",
+            vec![
+                ("start", self::start(start)),
+                ("name", string(name)),
+                ("variants", array(variants, enum_variant)),
+                ("doc", optional(doc.as_deref(), string)),
+            ],
+        ),
+        Statement::If {
+            start,
+            condition,
+            block,
+            alternate,
+        }
+        | Statement::ElseIf {
+            start,
+            condition,
+            block,
+            alternate,
+        } => kind(
+            match statement {
+                Statement::If { .. } => "If",
+                _ => "ElseIf",
+            },
+            vec![
+                ("start", self::start(start)),
+                ("condition", expression(condition)),
+                ("block", self::block(block)),
+                ("alternate", optional(alternate.as_deref(), self::statement)),
+            ],
+        ),
+        Statement::Else { start, block } => kind(
+            "Else",
+            vec![("start", self::start(start)), ("block", self::block(block))],
+        ),
+        Statement::While { start, test, block } => kind(
+            "While",
+            vec![
+                ("start", self::start(start)),
+                ("test", expression(test)),
+                ("block", self::block(block)),
+            ],
+        ),
+        Statement::For {
+            start,
+            variable,
+            test,
+            variable_update,
+            block,
+        } => kind(
+            "For",
+            vec![
+                ("start", self::start(start)),
+                ("variable", optional(variable.as_deref(), self::statement)),
+                ("test", optional(test.as_ref(), expression)),
+                (
+                    "variable_update",
+                    optional(variable_update.as_deref(), self::statement),
+                ),
+                ("block", self::block(block)),
+            ],
+        ),
+        Statement::ForIn {
+            start,
+            name,
+            values,
+            block,
+        } => kind(
+            "ForIn",
+            vec![
+                ("start", self::start(start)),
+                ("name", string(name)),
+                ("values", expression(values)),
+                ("block", self::block(block)),
+            ],
+        ),
+        Statement::Switch {
+            start,
+            subject,
+            cases,
+        } => kind(
+            "Switch",
+            vec![
+                ("start", self::start(start)),
+                ("subject", expression(subject)),
+                ("cases", array(cases, switch_case)),
+            ],
+        ),
+        Statement::Break { start } => kind("Break", vec![("start", self::start(start))]),
+        Statement::Continue { start } => kind("Continue", vec![("start", self::start(start))]),
+        Statement::Return { start, expression } => kind(
+            "Return",
+            vec![
+                ("start", self::start(start)),
+                (
+                    "expression",
+                    optional(expression.as_ref(), self::expression),
+                ),
+            ],
+        ),
+        Statement::VariableAlteration {
+            start,
+            name,
+            operator,
+            value,
+        } => kind(
+            "VariableAlteration",
+            vec![
+                ("start", self::start(start)),
+                ("name", string(name)),
+                ("operator", string(&operator.to_string())),
+                ("value", expression(value)),
+            ],
+        ),
+        Statement::FunctionCall { start, call } => kind(
+            "FunctionCall",
+            vec![("start", self::start(start)), ("call", expression(call))],
+        ),
+        Statement::PlaceAlteration {
+            start,
+            target,
+            operator,
+            value,
+        } => kind(
+            "PlaceAlteration",
+            vec![
+                ("start", self::start(start)),
+                ("target", expression(target)),
+                ("operator", string(&operator.to_string())),
+                ("value", expression(value)),
+            ],
+        ),
+    }
+}
+
+fn struct_field(field: &StructField) -> Json {
+    object(vec![
+        ("name", string(&field.name)),
+        ("type", variable_type(&field.r#type)),
+        ("doc", optional(field.doc.as_deref(), string)),
+    ])
+}
+
+fn enum_variant(variant: &EnumVariant) -> Json {
+    object(vec![
+        ("name", string(&variant.name)),
+        (
+            "payload",
+            optional(variant.payload.as_ref(), |payload| {
+                array(payload, variable_type)
+            }),
+        ),
+        ("doc", optional(variant.doc.as_deref(), string)),
+    ])
+}
+
+fn switch_case(case: &SwitchCase) -> Json {
+    object(vec![
+        ("start", start(&case.start)),
+        ("patterns", array(&case.patterns, pattern)),
+        ("block", block(&case.block)),
+    ])
+}
+
+fn pattern(pattern: &Pattern) -> Json {
+    match pattern {
+        Pattern::Number(value) => kind("Number", vec![("value", string(value))]),
+        Pattern::String(value) => kind("String", vec![("value", string(value))]),
+        Pattern::Variant {
+            r#enum,
+            variant,
+            bindings,
+        } => kind(
+            "Variant",
+            vec![
+                ("enum", string(r#enum)),
+                ("variant", string(variant)),
+                (
+                    "bindings",
+                    optional(bindings.as_ref(), |bindings| {
+                        array(bindings, |binding| string(binding))
+                    }),
+                ),
+            ],
+        ),
+    }
+}
+
+fn arguments(arguments: &Option<Vec<Expression>>) -> Json {
+    optional(arguments.as_ref(), |arguments| array(arguments, expression))
+}
+
+fn array_access(access: &ArrayAccess) -> Json {
+    match access {
+        ArrayAccess::Access { name, index } => kind(
+            "Access",
+            vec![("name", string(name)), ("index", expression(index))],
+        ),
+        ArrayAccess::NestedAccess { access, index } => kind(
+            "NestedAccess",
+            vec![
+                ("access", array_access(access)),
+                ("index", expression(index)),
+            ],
+        ),
+        ArrayAccess::ObjectAccess { object, index } => kind(
+            "ObjectAccess",
+            vec![("object", expression(object)), ("index", expression(index))],
+        ),
+    }
+}
+
+fn expression(expression: &Expression) -> Json {
+    match expression {
+        Expression::Identifier(name) => kind("Identifier", vec![("name", string(name))]),
+        Expression::Binary {
+            operator,
+            left,
+            right,
+        }
+        | Expression::Logical {
+            operator,
+            left,
+            right,
+        } => kind(
+            match expression {
+                Expression::Binary { .. } => "Binary",
+                _ => "Logical",
+            },
+            vec![
+                ("operator", string(&operator.to_string())),
+                ("left", self::expression(left)),
+                ("right", self::expression(right)),
+            ],
+        ),
+        Expression::Unary { operator, operand } => kind(
+            "Unary",
+            vec![
+                ("operator", string(&operator.to_string())),
+                ("operand", self::expression(operand)),
+            ],
+        ),
+        Expression::Literal { r#type, value } => kind(
+            "Literal",
+            vec![
+                (
+                    "type",
+                    string(match r#type {
+                        LiteralTypes::Numeric => "Numeric",
+                        LiteralTypes::String => "String",
+                        LiteralTypes::Character => "Character",
+                        LiteralTypes::Boolean => "Boolean",
+                        LiteralTypes::Null => "Null",
+                    }),
+                ),
+                ("value", string(value)),
+            ],
+        ),
+        Expression::ArrayLiteral { elements } => {
+            kind("ArrayLiteral", vec![("elements", arguments(elements))])
+        }
+        Expression::ArrayAccess(access) => {
+            kind("ArrayAccess", vec![("access", array_access(access))])
+        }
+        Expression::Call { name, arguments } => kind(
+            "Call",
+            vec![
+                ("name", string(name)),
+                ("arguments", self::arguments(arguments)),
+            ],
+        ),
+        Expression::Cast { expression, r#type } => kind(
+            "Cast",
+            vec![
+                ("expression", self::expression(expression)),
+                ("type", variable_type(r#type)),
+            ],
+        ),
+        Expression::StructLiteral { name, fields } => kind(
+            "StructLiteral",
+            vec![
+                ("name", string(name)),
+                (
+                    "fields",
+                    optional(fields.as_ref(), |fields| {
+                        array(fields, |field| {
+                            object(vec![
+                                ("name", string(&field.name)),
+                                ("value", self::expression(&field.value)),
+                            ])
+                        })
+                    }),
+                ),
+            ],
+        ),
+        Expression::FieldAccess { object, field } => kind(
+            "FieldAccess",
+            vec![
+                ("object", self::expression(object)),
+                ("field", string(field)),
+            ],
+        ),
+        Expression::MethodCall {
+            object,
+            name,
+            arguments,
+        } => kind(
+            "MethodCall",
+            vec![
+                ("object", self::expression(object)),
+                ("name", string(name)),
+                ("arguments", self::arguments(arguments)),
+            ],
+        ),
+        Expression::Ternary {
+            condition,
+            when_true,
+            when_false,
+        } => kind(
+            "Ternary",
+            vec![
+                ("condition", self::expression(condition)),
+                ("when_true", self::expression(when_true)),
+                ("when_false", self::expression(when_false)),
+            ],
+        ),
+        Expression::Lambda {
+            params,
+            r#type,
+            body,
+        } => kind(
+            "Lambda",
+            vec![
+                ("params", self::params(params)),
+                ("type", variable_type(r#type)),
+                ("body", block(body)),
+            ],
+        ),
+        Expression::Interpolation(parts) => kind(
+            "Interpolation",
+            vec![("parts", array(parts, self::expression))],
+        ),
+        Expression::Range {
+            from,
+            to,
+            inclusive,
+        } => kind(
+            "Range",
+            vec![
+                ("from", self::expression(from)),
+                ("to", self::expression(to)),
+                ("inclusive", Value::Boolean(*inclusive).into()),
+            ],
+        ),
+    }
+}
+
+// reading
+
+/// how many arrays and objects can be inside each other, reading deeper ones would overflow the stack
+const MAX_DEPTH: usize = 256;
+
+struct Reader<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: u32,
+    column: u32,
+    // arrays and objects the reader is inside of
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(text: &'a str) -> Self {
+        Reader {
+            chars: text.chars().peekable(),
+            line: 1,
+            column: 1,
+            depth: 0,
+        }
+    }
+
+    fn error(&self, message: String) -> Diagnostic {
+        Diagnostic::new(self.line, self.column, message)
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .chars
+            .peek()
+            .is_some_and(|c| matches!(c, ' ' | '\t' | '\n' | '\r'))
+        {
+            self.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Diagnostic> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some(&c) if c == expected => {
+                self.next();
+                Ok(())
+            }
+            Some(&c) => Err(self.error(format!("Expected '{}', found '{}'", expected, c))),
+            None => Err(self.error(format!(
+                "Expected '{}', found the end of the file",
+                expected
+            ))),
+        }
+    }
+
+    /// a single value with nothing but whitespace after it
+    fn document(&mut self) -> Result<Json, Diagnostic> {
+        let value = self.value()?;
+        self.skip_whitespace();
+        match self.chars.peek().copied() {
+            Some(c) => Err(self.error(format!("Unexpected '{}' after the end of the AST", c))),
+            None => Ok(value),
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, Diagnostic> {
+        self.skip_whitespace();
+        let (line, column) = (self.line, self.column);
+        let value = match self.chars.peek().copied() {
+            Some('{') => self.nested(Self::object)?,
+            Some('[') => self.nested(Self::array)?,
+            Some('"') => Value::String(self.string()?),
+            Some(c) if c.is_ascii_digit() || c == '-' => self.number()?,
+            Some(c) if c.is_ascii_alphabetic() => self.keyword()?,
+            Some(c) => return Err(self.error(format!("Expected a value, found '{}'", c))),
+            None => {
+                return Err(self.error("Expected a value, found the end of the file".to_owned()))
+            }
+        };
+        Ok(Json {
+            value,
+            line,
+            column,
+        })
+    }
+
+    /// an array or object inside the ones being read
+    fn nested(
+        &mut self,
+        read: impl Fn(&mut Self) -> Result<Value, Diagnostic>,
+    ) -> Result<Value, Diagnostic> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(format!(
+                "Arrays and objects can only be nested {} deep",
+                MAX_DEPTH
+            )));
+        }
+        self.depth += 1;
+        let value = read(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn object(&mut self) -> Result<Value, Diagnostic> {
+        self.expect('{')?;
+        let mut members: Vec<(String, Json)> = Vec::new();
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&'}') {
+            self.next();
+            return Ok(Value::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.chars.peek() != Some(&'"') {
+                return Err(self.error("Expected the name of a field in quotes".to_owned()));
+            }
+            let name = self.string()?;
+            if members.iter().any(|(other, _)| other.eq(&name)) {
+                return Err(self.error(format!("Field '{}' is already set", name)));
+            }
+            self.expect(':')?;
+            members.push((name, self.value()?));
+
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(Value::Object(members)),
+                _ => return Err(self.error("Expected ',' or '}' after a field".to_owned())),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, Diagnostic> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&']') {
+            self.next();
+            return Ok(Value::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Value::Array(items)),
+                _ => return Err(self.error("Expected ',' or ']' after a value".to_owned())),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Diagnostic> {
+        self.expect('"')?;
+        let mut text = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(text),
+                Some('\\') => text.push(self.escape()?),
+                Some(c) if (c as u32) < 0x20 => {
+                    return Err(
+                        self.error("Control characters in strings must be escaped".to_owned())
+                    )
+                }
+                Some(c) => text.push(c),
+                None => return Err(self.error("String not terminated".to_owned())),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, Diagnostic> {
+        match self.next() {
+            Some('"') => Ok('"'),
+            Some('\\') => Ok('\\'),
+            Some('/') => Ok('/'),
+            Some('b') => Ok('\u{8}'),
+            Some('f') => Ok('\u{c}'),
+            Some('n') => Ok('\n'),
+            Some('r') => Ok('\r'),
+            Some('t') => Ok('\t'),
+            Some('u') => {
+                let high = self.hex()?;
+                if !(0xD800..0xDC00).contains(&high) {
+                    return char::from_u32(high)
+                        .ok_or_else(|| self.error("Unpaired surrogate in \\u escape".to_owned()));
+                }
+                // a code point above U+FFFF is written as two escapes
+                if self.next() != Some('\\') || self.next() != Some('u') {
+                    return Err(self.error("Unpaired surrogate in \\u escape".to_owned()));
+                }
+                let low = self.hex()?;
+                if !(0xDC00..0xE000).contains(&low) {
+                    return Err(self.error("Unpaired surrogate in \\u escape".to_owned()));
+                }
+                Ok(char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)).unwrap())
+            }
+            _ => Err(self.error("Unknown escape sequence".to_owned())),
+        }
+    }
+
+    fn hex(&mut self) -> Result<u32, Diagnostic> {
+        let mut value = 0;
+        for _ in 0..4 {
+            match self.next().and_then(|c| c.to_digit(16)) {
+                Some(digit) => value = value * 16 + digit,
+                None => return Err(self.error("Expected 4 hex digits after \\u".to_owned())),
+            }
+        }
+        Ok(value)
+    }
+
+    // the AST only has line and column numbers, so only whole numbers are read
+    fn number(&mut self) -> Result<Value, Diagnostic> {
+        let mut text = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !(c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.')) {
+                break;
+            }
+            text.push(c);
+            self.next();
+        }
+        match text.parse::<u32>() {
+            Ok(number) if !text.starts_with('+') => Ok(Value::Number(number)),
+            _ => Err(self.error(format!(
+                "Expected a whole number from 0 to {}, found '{}'",
+                u32::MAX,
+                text
+            ))),
+        }
+    }
+
+    fn keyword(&mut self) -> Result<Value, Diagnostic> {
+        let mut text = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_ascii_alphanumeric() {
+                break;
+            }
+            text.push(c);
+            self.next();
+        }
+        match text.as_str() {
+            "null" => Ok(Value::Null),
+            "true" => Ok(Value::Boolean(true)),
+            "false" => Ok(Value::Boolean(false)),
+            _ => Err(self.error(format!("Expected a value, found '{}'", text))),
+        }
+    }
+}
+
+impl Json {
+    fn error(&self, message: String) -> Diagnostic {
+        Diagnostic::new(self.line, self.column, message)
+    }
+
+    fn field(&self, name: &str) -> Result<&Json, Diagnostic> {
+        self.optional_field(name)?
+            .ok_or_else(|| self.error(format!("Missing field '{}'", name)))
+    }
+
+    /// a field that is missing or null
+    fn optional_field(&self, name: &str) -> Result<Option<&Json>, Diagnostic> {
+        let Value::Object(members) = &self.value else {
+            return Err(self.error("Expected an object".to_owned()));
+        };
+        Ok(members
+            .iter()
+            .find(|(other, _)| other.eq(name))
+            .map(|(_, value)| value)
+            .filter(|value| !matches!(value.value, Value::Null)))
+    }
+
+    fn optional<T>(
+        &self,
+        name: &str,
+        read: impl Fn(&Json) -> Result<T, Diagnostic>,
+    ) -> Result<Option<T>, Diagnostic> {
+        self.optional_field(name)?.map(read).transpose()
+    }
+
+    fn number(&self) -> Result<u32, Diagnostic> {
+        match self.value {
+            Value::Number(number) => Ok(number),
+            _ => Err(self.error("Expected a number".to_owned())),
+        }
+    }
+
+    fn boolean(&self) -> Result<bool, Diagnostic> {
+        match self.value {
+            Value::Boolean(boolean) => Ok(boolean),
+            _ => Err(self.error("Expected true or false".to_owned())),
+        }
+    }
+
+    fn string(&self) -> Result<String, Diagnostic> {
+        match &self.value {
+            Value::String(text) => Ok(text.to_owned()),
+            _ => Err(self.error("Expected a string".to_owned())),
+        }
+    }
+
+    /// the lines of a doc comment, which become C line comments so they cannot hold a '\r' or
+    /// any other control character that could end one early
+    fn doc(&self) -> Result<String, Diagnostic> {
+        let doc = self.string()?;
+        let allowed = |c: char| c == '\n' || c == '\t' || !c.is_control();
+        match doc.chars().all(allowed) {
+            true => Ok(doc),
+            false => Err(self.error(format!("{:?} is not a valid doc comment", doc))),
+        }
+    }
+
+    /// a name the lexer reads as an identifier, so it can't be a keyword or carry any C
+    fn name(&self) -> Result<String, Diagnostic> {
+        let name = self.string()?;
+        match is_name(&name) {
+            true => Ok(name),
+            false => Err(self.error(format!("'{}' is not a valid name", name))),
+        }
+    }
+
+    /// a name that can also be module.Name, like the names of types
+    fn qualified_name(&self) -> Result<String, Diagnostic> {
+        let name = self.string()?;
+        let valid = match name.split_once('.') {
+            Some((module, name)) => is_name(module) && is_name(name),
+            None => is_name(&name),
+        };
+        match valid {
+            true => Ok(name),
+            false => Err(self.error(format!("'{}' is not a valid name", name))),
+        }
+    }
+
+    /// modules are named after their files, which may be named like keywords
+    fn module_name(&self) -> Result<String, Diagnostic> {
+        let name = self.string()?;
+        let mut chars = name.chars();
+        let valid = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        match valid {
+            true => Ok(name),
+            false => Err(self.error(format!("'{}' is not a valid module name", name))),
+        }
+    }
+
+    /// the text of a number literal, as the lexer would read it from the source
+    fn number_literal(&self) -> Result<String, Diagnostic> {
+        let text = self.string()?;
+        // anything else could start a str, which the lexer may warn about
+        let starts_number = text.starts_with(|c: char| c.is_ascii_digit() || c == '.');
+        let mut tokens = Lexer::new(&text);
+        match (tokens.next(), tokens.next()) {
+            (Some(Ok(number)), Some(Ok(end)))
+                if starts_number
+                    && number.token_type.eq(&TokenTypes::NumberLiteral)
+                    && end.token_type.eq(&TokenTypes::EOF) =>
+            {
+                Ok(number.token_value)
+            }
+            _ => Err(self.error(format!("'{}' is not a number literal", text))),
+        }
+    }
+
+    fn array<T>(
+        &self,
+        read: impl Fn(&Json) -> Result<T, Diagnostic>,
+    ) -> Result<Vec<T>, Diagnostic> {
+        match &self.value {
+            Value::Array(items) => items.iter().map(read).collect(),
+            _ => Err(self.error("Expected an array".to_owned())),
+        }
+    }
+
+    fn kind(&self) -> Result<String, Diagnostic> {
+        self.field("kind")?.string()
+    }
+
+    fn unknown_kind(&self, what: &str) -> Diagnostic {
+        let kind = self.kind().unwrap_or_default();
+        self.error(format!("Unknown {} kind '{}'", what, kind))
+    }
+}
+
+fn read_doc(json: &Json) -> Result<String, Diagnostic> {
+    json.doc()
+}
+
+fn read_name(json: &Json) -> Result<String, Diagnostic> {
+    json.name()
+}
+
+/// if the lexer reads the text as a single identifier
+fn is_name(text: &str) -> bool {
+    if !text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return false;
+    }
+    let mut tokens = Lexer::new(text);
+    match (tokens.next(), tokens.next()) {
+        (Some(Ok(name)), Some(Ok(end))) => {
+            name.token_type.eq(&TokenTypes::Identifier)
+                && name.token_value.eq(text)
+                && end.token_type.eq(&TokenTypes::EOF)
+        }
+        _ => false,
+    }
+}
+
+fn read_start(json: &Json) -> Result<Start, Diagnostic> {
+    Ok(Start {
+        line: json.field("line")?.number()?,
+        column: json.field("column")?.number()?,
+    })
+}
+
+fn read_block(json: &Json) -> Result<Vec<Statement>, Diagnostic> {
+    json.array(read_statement)
+}
+
+fn read_boxed_statement(json: &Json) -> Result<Box<Statement>, Diagnostic> {
+    Ok(Box::new(read_statement(json)?))
+}
+
+fn read_boxed_expression(json: &Json) -> Result<Box<Expression>, Diagnostic> {
+    Ok(Box::new(read_expression(json)?))
+}
+
+fn read_operator(json: &Json, operators: &[TokenTypes]) -> Result<TokenTypes, Diagnostic> {
+    let text = json.string()?;
+    operators
+        .iter()
+        .find(|operator| operator.to_string().eq(&text))
+        .cloned()
+        .ok_or_else(|| json.error(format!("'{}' is not an operator here", text)))
+}
+
+fn read_params(json: &Json) -> Result<Vec<FuncParam>, Diagnostic> {
+    json.array(|param| {
+        Ok(FuncParam {
+            name: param.field("name")?.name()?,
+            r#type: read_type(param.field("type")?)?,
+        })
+    })
+}
+
+fn read_type(json: &Json) -> Result<VariableTypes, Diagnostic> {
+    Ok(match json.kind()?.as_str() {
+        "i32" => VariableTypes::Int,
+        "f64" => VariableTypes::Flo,
+        "str" => VariableTypes::Str,
+        "char" => VariableTypes::Chr,
+        "null" => VariableTypes::Nul,
+        "bool" => VariableTypes::Boo,
+        "vec" => VariableTypes::Arr(Box::new(read_type(json.field("element")?)?)),
+        "optional" => VariableTypes::Optional(Box::new(read_type(json.field("type")?)?)),
+        "obj" => VariableTypes::Obj(json.field("name")?.qualified_name()?),
+        "func" => VariableTypes::Func {
+            params: json.field("params")?.array(read_type)?,
+            r#type: Box::new(read_type(json.field("type")?)?),
+        },
+        _ => return Err(json.unknown_kind("type")),
+    })
+}
+
+fn read_statement(json: &Json) -> Result<Statement, Diagnostic> {
+    let start = || read_start(json.field("start")?);
+    let block = || json.optional("block", read_block);
+
+    Ok(match json.kind()?.as_str() {
+        "Program" => Statement::Program {
+            start: start()?,
+            body: read_block(json.field("body")?)?,
+        },
+        "Module" => Statement::Module {
+            start: start()?,
+            name: json.field("name")?.module_name()?,
+            file: json.field("file")?.string()?,
+            body: read_block(json.field("body")?)?,
+        },
+        "Use" => Statement::Use {
+            start: start()?,
+            name: json.field("name")?.name()?,
+            path: json.field("path")?.string()?,
+            module: json.optional("module", |module| module.module_name())?,
+        },
+        "VariableDeclaration" => {
+            let mutable = json.field("mutable")?.boolean()?;
+            Statement::VariableDeclaration {
+                start: start()?,
+                name: Some(json.field("name")?.name()?),
+                kind: match mutable {
+                    true => VarDeclarationKind::Mutable,
+                    false => VarDeclarationKind::Immutable,
+                },
+                r#type: Some(read_type(json.field("type")?)?),
+                // like in the source, only 'mut' variables can be declared without a value
+                value: match mutable {
+                    true => json.optional("value", read_expression)?,
+                    false => Some(read_expression(json.field("value")?)?),
+                },
+                doc: json.optional("doc", read_doc)?,
+            }
+        }
+        "FunctionDeclaration" => Statement::FunctionDeclaration {
+            start: start()?,
+            name: json.field("name")?.name()?,
+            r#type: read_type(json.field("type")?)?,
+            params: json.optional("params", read_params)?,
+            body: json.optional("body", read_block)?,
+            doc: json.optional("doc", read_doc)?,
+        },
+        "StructDeclaration" => Statement::StructDeclaration {
+            start: start()?,
+            name: json.field("name")?.name()?,
+            fields: json.field("fields")?.array(|field| {
+                Ok(StructField {
+                    name: field.field("name")?.name()?,
+                    r#type: read_type(field.field("type")?)?,
+                    doc: field.optional("doc", read_doc)?,
+                })
+            })?,
+            doc: json.optional("doc", read_doc)?,
+        },
+        "EnumDeclaration" => Statement::EnumDeclaration {
+            start: start()?,
+            name: json.field("name")?.name()?,
+            variants: json.field("variants")?.array(|variant| {
+                Ok(EnumVariant {
+                    name: variant.field("name")?.name()?,
+                    payload: variant.optional("payload", |payload| payload.array(read_type))?,
+                    doc: variant.optional("doc", read_doc)?,
+                })
+            })?,
+            doc: json.optional("doc", read_doc)?,
+        },
+        "If" => Statement::If {
+            start: start()?,
+            condition: read_expression(json.field("condition")?)?,
+            block: block()?,
+            alternate: json.optional("alternate", read_boxed_statement)?,
+        },
+        "ElseIf" => Statement::ElseIf {
+            start: start()?,
+            condition: read_expression(json.field("condition")?)?,
+            block: block()?,
+            alternate: json.optional("alternate", read_boxed_statement)?,
+        },
+        "Else" => Statement::Else {
+            start: start()?,
+            block: block()?,
+        },
+        "While" => Statement::While {
+            start: start()?,
+            test: read_expression(json.field("test")?)?,
+            block: block()?,
+        },
+        "For" => Statement::For {
+            start: start()?,
+            variable: json.optional("variable", read_boxed_statement)?,
+            test: json.optional("test", read_expression)?,
+            variable_update: json.optional("variable_update", read_boxed_statement)?,
+            block: block()?,
+        },
+        "ForIn" => Statement::ForIn {
+            start: start()?,
+            name: json.field("name")?.name()?,
+            values: read_expression(json.field("values")?)?,
+            block: block()?,
+        },
+        "Switch" => Statement::Switch {
+            start: start()?,
+            subject: read_expression(json.field("subject")?)?,
+            cases: json.field("cases")?.array(|case| {
+                Ok(SwitchCase {
+                    start: read_start(case.field("start")?)?,
+                    patterns: case.field("patterns")?.array(read_pattern)?,
+                    block: case.optional("block", read_block)?,
+                })
+            })?,
+        },
+        "Break" => Statement::Break { start: start()? },
+        "Continue" => Statement::Continue { start: start()? },
+        "Return" => Statement::Return {
+            start: start()?,
+            expression: json.optional("expression", read_expression)?,
+        },
+        "VariableAlteration" => Statement::VariableAlteration {
+            start: start()?,
+            name: json.field("name")?.name()?,
+            operator: read_operator(json.field("operator")?, &ASSIGN_OPERATORS)?,
+            value: read_expression(json.field("value")?)?,
+        },
+        "FunctionCall" => Statement::FunctionCall {
+            start: start()?,
+            call: read_expression(json.field("call")?)?,
+        },
+        "PlaceAlteration" => Statement::PlaceAlteration {
+            start: start()?,
+            target: read_expression(json.field("target")?)?,
+            operator: read_operator(json.field("operator")?, &ASSIGN_OPERATORS)?,
+            value: read_expression(json.field("value")?)?,
+        },
+        _ => return Err(json.unknown_kind("statement")),
+    })
+}
+
+fn read_pattern(json: &Json) -> Result<Pattern, Diagnostic> {
+    Ok(match json.kind()?.as_str() {
+        "Number" => Pattern::Number(json.field("value")?.string()?),
+        "String" => Pattern::String(json.field("value")?.string()?),
+        "Variant" => Pattern::Variant {
+            r#enum: json.field("enum")?.qualified_name()?,
+            variant: json.field("variant")?.name()?,
+            bindings: json.optional("bindings", |bindings| bindings.array(read_name))?,
+        },
+        _ => return Err(json.unknown_kind("pattern")),
+    })
+}
+
+fn read_array_access(json: &Json) -> Result<ArrayAccess, Diagnostic> {
+    Ok(match json.kind()?.as_str() {
+        "Access" => ArrayAccess::Access {
+            name: json.field("name")?.name()?,
+            index: read_boxed_expression(json.field("index")?)?,
+        },
+        "NestedAccess" => ArrayAccess::NestedAccess {
+            access: Box::new(read_array_access(json.field("access")?)?),
+            index: read_boxed_expression(json.field("index")?)?,
+        },
+        "ObjectAccess" => ArrayAccess::ObjectAccess {
+            object: read_boxed_expression(json.field("object")?)?,
+            index: read_boxed_expression(json.field("index")?)?,
+        },
+        _ => return Err(json.unknown_kind("array access")),
+    })
+}
+
+/// numbers and bools are written to the C code as they are, so they must be what the lexer reads
+fn read_literal_value(json: &Json, r#type: &LiteralTypes) -> Result<String, Diagnostic> {
+    let value = json.string()?;
+    let valid = match r#type {
+        LiteralTypes::Numeric => return json.number_literal(),
+        LiteralTypes::String => true,
+        LiteralTypes::Character => value.chars().count() == 1,
+        LiteralTypes::Boolean => matches!(value.as_str(), "true" | "false"),
+        LiteralTypes::Null => value.eq("null"),
+    };
+    match valid {
+        true => Ok(value),
+        false => Err(json.error(format!("'{}' is not a valid {:?} literal", value, r#type))),
+    }
+}
+
+fn read_expression(json: &Json) -> Result<Expression, Diagnostic> {
+    let arguments = |name: &str| json.optional(name, |arguments| arguments.array(read_expression));
+
+    Ok(match json.kind()?.as_str() {
+        "Identifier" => Expression::Identifier(json.field("name")?.name()?),
+        "Binary" => Expression::Binary {
+            operator: read_operator(json.field("operator")?, &BINARY_OPERATORS)?,
+            left: read_boxed_expression(json.field("left")?)?,
+            right: read_boxed_expression(json.field("right")?)?,
+        },
+        "Logical" => Expression::Logical {
+            operator: read_operator(json.field("operator")?, &LOGICAL_OPERATORS)?,
+            left: read_boxed_expression(json.field("left")?)?,
+            right: read_boxed_expression(json.field("right")?)?,
+        },
+        "Unary" => Expression::Unary {
+            operator: read_operator(json.field("operator")?, &UNARY_OPERATORS)?,
+            operand: read_boxed_expression(json.field("operand")?)?,
+        },
+        "Literal" => {
+            let r#type = match json.field("type")?.string()?.as_str() {
+                "Numeric" => LiteralTypes::Numeric,
+                "String" => LiteralTypes::String,
+                "Character" => LiteralTypes::Character,
+                "Boolean" => LiteralTypes::Boolean,
+                "Null" => LiteralTypes::Null,
+                _ => return Err(json.field("type")?.error("Unknown literal type".to_owned())),
+            };
+            let value = read_literal_value(json.field("value")?, &r#type)?;
+            Expression::Literal { r#type, value }
+        }
+        "ArrayLiteral" => Expression::ArrayLiteral {
+            elements: arguments("elements")?,
+        },
+        "ArrayAccess" => Expression::ArrayAccess(read_array_access(json.field("access")?)?),
+        "Call" => Expression::Call {
+            name: json.field("name")?.name()?,
+            arguments: arguments("arguments")?,
+        },
+        "Cast" => Expression::Cast {
+            expression: read_boxed_expression(json.field("expression")?)?,
+            r#type: read_type(json.field("type")?)?,
+        },
+        "StructLiteral" => Expression::StructLiteral {
+            name: json.field("name")?.qualified_name()?,
+            fields: json.optional("fields", |fields| {
+                fields.array(|field| {
+                    Ok(FieldValue {
+                        name: field.field("name")?.name()?,
+                        value: read_expression(field.field("value")?)?,
+                    })
+                })
+            })?,
+        },
+        "FieldAccess" => Expression::FieldAccess {
+            object: read_boxed_expression(json.field("object")?)?,
+            field: json.field("field")?.name()?,
+        },
+        "MethodCall" => Expression::MethodCall {
+            object: read_boxed_expression(json.field("object")?)?,
+            name: json.field("name")?.name()?,
+            arguments: arguments("arguments")?,
+        },
+        "Ternary" => Expression::Ternary {
+            condition: read_boxed_expression(json.field("condition")?)?,
+            when_true: read_boxed_expression(json.field("when_true")?)?,
+            when_false: read_boxed_expression(json.field("when_false")?)?,
+        },
+        "Lambda" => Expression::Lambda {
+            params: json.optional("params", read_params)?,
+            r#type: read_type(json.field("type")?)?,
+            body: json.optional("body", read_block)?,
+        },
+        "Interpolation" => Expression::Interpolation(json.field("parts")?.array(read_expression)?),
+        "Range" => Expression::Range {
+            from: read_boxed_expression(json.field("from")?)?,
+            to: read_boxed_expression(json.field("to")?)?,
+            inclusive: json.field("inclusive")?.boolean()?,
+        },
+        _ => return Err(json.unknown_kind("expression")),
+    })
+}
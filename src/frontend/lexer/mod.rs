@@ -1,75 +1,263 @@
-use crate::error;
-use crate::frontend::types::{Token, TokenTypes as TType};
-use std::process::exit;
+use crate::frontend::types::{Span, Token, TokenTypes as TType, Trivia, TriviaKind};
+use crate::{warning, Diagnostic};
+use std::collections::VecDeque;
 
+/// tokens are lexed as they are taken, but always from one source borrowed for the whole lexing,
+/// input that arrives in parts, like the lines typed into a REPL, cannot be added to a lexer
+/// and has to be lexed again from the start once it is complete
 #[derive(Debug)]
 pub struct Lexer<'a> {
+    source_code: &'a str,
     source_code_iter: std::str::Chars<'a>,
     current_char: char,
     current_line: u32,
     current_column: u32,
+    // position of the character before the current one, where a token ending here ends
+    previous_line: u32,
+    previous_column: u32,
     is_end_of_file: bool,
-    pub token_list: Vec<Token>,
+    // byte offset of the token being lexed
+    token_start: usize,
+    // keeps the source text of every token, and whitespace and comments as trivia
+    lossless: bool,
+    // trivia found since the last token, it goes to the next one
+    trivia: Vec<Trivia>,
+    // tokens lexed but not yet taken, a string with {expressions} adds several at once
+    tokens: VecDeque<Token>,
+    started: bool,
+    // after the EOF or an error there is nothing left to lex
+    finished: bool,
 }
 
 impl<'a> Lexer<'a> {
     pub fn new(source_code: &'a str) -> Self {
         Lexer {
+            source_code,
             source_code_iter: source_code.chars(),
-            token_list: Vec::new(),
+            tokens: VecDeque::new(),
+            started: false,
+            finished: false,
             is_end_of_file: false,
             current_char: '\0',
             current_line: 1,
             current_column: 0,
+            previous_line: 1,
+            previous_column: 0,
+            token_start: 0,
+            lossless: false,
+            trivia: Vec::new(),
         }
     }
 
-    pub fn scan_source_code(&mut self) {
-        // initializes the current_char to Ln 1 Col 1 char of the file
-        self.move_to_next_char();
-        while !self.is_end_of_file {
-            if self.is_whitespace() {
-                self.move_to_next_char();
-            } else {
-                if self.is_valid_initial_identifier() {
-                    let token = self.determine_alphabetic_token();
+    /// a lexer for tools that need the exact source back, see source_text, it leaves warnings
+    /// about the source to the compiler
+    pub fn lossless(source_code: &'a str) -> Self {
+        Lexer {
+            lossless: true,
+            ..Lexer::new(source_code)
+        }
+    }
+
+    /// lexes until at least one token is ready
+    fn scan_tokens(&mut self) -> Result<(), Diagnostic> {
+        if !self.started {
+            // initializes the current_char to Ln 1 Col 1 char of the file
+            self.move_to_next_char();
+            self.started = true;
+        }
+        while self.tokens.is_empty() {
+            if self.is_end_of_file {
+                // the trivia at the end of the file goes to the EOF
+                self.token_start = self.offset();
+                let eof = Token::new(
+                    TType::EOF,
+                    String::new(),
+                    self.current_line,
+                    self.current_column,
+                );
+                self.add_token_to_list(eof);
+                self.finished = true;
+                break;
+            }
+            self.scan_next()?;
+        }
+        Ok(())
+    }
+
+    /// moves past the current whitespace character or adds the token starting at it
+    fn scan_next(&mut self) -> Result<(), Diagnostic> {
+        self.token_start = self.offset();
+        if self.is_whitespace() && self.lossless {
+            self.determine_whitespace();
+        } else if self.is_whitespace() {
+            self.move_to_next_char();
+        } else if self.is_valid_string_literal() {
+            let token = self.determine_string_literal()?;
+            self.add_token_to_list(token);
+        } else if self.is_valid_initial_identifier() {
+            let token = self.determine_alphabetic_token();
+            self.add_token_to_list(token);
+        } else if self.is_valid_number_literal_initializer() {
+            let token = self.determine_number_literal()?;
+            self.add_token_to_list(token);
+        } else {
+            // this function moves to the char after the current one to check for
+            // 2 char long operators
+            let token = self.determine_token()?;
+            match token.token_type {
+                // '///' documents, '////' is a plain comment
+                TType::Comment
+                    if self.current_char == '/'
+                        && self.source_code_iter.clone().next() != Some('/') =>
+                {
+                    let token = self.determine_doc_comment(token);
                     self.add_token_to_list(token);
-                } else if self.is_valid_number_literal_initializer() {
-                    let token = self.determine_number_literal();
+                }
+                TType::Comment if self.lossless => {
+                    self.ignore_current_line();
+                    self.add_trivia(TriviaKind::Comment);
+                }
+                TType::BlockComment if self.lossless => {
+                    self.ignore_block_comment(&token)?;
+                    self.add_trivia(TriviaKind::BlockComment);
+                }
+                TType::Comment => {
                     self.add_token_to_list(token);
-                } else if self.is_valid_string_literal() {
-                    let token = self.determine_string_literal();
+                    self.ignore_current_line();
+                }
+                TType::BlockComment => {
+                    self.ignore_block_comment(&token)?;
+                    self.add_token_to_list(token);
+                }
+                _ => {
                     self.add_token_to_list(token);
-                } else {
-                    // this function moves to the char after the current one to check for
-                    // 2 char long operators
-                    let token = self.determine_token();
-                    match token.token_type {
-                        TType::Comment => {
-                            self.add_token_to_list(token);
-                            self.ignore_current_line();
-                        }
-                        _ => {
-                            self.add_token_to_list(token);
-                        }
-                    }
                 }
             }
         }
+        Ok(())
+    }
+
+    /// a new line, or the spaces and tabs up to the next character that is not one
+    fn determine_whitespace(&mut self) {
+        if self.is_new_line() {
+            let carriage_return = self.current_char == '\r';
+            self.move_to_next_char();
+            if carriage_return && self.current_char == '\n' && !self.is_end_of_file {
+                self.move_to_next_char();
+            }
+            self.add_trivia(TriviaKind::NewLine);
+            return;
+        }
+        while self.is_whitespace() && !self.is_new_line() && !self.is_end_of_file {
+            self.move_to_next_char();
+        }
+        self.add_trivia(TriviaKind::Whitespace);
+    }
+
+    /// the text from token_start up to the current character
+    fn add_trivia(&mut self, kind: TriviaKind) {
+        let span = self.span();
+        self.trivia.push(Trivia {
+            kind,
+            text: self.source_code[span.start..span.end].to_string(),
+            span,
+        });
+    }
+
+    /// byte offset of the current character, the length of the source once it is all read
+    fn offset(&self) -> usize {
+        match self.is_end_of_file {
+            true => self.source_code.len(),
+            false => {
+                self.source_code.len()
+                    - self.source_code_iter.as_str().len()
+                    - self.current_char.len_utf8()
+            }
+        }
+    }
+
+    /// from token_start up to, but not including, the current character
+    fn span(&self) -> Span {
+        Span {
+            start: self.token_start,
+            end: self.offset(),
+            end_line: self.previous_line,
+            end_column: self.previous_column,
+        }
     }
 
     fn ignore_current_line(&mut self) {
-        while !self.is_new_line() {
+        while !self.is_new_line() && !self.is_end_of_file {
             self.move_to_next_char();
         }
     }
 
+    /// moves past the '*/' closing the comment, every '/*' inside it needs its own '*/'
+    fn ignore_block_comment(&mut self, start: &Token) -> Result<(), Diagnostic> {
+        let mut depth = 1;
+        while depth > 0 {
+            if self.is_end_of_file {
+                return Err(Diagnostic::new(
+                    start.line_number,
+                    start.column_number,
+                    String::from("Block comment not terminated before the end of file"),
+                ));
+            }
+            let next_char = self.source_code_iter.clone().next();
+            match (self.current_char, next_char) {
+                ('/', Some('*')) => {
+                    depth += 1;
+                    self.move_to_next_char();
+                }
+                ('*', Some('/')) => {
+                    depth -= 1;
+                    self.move_to_next_char();
+                }
+                _ => {}
+            }
+            self.move_to_next_char();
+        }
+        Ok(())
+    }
+
+    /// the text after '///' up to the end of the line, without the space that follows '///'
+    fn determine_doc_comment(&mut self, start: Token) -> Token {
+        // the third '/'
+        self.move_to_next_char();
+        if self.current_char == ' ' {
+            self.move_to_next_char();
+        }
+
+        let mut text = String::new();
+        while !self.is_new_line() && !self.is_end_of_file {
+            text.push(self.current_char);
+            self.move_to_next_char();
+        }
+
+        Token::new(
+            TType::DocComment,
+            text,
+            start.line_number,
+            start.column_number,
+        )
+    }
+
+    /// a quote or the 'r' of a raw string
     fn is_valid_string_literal(&self) -> bool {
-        self.current_char == '\'' || self.current_char == '\"'
+        match self.current_char {
+            '\'' | '\"' => true,
+            'r' => matches!(self.source_code_iter.clone().next(), Some('\'' | '\"')),
+            _ => false,
+        }
     }
 
-    fn add_token_to_list(&mut self, token: Token) {
-        self.token_list.push(token)
+    fn add_token_to_list(&mut self, mut token: Token) {
+        token.span = self.span();
+        if self.lossless {
+            token.text = self.source_code[token.span.start..token.span.end].to_string();
+            token.trivia = std::mem::take(&mut self.trivia);
+        }
+        self.tokens.push_back(token)
     }
 
     fn is_valid_initial_identifier(&self) -> bool {
@@ -97,94 +285,288 @@ impl<'a> Lexer<'a> {
     }
 
     fn is_valid_number_literal_initializer(&self) -> bool {
-        self.is_number_digit() || self.current_char == '.'
+        // a '.' not followed by a digit is a field access, p.x
+        self.is_number_digit()
+            || (self.current_char == '.'
+                && self
+                    .source_code_iter
+                    .clone()
+                    .next()
+                    .is_some_and(|c| c.is_ascii_digit()))
     }
 
-    fn determine_string_literal(&mut self) -> Token {
+    /// lexes "text", r"raw text", """multi-line text""" and 'c' chars,
+    /// a string with {expressions} adds StringStart and StringMiddle tokens around the
+    /// tokens of each expression and returns the StringEnd
+    fn determine_string_literal(&mut self) -> Result<Token, Diagnostic> {
         let initial_column = self.current_column;
         let initial_line = self.current_line;
+        let raw = self.current_char == 'r';
+        if raw {
+            self.move_to_next_char();
+        }
         let literal_initializer = self.current_char;
-        self.move_to_next_char();
-        let mut prev_char = self.current_char;
-        self.move_to_next_char();
-        let mut current_char = self.current_char;
+        let mut following = self.source_code_iter.clone();
+        let multi_line = following.next() == Some(literal_initializer)
+            && following.next() == Some(literal_initializer);
+        let delimiter = match multi_line {
+            true => literal_initializer.to_string().repeat(3),
+            false => literal_initializer.to_string(),
+        };
+        for _ in 0..delimiter.len() {
+            self.move_to_next_char();
+        }
+        // the new line right after the opening quotes is not part of the string
+        if multi_line && self.current_char == '\r' {
+            self.move_to_next_char();
+        }
+        if multi_line && self.current_char == '\n' {
+            self.move_to_next_char();
+        }
 
-        let mut string_literal: Vec<char> = vec![literal_initializer];
+        let mut string_literal = String::new();
+        // where the text after the last interpolated expression starts
+        let mut part_line = initial_line;
+        let mut part_column = initial_column;
+        let mut interpolated = false;
 
         loop {
-            if self.is_new_line() {
-                if prev_char == literal_initializer {
-                    error(
-                        initial_line,
-                        initial_column,
-                        format!("Missing ';' at the end of String literal"),
-                    );
-                    exit(1)
-                }
-                error(
-                    initial_line,
-                    initial_column,
-                    format!(
-                        "String literal '{}' must be terminated within the same line",
-                        literal_initializer
-                    ),
-                );
-                exit(1)
-            }
             if self.is_end_of_file {
-                error(
+                return Err(Diagnostic::new(
                     initial_line,
                     initial_column,
                     format!(
                         "String literal '{}' not terminated before the end of file",
-                        literal_initializer
+                        delimiter
                     ),
-                );
-                exit(1)
+                ));
+            }
+            if self.is_new_line() {
+                if !multi_line {
+                    return Err(Diagnostic::new(
+                        initial_line,
+                        initial_column,
+                        format!(
+                            "String literal '{}' must be terminated within the same line, use '{}' for multi-line strings",
+                            delimiter,
+                            delimiter.repeat(3)
+                        ),
+                    ));
+                }
+                if self.current_char == '\n' {
+                    string_literal.push('\n');
+                }
+                self.move_to_next_char();
+                continue;
             }
 
-            if prev_char == '\\' {
-                match get_escaped_char(current_char) {
-                    Ok(character) => {
-                        string_literal.push(character);
-                        self.move_to_next_char();
-                        prev_char = self.current_char;
-                        self.move_to_next_char();
-                        current_char = self.current_char;
-                        continue;
-                    }
-                    Err(_) => {
-                        break;
-                    }
+            if self.current_char == literal_initializer && self.is_closing(multi_line) {
+                for _ in 0..delimiter.len() {
+                    self.move_to_next_char();
                 }
-            } else if prev_char == literal_initializer {
-                string_literal.push(prev_char);
                 break;
             }
-            string_literal.push(prev_char);
-            prev_char = current_char;
+            if self.current_char == '\\' && !raw {
+                match self.determine_escape()? {
+                    Some(character) => string_literal.push(character),
+                    // a backslash ending a line of a multi-line string joins it with the
+                    // next one, in other strings it is reported as an unterminated string
+                    None if multi_line && self.is_new_line() => {
+                        while self.is_whitespace() {
+                            self.move_to_next_char();
+                        }
+                    }
+                    None => {}
+                }
+                continue;
+            }
+            if self.current_char == '{' && !raw {
+                let (line, column) = (self.current_line, self.current_column);
+                self.move_to_next_char();
+                let token_type = match interpolated {
+                    true => TType::StringMiddle,
+                    false => TType::StringStart,
+                };
+                let value = std::mem::take(&mut string_literal);
+                self.add_token_to_list(Token::new(token_type, value, part_line, part_column));
+                self.determine_interpolation(line, column)?;
+
+                // the next part starts at the closing '}'
+                self.token_start = self.offset();
+                part_line = self.current_line;
+                part_column = self.current_column;
+                interpolated = true;
+                self.move_to_next_char();
+                continue;
+            }
+            string_literal.push(self.current_char);
             self.move_to_next_char();
-            current_char = self.current_char;
         }
 
-        // removes the surrounding quotes from the string
-        string_literal.remove(0);
-        string_literal.pop();
+        // single quotes around one character are a char, they used to be a str like double quotes
+        let single_quoted = literal_initializer == '\'' && !raw && !multi_line;
+        if single_quoted && !interpolated && string_literal.chars().count() == 1 {
+            return Ok(Token::new(
+                TType::CharLiteral,
+                string_literal,
+                initial_line,
+                initial_column,
+            ));
+        }
+        if single_quoted && !self.lossless {
+            warning(
+                initial_line,
+                initial_column,
+                String::from("Single quoted strs are deprecated, use double quotes instead"),
+            );
+        }
+
+        let token_type = match interpolated {
+            true => TType::StringEnd,
+            false => TType::StringLiteral,
+        };
+        Ok(Token::new(
+            token_type,
+            string_literal,
+            part_line,
+            part_column,
+        ))
+    }
 
-        Token {
-            token_value: string_literal.into_iter().collect(),
-            token_type: TType::StringLiteral,
-            column_number: initial_column,
-            line_number: initial_line,
+    /// if the current quote closes the string, multi-line strings need 3 of them
+    fn is_closing(&self, multi_line: bool) -> bool {
+        if !multi_line {
+            return true;
         }
+        let mut following = self.source_code_iter.clone();
+        following.next() == Some(self.current_char) && following.next() == Some(self.current_char)
+    }
+
+    /// adds the tokens of the expression after the '{' at line and column,
+    /// stopping at its '}'
+    fn determine_interpolation(&mut self, line: u32, column: u32) -> Result<(), Diagnostic> {
+        let mut depth = 0;
+        loop {
+            if self.is_end_of_file {
+                return Err(Diagnostic::new(
+                    line,
+                    column,
+                    String::from("'{' in string literal not closed with '}'"),
+                ));
+            }
+            match self.current_char {
+                '}' if depth == 0 => break,
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => {}
+            }
+            self.scan_next()?;
+        }
+        Ok(())
+    }
+
+    /// reads the escape sequence starting at the current backslash and moves past it
+    fn determine_escape(&mut self) -> Result<Option<char>, Diagnostic> {
+        let line = self.current_line;
+        let column = self.current_column;
+        self.move_to_next_char();
+        if self.is_new_line() || self.is_end_of_file {
+            return Ok(None);
+        }
+
+        let escape = self.current_char;
+        self.move_to_next_char();
+        let character = match escape {
+            'x' => {
+                let mut digits = String::new();
+                for _ in 0..2 {
+                    if !self.current_char.is_ascii_hexdigit() || self.is_end_of_file {
+                        return Err(Diagnostic::new(
+                            self.current_line,
+                            self.current_column,
+                            String::from("Expected 2 hex digits after '\\x'"),
+                        ));
+                    }
+                    digits.push(self.current_char);
+                    self.move_to_next_char();
+                }
+                let code = u32::from_str_radix(&digits, 16).unwrap();
+                if code > 0x7F {
+                    return Err(Diagnostic::new(
+                        line,
+                        column,
+                        format!(
+                            "'\\x{}' is not an ASCII character, use '\\u{{{}}}' instead",
+                            digits, digits
+                        ),
+                    ));
+                }
+                char::from_u32(code).unwrap()
+            }
+            'u' => {
+                if self.current_char != '{' {
+                    return Err(Diagnostic::new(
+                        self.current_line,
+                        self.current_column,
+                        String::from("Expected '{' after '\\u'"),
+                    ));
+                }
+                self.move_to_next_char();
+                let mut digits = String::new();
+                while self.current_char.is_ascii_hexdigit() && !self.is_end_of_file {
+                    digits.push(self.current_char);
+                    self.move_to_next_char();
+                }
+                if digits.is_empty() || digits.len() > 6 || self.current_char != '}' {
+                    return Err(Diagnostic::new(
+                        line,
+                        column,
+                        String::from("Expected 1 to 6 hex digits between '\\u{' and '}'"),
+                    ));
+                }
+                self.move_to_next_char();
+                match char::from_u32(u32::from_str_radix(&digits, 16).unwrap()) {
+                    Some(character) => character,
+                    None => {
+                        return Err(Diagnostic::new(
+                            line,
+                            column,
+                            format!("'\\u{{{}}}' is not a valid unicode character", digits),
+                        ));
+                    }
+                }
+            }
+            _ => match get_escaped_char(escape) {
+                Ok(character) => character,
+                Err(_) => {
+                    return Err(Diagnostic::new(
+                        line,
+                        column,
+                        format!("Unknown escape sequence '\\{}'", escape),
+                    ));
+                }
+            },
+        };
+        Ok(Some(character))
     }
 
-    fn determine_number_literal(&mut self) -> Token {
+    fn determine_number_literal(&mut self) -> Result<Token, Diagnostic> {
+        if self.current_char == '0'
+            && self
+                .source_code_iter
+                .clone()
+                .next()
+                .is_some_and(|c| matches!(c, 'x' | 'X' | 'b' | 'B' | 'o' | 'O'))
+        {
+            return self.determine_radix_literal();
+        }
+
         let initial_column = self.current_column;
         let initial_line = self.current_line;
         let mut number_literal: Vec<char> = vec![];
 
         let mut has_dot = false;
+        let mut has_exponent = false;
         let mut previous_char: Option<char> = None;
 
         let mut previous_column = self.current_column;
@@ -194,49 +576,73 @@ impl<'a> Lexer<'a> {
             if self.is_end_of_file {
                 break;
             }
+            // 0..10 is a range, not a number with two dots
+            if self.current_char == '.' && self.source_code_iter.clone().next() == Some('.') {
+                break;
+            }
+            if has_exponent && self.current_char == '.' {
+                return Err(Diagnostic::new(
+                    self.current_line,
+                    self.current_column,
+                    String::from("The exponent of a number literal cannot have a '.'"),
+                ));
+            }
             if has_dot && self.current_char == '.' {
-                error(
+                return Err(Diagnostic::new(
                     self.current_line,
                     self.current_column,
                     String::from("Cannot have multiple '.' in a number literal"),
-                );
-                exit(1)
+                ));
             }
             if self.current_char == '.' {
                 has_dot = true;
             }
             if let Some(c) = previous_char {
                 if c == '_' && self.current_char == '_' {
-                    error(
+                    return Err(Diagnostic::new(
                         self.current_line,
                         self.current_column,
                         String::from("Cannot have multiple adjacent '_'"),
-                    );
-                    exit(1)
+                    ));
                 } else if !c.is_ascii_digit() && !self.is_number_digit() {
                     // if its a whitespace or linebreak, ignore it
                     if self.is_whitespace() {
                         self.move_to_next_char();
                         continue;
                     }
-                    if c == '_' {
-                        error(
-                            previous_line,
-                            previous_column,
-                            String::from("'_' can only appear between digits"),
-                        );
-                    } else if c == '.' {
-                        error(
-                            previous_line,
-                            previous_column,
-                            String::from(
-                                "'.' can only appear between or on the start of numeric literals",
-                            ),
-                        );
-                    }
-                    exit(1)
+                    let message = match c {
+                        '_' => "'_' can only appear between digits",
+                        _ => "'.' can only appear between or on the start of numeric literals",
+                    };
+                    return Err(Diagnostic::new(
+                        previous_line,
+                        previous_column,
+                        String::from(message),
+                    ));
                 }
             }
+            // 1.5e-3, the exponent is an integer that may have a sign
+            if matches!(self.current_char, 'e' | 'E')
+                && !has_exponent
+                && previous_char.is_some_and(|c| c.is_ascii_digit())
+            {
+                has_exponent = true;
+                number_literal.push(self.current_char);
+                self.move_to_next_char();
+                if matches!(self.current_char, '+' | '-') {
+                    number_literal.push(self.current_char);
+                    self.move_to_next_char();
+                }
+                if !self.is_number_digit() {
+                    return Err(Diagnostic::new(
+                        self.current_line,
+                        self.current_column,
+                        String::from("Expected digits after the exponent of a number literal"),
+                    ));
+                }
+                previous_char = number_literal.last().copied();
+                continue;
+            }
             if is_valid_number_literal(&self.current_char) {
                 previous_char = Some(self.current_char);
                 number_literal.push(self.current_char);
@@ -251,34 +657,143 @@ impl<'a> Lexer<'a> {
             }
         }
 
+        self.reject_glued_identifier()?;
+
         let mut number_literal: String = number_literal.into_iter().collect();
         if number_literal.chars().nth(0).unwrap() == '.' {
-            number_literal.insert_str(0, "0");
+            number_literal.insert(0, '0');
         }
 
-        Token {
-            token_value: number_literal,
-            token_type: TType::NumberLiteral,
-            line_number: initial_line,
-            column_number: initial_column,
+        Ok(Token::new(
+            TType::NumberLiteral,
+            number_literal,
+            initial_line,
+            initial_column,
+        ))
+    }
+
+    /// 0xFF, 0b1010 or 0o17, the prefix is kept in the token
+    fn determine_radix_literal(&mut self) -> Result<Token, Diagnostic> {
+        let initial_column = self.current_column;
+        let initial_line = self.current_line;
+
+        // 0
+        let mut number_literal: Vec<char> = vec![self.current_char];
+        self.move_to_next_char();
+        // x, b or o
+        number_literal.push(self.current_char);
+        let (radix, name) = match self.current_char.to_ascii_lowercase() {
+            'x' => (16, "hex"),
+            'b' => (2, "binary"),
+            _ => (8, "octal"),
+        };
+        self.move_to_next_char();
+
+        let mut previous_char: Option<char> = None;
+        let mut previous_column = self.current_column;
+        let mut previous_line = self.current_line;
+
+        while !self.is_end_of_file {
+            if self.current_char == '_' {
+                match previous_char {
+                    Some('_') => {
+                        return Err(Diagnostic::new(
+                            self.current_line,
+                            self.current_column,
+                            String::from("Cannot have multiple adjacent '_'"),
+                        ));
+                    }
+                    None => {
+                        return Err(Diagnostic::new(
+                            self.current_line,
+                            self.current_column,
+                            String::from("'_' can only appear between digits"),
+                        ));
+                    }
+                    _ => {}
+                }
+            } else if self.current_char.is_ascii_alphanumeric() {
+                if !self.current_char.is_digit(radix) {
+                    return Err(Diagnostic::new(
+                        self.current_line,
+                        self.current_column,
+                        format!("'{}' is not a valid {} digit", self.current_char, name),
+                    ));
+                }
+            } else if self.current_char == '.' && self.source_code_iter.clone().next() != Some('.')
+            {
+                return Err(Diagnostic::new(
+                    self.current_line,
+                    self.current_column,
+                    format!("A {} number literal cannot have a '.'", name),
+                ));
+            } else {
+                break;
+            }
+            previous_char = Some(self.current_char);
+            number_literal.push(self.current_char);
+            previous_column = self.current_column;
+            previous_line = self.current_line;
+            self.move_to_next_char();
+        }
+
+        match previous_char {
+            None => {
+                let prefix: String = number_literal.iter().collect();
+                return Err(Diagnostic::new(
+                    initial_line,
+                    initial_column,
+                    format!("Expected {} digits after '{}'", name, prefix),
+                ));
+            }
+            Some('_') => {
+                return Err(Diagnostic::new(
+                    previous_line,
+                    previous_column,
+                    String::from("'_' can only appear between digits"),
+                ));
+            }
+            _ => {}
         }
+
+        Ok(Token::new(
+            TType::NumberLiteral,
+            number_literal.into_iter().collect(),
+            initial_line,
+            initial_column,
+        ))
+    }
+
+    /// 123abc is neither a number nor a name
+    fn reject_glued_identifier(&self) -> Result<(), Diagnostic> {
+        if !self.is_end_of_file && self.is_valid_initial_identifier() {
+            return Err(Diagnostic::new(
+                self.current_line,
+                self.current_column,
+                format!(
+                    "Invalid character '{}' in number literal",
+                    self.current_char
+                ),
+            ));
+        }
+        Ok(())
     }
 
     fn move_to_next_char(&mut self) {
+        self.previous_line = self.current_line;
+        self.previous_column = self.current_column;
         if let Some(next_char) = self.source_code_iter.next() {
+            // the \n of a \r\n ends the same line as the \r
+            let line_feed_after_return = self.current_char == '\r' && next_char == '\n';
             self.current_char = next_char;
             if self.is_new_line() {
-                self.move_to_next_line();
+                if !line_feed_after_return {
+                    self.move_to_next_line();
+                }
             } else {
                 self.current_column += 1;
             }
         } else {
-            self.token_list.push(Token {
-                token_value: String::new(),
-                token_type: TType::EOF,
-                line_number: self.current_line,
-                column_number: self.current_column,
-            });
             self.is_end_of_file = true;
         }
     }
@@ -288,39 +803,46 @@ impl<'a> Lexer<'a> {
         self.current_column = 0;
     }
 
-    fn determine_token(&mut self) -> Token {
+    fn determine_token(&mut self) -> Result<Token, Diagnostic> {
         let first_char = self.current_char;
         let first_char_line = self.current_line;
         let first_char_column = self.current_column;
         self.move_to_next_char();
         let token_type = self.determine_operator(first_char, self.current_char);
         match token_type {
-            TType::UNKNOWN => {
-                error(
-                    first_char_line,
-                    first_char_column,
-                    format!("Unknown Token '{}'", &first_char),
-                );
-                exit(1)
-            }
+            TType::UNKNOWN => Err(Diagnostic::new(
+                first_char_line,
+                first_char_column,
+                format!("Unknown Token '{}'", &first_char),
+            )),
             _ => {
                 let token_value = format!("{}{}", first_char, self.current_char);
                 if is_valid_multi_char(&token_value) {
                     let current_line = self.current_line;
                     self.move_to_next_char();
-                    Token {
+                    // ..= is the only 3 char operator
+                    if token_type == TType::Range && self.current_char == '=' {
+                        self.move_to_next_char();
+                        return Ok(Token::new(
+                            TType::RangeInclusive,
+                            String::from("..="),
+                            current_line,
+                            first_char_column,
+                        ));
+                    }
+                    Ok(Token::new(
                         token_type,
                         token_value,
-                        line_number: current_line,
-                        column_number: first_char_column,
-                    }
+                        current_line,
+                        first_char_column,
+                    ))
                 } else {
-                    Token {
+                    Ok(Token::new(
                         token_type,
-                        token_value: first_char.to_string(),
-                        line_number: first_char_line,
-                        column_number: first_char_column,
-                    }
+                        first_char.to_string(),
+                        first_char_line,
+                        first_char_column,
+                    ))
                 }
             }
         }
@@ -333,37 +855,41 @@ impl<'a> Lexer<'a> {
         let mut alphabetic_token: Vec<char> = vec![self.current_char];
         // ignores the current character for the loop and checks only the next one
         self.move_to_next_char();
-        while self.is_valid_following_identifier() {
+        while self.is_valid_following_identifier() && !self.is_end_of_file {
             alphabetic_token.push(self.current_char);
             self.move_to_next_char();
         }
         let alphabetic_token: String = alphabetic_token.into_iter().collect();
         let token_type = determine_alphabetic_token_type(&alphabetic_token);
-        Token {
+        Token::new(
             token_type,
-            line_number: initial_line,
-            column_number: initial_column,
-            token_value: alphabetic_token.to_string(),
-        }
+            alphabetic_token.to_string(),
+            initial_line,
+            initial_column,
+        )
     }
 
     fn determine_operator(&mut self, first_char: char, next_char: char) -> TType {
         match first_char {
             '=' => match is_valid_long_operator(next_char) {
                 TType::Assign => TType::LogicalEquals,
+                TType::LogicalGreaterThan => TType::SmallFunction,
                 _ => TType::Assign,
             },
             '+' => match is_valid_long_operator(next_char) {
                 TType::Assign => TType::AssignPlus,
+                TType::BinaryPlus => TType::BinaryIncrement,
                 _ => TType::BinaryPlus,
             },
             '-' => match is_valid_long_operator(next_char) {
                 TType::Assign => TType::AssignMinus,
+                TType::BinaryMinus => TType::BinaryDecrement,
                 _ => TType::BinaryMinus,
             },
             '/' => match is_valid_long_operator(next_char) {
                 TType::Assign => TType::AssignDivision,
                 TType::BinaryDivision => TType::Comment,
+                TType::BinaryMultiply => TType::BlockComment,
                 _ => TType::BinaryDivision,
             },
             '*' => match is_valid_long_operator(next_char) {
@@ -376,18 +902,28 @@ impl<'a> Lexer<'a> {
             },
             '<' => match is_valid_long_operator(next_char) {
                 TType::Assign => TType::LogicalSmallerOrEqualsThan,
+                TType::LogicalSmallerThan => TType::BitwiseShiftLeft,
                 _ => TType::LogicalSmallerThan,
             },
             '>' => match is_valid_long_operator(next_char) {
                 TType::Assign => TType::LogicalGreaterOrEqualsThan,
+                TType::LogicalGreaterThan => TType::BitwiseShiftRight,
                 _ => TType::LogicalGreaterThan,
             },
             '!' => match is_valid_long_operator(next_char) {
                 TType::Assign => TType::LogicalDifferent,
                 _ => TType::LogicalNot,
             },
-            '&' => TType::LogicalAnd,
-            '|' => TType::LogicalOr,
+            '&' => match is_valid_long_operator(next_char) {
+                TType::LogicalAnd => TType::LogicalAnd,
+                _ => TType::BitwiseAnd,
+            },
+            '|' => match is_valid_long_operator(next_char) {
+                TType::LogicalOr => TType::LogicalOr,
+                _ => TType::BitwiseOr,
+            },
+            '^' => TType::BitwiseXor,
+            '~' => TType::BitwiseNot,
             '\"' => TType::DoubleQuotes,
             '\'' => TType::SingleQuotes,
             '(' => TType::LeftParenthesis,
@@ -396,20 +932,84 @@ impl<'a> Lexer<'a> {
             ']' => TType::RightSquareBracket,
             '{' => TType::LeftCurlyBrace,
             '}' => TType::RightCurlyBrace,
-            '.' => TType::Dot,
+            '.' => match is_valid_long_operator(next_char) {
+                TType::Dot => TType::Range,
+                _ => TType::Dot,
+            },
             ':' => TType::Colon,
             ';' => TType::Semicolon,
             ',' => TType::Comma,
+            '?' => TType::QuestionMark,
             _ => TType::UNKNOWN,
         }
     }
 }
 
+impl Iterator for Lexer<'_> {
+    type Item = Result<Token, Diagnostic>;
+
+    /// the tokens are lexed as they are asked for, the last one is EOF or the first error
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.tokens.is_empty() && !self.finished {
+            if let Err(diagnostic) = self.scan_tokens() {
+                self.finished = true;
+                self.tokens.clear();
+                return Some(Err(diagnostic));
+            }
+        }
+        self.tokens.pop_front().map(Ok)
+    }
+}
+
+/// 16 for 0xFF, 2 for 0b1010, 8 for 0o17 and 10 for everything else
+pub fn radix(number: &str) -> u32 {
+    match number.trim_start_matches('-').get(..2) {
+        Some("0x" | "0X") => 16,
+        Some("0b" | "0B") => 2,
+        Some("0o" | "0O") => 8,
+        _ => 10,
+    }
+}
+
+/// value of an integer literal without '_', None when it does not fit in an i32,
+/// hex, binary and octal ones can set all 32 bits so 0xFFFFFFFF is -1
+pub fn parse_int_literal(number: &str) -> Option<i32> {
+    let radix = radix(number);
+    if radix == 10 {
+        return number.parse::<i32>().ok();
+    }
+    let (negative, digits) = match number.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, number),
+    };
+    let value = u32::from_str_radix(&digits[2..], radix).ok()? as i32;
+    Some(if negative {
+        value.wrapping_neg()
+    } else {
+        value
+    })
+}
+
+/// the source the tokens of Lexer::lossless were read from, byte for byte
+pub fn source_text(tokens: &[Token]) -> String {
+    let mut text = String::new();
+    for token in tokens {
+        for trivia in &token.trivia {
+            text.push_str(&trivia.text);
+        }
+        text.push_str(&token.text);
+    }
+    text
+}
+
 fn get_escaped_char(character: char) -> Result<char, ()> {
     match character {
         'n' => Ok('\n'),
         'r' => Ok('\r'),
         't' => Ok('\t'),
+        '0' => Ok('\0'),
+        '{' => Ok('{'),
+        '}' => Ok('}'),
         '\\' => Ok('\\'),
         '\'' => Ok('\''),
         '\"' => Ok('\"'),
@@ -424,15 +1024,16 @@ fn is_valid_number_literal(digit: &char) -> bool {
 fn is_valid_long_operator(character: char) -> TType {
     match character {
         '=' => TType::Assign,
-        // '+' => TType::BinaryPlus,
-        // '-' => TType::BinaryMinus,
+        '+' => TType::BinaryPlus,
+        '-' => TType::BinaryMinus,
         '/' => TType::BinaryDivision,
-        // '*' => TType::BinaryMultiply,
+        '*' => TType::BinaryMultiply,
         // '%' => TType::BinaryRest,
-        // '&' => TType::LogicalAnd,
-        // '|' => TType::LogicalOr,
+        '&' => TType::LogicalAnd,
+        '|' => TType::LogicalOr,
         '<' => TType::LogicalSmallerThan,
         '>' => TType::LogicalGreaterThan,
+        '.' => TType::Dot,
         // '!' => TType::LogicalNot,
         // '\"' => TType::DoubleQuotes,
         // '\'' => TType::SingleQuotes,
@@ -440,20 +1041,29 @@ fn is_valid_long_operator(character: char) -> TType {
     }
 }
 
+// one operator per line reads better as a table than a matches!
+#[allow(clippy::match_like_matches_macro)]
 fn is_valid_multi_char(string: &str) -> bool {
     match string {
         "==" => true,
+        "=>" => true,
         "+=" => true,
         "-=" => true,
         "/=" => true,
         "*=" => true,
         "%=" => true,
-        // "++" => true,
-        // "--" => true,
+        "++" => true,
+        "--" => true,
+        "&&" => true,
+        "||" => true,
+        "<<" => true,
+        ">>" => true,
         "<=" => true,
         ">=" => true,
         "!=" => true,
         "//" => true,
+        "/*" => true,
+        ".." => true,
         _ => false,
     }
 }
@@ -467,8 +1077,11 @@ fn determine_alphabetic_token_type(token: &str) -> TType {
         "i32" => TType::Int,
         "f64" => TType::Flo,
         "str" => TType::Str,
+        "char" => TType::Chr,
         // "null" => TType::Nul,
         "vec" => TType::Arr,
+        "obj" => TType::Obj,
+        "enum" => TType::Enum,
         "let" => TType::ConstantVariable,
         "mut" => TType::MutableVariable,
         "func" => TType::Function,
@@ -480,12 +1093,13 @@ fn determine_alphabetic_token_type(token: &str) -> TType {
         "cnt" => TType::Continue,
         "ret" => TType::Return,
         "for" => TType::For,
+        "in" => TType::In,
+        "as" => TType::As,
+        "switch" => TType::Switch,
+        "case" => TType::Case,
+        "casenot" => TType::CaseNot,
+        "use" => TType::Use,
+        "from" => TType::From,
         _ => TType::Identifier,
-        // "Obj" => TType::Object,
-        // "use" => TType::Use,
-        // "from" => TType::From,
-        // "switch" => TType::Switch,
-        // "case" => TType::Case,
-        // "casenot" => TType::CaseNot,
     }
 }
@@ -1,6 +1,12 @@
+pub mod dump;
+pub mod formatter;
+pub mod json;
 pub mod lexer;
+pub mod modules;
 pub mod parser;
+pub mod printer;
 pub mod types;
+pub mod visit;
 
 // pub use lexer::*;
 // pub use parser::*;
@@ -0,0 +1,186 @@
+use crate::{
+    abort, error,
+    frontend::{
+        lexer::Lexer,
+        parser::Parser,
+        types::{Start, Statement},
+    },
+    set_module_file,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug)]
+struct Linker {
+    // canonical path and name of every module parsed so far
+    loaded: Vec<(PathBuf, String)>,
+    // canonical and shown path of the files whose imports are being resolved, innermost last
+    loading: Vec<(PathBuf, String)>,
+    // modules in the order they must be transpiled, dependencies first
+    modules: Vec<Statement>,
+}
+
+/// parses every file the program imports, once each, and puts them before the program as modules
+pub fn link(program: Statement, file: &str) -> Statement {
+    let Statement::Program { start, body } = program else {
+        return program;
+    };
+
+    let mut linker = Linker {
+        loaded: Vec::new(),
+        loading: vec![(canonical(Path::new(file)), file.to_owned())],
+        modules: Vec::new(),
+    };
+    let body = linker.resolve_imports(body);
+
+    let mut linked = linker.modules;
+    linked.extend(body);
+
+    Statement::Program {
+        start,
+        body: linked,
+    }
+}
+
+impl Linker {
+    /// sets the module of every 'use' in the body of the innermost loading file
+    fn resolve_imports(&mut self, body: Vec<Statement>) -> Vec<Statement> {
+        let mut aliases: Vec<String> = Vec::new();
+
+        body.into_iter()
+            .map(|stmt| match stmt {
+                Statement::Use {
+                    start, name, path, ..
+                } => {
+                    if aliases.contains(&name) {
+                        error(
+                            start.line,
+                            start.column,
+                            format!("Module '{}' is already imported", name),
+                        );
+                        abort()
+                    }
+                    aliases.push(name.to_owned());
+
+                    let module = self.load(&start, &path);
+                    Statement::Use {
+                        start,
+                        name,
+                        path,
+                        module: Some(module),
+                    }
+                }
+                stmt => stmt,
+            })
+            .collect()
+    }
+
+    /// returns the name of the module at the path, relative to the file importing it
+    fn load(&mut self, start: &Start, path: &str) -> String {
+        let (_, importer) = self.loading.last().unwrap();
+        let mut shown = Path::new(importer)
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join(path);
+        if shown.extension().is_none() {
+            shown.set_extension("bline");
+        }
+        let shown = shown.to_string_lossy().to_string();
+
+        let Ok(file) = fs::canonicalize(&shown) else {
+            error(
+                start.line,
+                start.column,
+                format!("Module '{}' not found, looked for '{}'", path, shown),
+            );
+            abort()
+        };
+
+        if let Some(i) = self.loading.iter().position(|(f, _)| f.eq(&file)) {
+            let cycle: Vec<&str> = self.loading[i..]
+                .iter()
+                .map(|(_, s)| s.as_str())
+                .chain([shown.as_str()])
+                .collect();
+            error(
+                start.line,
+                start.column,
+                format!("Circular import, {}", cycle.join(" -> ")),
+            );
+            abort()
+        }
+        if let Some((_, name)) = self.loaded.iter().find(|(f, _)| f.eq(&file)) {
+            return name.to_owned();
+        }
+
+        let source = match fs::read_to_string(&file) {
+            Ok(source) => source,
+            Err(err) => {
+                error(
+                    start.line,
+                    start.column,
+                    format!("Cannot read module '{}', {}", shown, err),
+                );
+                abort()
+            }
+        };
+
+        let name = self.unique_name(&file);
+        self.loaded.push((file.to_owned(), name.to_owned()));
+        self.loading.push((file, shown.to_owned()));
+        set_module_file(Some(shown.to_owned()));
+
+        let mut parser_instance = Parser::new(Lexer::new(&source));
+        parser_instance.parse_tokens();
+
+        let body = match parser_instance.abstract_syntax_tree {
+            Statement::Program { body, .. } => self.resolve_imports(body),
+            _ => Vec::new(),
+        };
+
+        self.loading.pop();
+        // errors after this point belong to the importing file again
+        set_module_file(match self.loading.len() {
+            1 => None,
+            _ => self.loading.last().map(|(_, s)| s.to_owned()),
+        });
+
+        self.modules.push(Statement::Module {
+            start: Start { line: 1, column: 0 },
+            name: name.to_owned(),
+            file: shown,
+            body,
+        });
+
+        name
+    }
+
+    /// the file name made valid inside C identifiers, 'math' or 'math2' if it is taken
+    fn unique_name(&self, file: &Path) -> String {
+        let stem: String = file
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let stem = match stem.chars().next() {
+            Some(c) if c.is_ascii_alphabetic() => stem,
+            _ => format!("m{}", stem),
+        };
+
+        let mut name = stem.to_owned();
+        let mut i = 1;
+        while self.loaded.iter().any(|(_, n)| n.eq(&name)) {
+            i += 1;
+            name = format!("{}{}", stem, i);
+        }
+        name
+    }
+}
+
+fn canonical(file: &Path) -> PathBuf {
+    fs::canonicalize(file).unwrap_or(file.to_path_buf())
+}
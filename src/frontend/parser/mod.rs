@@ -1,17 +1,27 @@
 use crate::{
-    error,
+    abort, error,
+    frontend::lexer::{parse_int_literal, radix},
     frontend::types::{
-        ArrayAccess, Expression, FuncParam, LiteralTypes, Start, Statement, Token, TokenTypes,
-        VarDeclarationKind, VariableTypes,
+        ArrayAccess, EnumVariant, Expression, FieldValue, FuncParam, LiteralTypes, Pattern, Span,
+        Start, Statement, StructField, SwitchCase, Token, TokenTypes, VarDeclarationKind,
+        VariableTypes,
     },
-    report,
+    report, Diagnostic,
 };
-use std::{iter::Peekable, process::exit};
+use std::collections::VecDeque;
 
 #[derive(Debug)]
-pub struct Parser {
-    tokens: Peekable<std::vec::IntoIter<Token>>,
+pub struct Parser<I: Iterator<Item = Result<Token, Diagnostic>>> {
+    tokens: I,
+    // tokens already taken from the lexer to look past the current one
+    lookahead: VecDeque<Token>,
     current_token: Token,
+    // false while parsing conditions, where '{' starts the block, if foo {
+    struct_literals: bool,
+    // text of the /// comments waiting for the declaration after them
+    doc: Option<String>,
+    // a '-' right before the number literal being parsed, 2147483648 only fits in an i32 after one
+    negated: bool,
     pub abstract_syntax_tree: Statement,
 }
 
@@ -27,36 +37,49 @@ enum Loop {
     No,
 }
 
-// operator precedence
-// 12 ()
+// operator precedence, the printer puts parentheses around anything that binds less tightly
+// 15 ()
+// 15 [] Arr[0]
+// 15 func()
+// 15 foo.bar
 //
-// 11 [] Arr[0]
-// 11 func()
+// 14 !Boo
+// 14 ~Int
+// 14 + Num
+// 14 - Num
 //
-// 10 !Boo
+// 13 Expr as Type
 //
-// 9 + Num
-// 9 - Num
+// 12 Bin * Bin
+// 12 Bin / Bin
+// 12 Bin % Bin
 //
-// 8 Bin * Bin
-// 8 Bin / Bin
-// 8 Bin % Bin
+// 11 Bin + Bin
+// 11 Bin - Bin
+// 11 Str + Str
 //
-// 7 Bin + Bin
-// 7 Bin - Bin
-// 7 Str + Str
+// 10 Int << Int
+// 10 Int >> Int
 //
-// 6 Boo < Boo
-// 6 Boo <= Boo
-// 6 Boo > Boo
-// 6 Boo >= Boo
+// 9 Boo < Boo
+// 9 Boo <= Boo
+// 9 Boo > Boo
+// 9 Boo >= Boo
 //
-// 5 Boo == Boo
-// 5 Boo != Boo
+// 8 Boo == Boo
+// 8 Boo != Boo
 //
-// 4 Boo & Boo
+// 7 Int & Int
 //
-// 3 Boo | Boo
+// 6 Int ^ Int
+//
+// 5 Int | Int
+//
+// 4 Boo && Boo
+//
+// 3 Boo || Boo
+//
+// 2 Boo ? Expr : Expr
 //
 // 2 = assignment
 // 2 += assignment
@@ -64,77 +87,138 @@ enum Loop {
 // 2 *= assignment
 // 2 /= assignment
 // 2 %= assignment
+// 2 ++ assignment
+// 2 -- assignment
 //
 // 1 , separator (10,10+10)
 
-impl Parser {
-    pub fn new(token_vector: Vec<Token>) -> Self {
-        let mut iterator = token_vector.into_iter().peekable();
-        Self {
-            current_token: iterator.next().unwrap(),
-            tokens: iterator,
+impl<I: Iterator<Item = Result<Token, Diagnostic>>> Parser<I> {
+    /// tokens are taken from the lexer as the parser needs them
+    pub fn new(tokens: I) -> Self {
+        let mut parser = Self {
+            tokens,
+            lookahead: VecDeque::new(),
+            current_token: Token::new(TokenTypes::EOF, String::new(), 1, 0),
+            struct_literals: true,
+            doc: None,
+            negated: false,
             abstract_syntax_tree: Statement::Program {
                 start: Start { line: 1, column: 0 },
-                body: Box::new(Vec::new()),
+                body: Vec::new(),
             },
+        };
+        parser.current_token = parser.next_token().unwrap();
+        parser
+    }
+
+    /// the next token from the lexer, None after the EOF
+    fn next_token(&mut self) -> Option<Token> {
+        loop {
+            match self.tokens.next()? {
+                // comments can go anywhere, only doc comments mean something to the parser
+                Ok(token)
+                    if matches!(
+                        token.token_type,
+                        TokenTypes::Comment | TokenTypes::BlockComment
+                    ) => {}
+                Ok(token) => return Some(token),
+                Err(diagnostic) => {
+                    diagnostic.emit();
+                    abort()
+                }
+            }
+        }
+    }
+
+    /// the token n places after the current one, 0 is the peek token
+    fn peek_nth(&mut self, n: usize) -> Option<&Token> {
+        while self.lookahead.len() <= n {
+            match self.next_token() {
+                Some(token) => self.lookahead.push_back(token),
+                None => break,
+            }
         }
+        self.lookahead.get(n)
     }
 
     pub fn parse_tokens(&mut self) {
         while !self.current_type().eq(&TokenTypes::EOF) {
             let ast_node = match &self.current_type() {
                 TokenTypes::Function => self.parse_function_statement(),
+                TokenTypes::Obj => self.parse_struct_statement(),
+                TokenTypes::Enum => self.parse_enum_statement(),
+                TokenTypes::Use => self.parse_use_statement(),
                 TokenTypes::Semicolon => {
                     self.unexpected_token_error(self.current());
-                    exit(1)
+                    abort()
                 }
-                TokenTypes::Comment => {
-                    self.advance();
+                TokenTypes::DocComment => {
+                    self.doc = self.parse_doc_comment();
+                    if !matches!(
+                        self.current_type(),
+                        TokenTypes::Function
+                            | TokenTypes::Obj
+                            | TokenTypes::Enum
+                            | TokenTypes::ConstantVariable
+                            | TokenTypes::MutableVariable
+                    ) {
+                        self.custom_error_current(
+                            "Doc comments must be followed by a function, struct, enum or variable",
+                        );
+                        abort()
+                    }
                     continue;
                 }
                 TokenTypes::ConstantVariable | TokenTypes::MutableVariable => {
-                    self.custom_error_current("Variables cannot be defined outside of a function");
-                    exit(1)
+                    self.parse_var_declaration()
                 }
                 TokenTypes::If => {
                     self.custom_error_current(
                         "If statements cannot be defined outside of a function",
                     );
-                    exit(1)
+                    abort()
                 }
                 TokenTypes::ElseIf => {
                     self.custom_error_current(
                         "ElseIf statements cannot be defined outside of a function",
                     );
-                    exit(1)
+                    abort()
                 }
                 TokenTypes::Else => {
                     self.custom_error_current(
                         "Else statements cannot be defined outside of a function",
                     );
-                    exit(1)
+                    abort()
                 }
                 TokenTypes::While | TokenTypes::For => {
                     self.custom_error_current("Loops cannot be defined outside of a function");
-                    exit(1)
+                    abort()
+                }
+                TokenTypes::Switch => {
+                    self.custom_error_current(
+                        "Switch statements cannot be defined outside of a function",
+                    );
+                    abort()
                 }
                 TokenTypes::Return => {
                     self.custom_error_current(
                         "Return statements cannot be used outside of a function",
                     );
-                    exit(1)
+                    abort()
                 }
                 TokenTypes::Continue | TokenTypes::Break => {
                     self.custom_error_current("Loop controls cannot be used outside of a loop");
-                    exit(1)
+                    abort()
                 }
                 _ => {
                     if self.is_expr() {
                         self.custom_error_current("Expressions cannot be standalone statements");
-                        exit(1)
+                        abort()
                     }
-                    self.custom_error_current("Only functions can be defined at the global scope");
-                    exit(1)
+                    self.custom_error_current(
+                        "Only functions, variables, structs and enums can be defined at the global scope",
+                    );
+                    abort()
                 }
             };
             self.push_statement(ast_node);
@@ -170,9 +254,14 @@ impl Parser {
                         stmt
                     } else if self.is_assign_operator(&peek.token_type) {
                         self.parse_var_mutation()
+                    } else if matches!(
+                        peek.token_type,
+                        TokenTypes::Dot | TokenTypes::LeftSquareBracket
+                    ) {
+                        self.parse_place_mutation()
                     } else {
-                        self.unexpected_token_error(&self.current());
-                        exit(1)
+                        self.unexpected_token_error(self.current());
+                        abort()
                     }
                 }
                 TokenTypes::Function => {
@@ -181,7 +270,40 @@ impl Parser {
                         self.current().column_number,
                         String::from("Functions cannot be defined inside functions"),
                     );
-                    exit(1)
+                    abort()
+                }
+                TokenTypes::Obj => {
+                    report(
+                        self.current().line_number,
+                        self.current().column_number,
+                        String::from("Structs cannot be defined inside functions"),
+                    );
+                    abort()
+                }
+                TokenTypes::Enum => {
+                    report(
+                        self.current().line_number,
+                        self.current().column_number,
+                        String::from("Enums cannot be defined inside functions"),
+                    );
+                    abort()
+                }
+                TokenTypes::Switch => self.parse_switch_stmt(is_loop),
+                TokenTypes::Use => {
+                    report(
+                        self.current().line_number,
+                        self.current().column_number,
+                        String::from("Modules can only be imported outside of functions"),
+                    );
+                    abort()
+                }
+                TokenTypes::Case | TokenTypes::CaseNot => {
+                    report(
+                        self.current().line_number,
+                        self.current().column_number,
+                        String::from("Standalone case statement"),
+                    );
+                    abort()
                 }
                 TokenTypes::Continue | TokenTypes::Break => match is_loop {
                     Loop::Yes => self.parse_loop_controls(),
@@ -191,7 +313,7 @@ impl Parser {
                             self.current().column_number,
                             String::from("Loop controls cannot be used outside of loops"),
                         );
-                        exit(1)
+                        abort()
                     }
                 },
                 TokenTypes::Return => self.parse_func_return(),
@@ -202,7 +324,7 @@ impl Parser {
                         self.current().column_number,
                         String::from("Standalone elseif statement"),
                     );
-                    exit(1)
+                    abort()
                 }
                 TokenTypes::Else => {
                     report(
@@ -210,16 +332,25 @@ impl Parser {
                         self.current().column_number,
                         String::from("Standalone else statement"),
                     );
-                    exit(1)
+                    abort()
                 }
                 TokenTypes::While => self.parse_while_loop(&Loop::Yes),
                 TokenTypes::For => self.parse_for_loop(&Loop::Yes),
                 TokenTypes::Semicolon => {
                     self.unexpected_token_error(self.current());
-                    exit(1)
+                    abort()
                 }
-                TokenTypes::Comment => {
-                    self.advance();
+                TokenTypes::DocComment => {
+                    self.doc = self.parse_doc_comment();
+                    if !matches!(
+                        self.current_type(),
+                        TokenTypes::ConstantVariable | TokenTypes::MutableVariable
+                    ) {
+                        self.custom_error_current(
+                            "Doc comments inside functions can only be followed by a variable",
+                        );
+                        abort()
+                    }
                     continue;
                 }
                 _ => {
@@ -227,10 +358,10 @@ impl Parser {
                         self.custom_error_current(
                             "Only function calls can be standalone statements",
                         );
-                        exit(1)
+                        abort()
                     }
                     self.unknown_error(&self.current_token);
-                    exit(1)
+                    abort()
                 }
             };
 
@@ -244,10 +375,10 @@ impl Parser {
                 self.current().column_number,
                 String::from("Unclosed block"),
             );
-            exit(1)
+            abort()
         }
 
-        if block_stmts.len() == 0 {
+        if block_stmts.is_empty() {
             None
         } else {
             Some(block_stmts)
@@ -264,10 +395,11 @@ impl Parser {
             kind: match self.current().token_type {
                 TokenTypes::ConstantVariable => VarDeclarationKind::Immutable,
                 TokenTypes::MutableVariable => VarDeclarationKind::Mutable,
-                _ => exit(1),
+                _ => abort(),
             },
             r#type: None,
             value: None,
+            doc: self.doc.take(),
         };
 
         self.mutate_or_error(
@@ -283,26 +415,26 @@ impl Parser {
         self.determine_var_type(&mut var_dec);
         // self.advance();
 
-        match &var_dec {
-            Statement::VariableDeclaration { kind, .. } => match kind {
-                VarDeclarationKind::Mutable => match self.current_type() {
-                    TokenTypes::Semicolon => {
-                        return var_dec;
-                    }
-                    TokenTypes::Assign => {}
-                    _ => {
-                        self.unexpected_token_error(self.current());
-                        exit(1)
-                    }
-                },
-                _ => {}
-            },
-            _ => {}
+        if let Statement::VariableDeclaration {
+            kind: VarDeclarationKind::Mutable,
+            ..
+        } = &var_dec
+        {
+            match self.current_type() {
+                TokenTypes::Semicolon => {
+                    return var_dec;
+                }
+                TokenTypes::Assign => {}
+                _ => {
+                    self.unexpected_token_error(self.current());
+                    abort()
+                }
+            }
         }
 
         if !self.current_type().eq(&TokenTypes::Assign) {
             self.expected_error("=", self.current());
-            exit(1)
+            abort()
         }
         // self.expected_or_error(&TokenTypes::Assign, "=");
         // self.advance();
@@ -320,7 +452,46 @@ impl Parser {
     }
 
     fn parse_expr(&mut self) -> Expression {
-        self.parse_or_expr()
+        self.parse_ternary_expr()
+    }
+
+    /// parse condition ? expr : expr, the else branch can be another ternary
+    fn parse_ternary_expr(&mut self) -> Expression {
+        let condition = self.parse_or_expr();
+
+        if !self.peek_type().eq(&TokenTypes::QuestionMark) {
+            return condition;
+        }
+        self.advance();
+
+        self.expect_expr_or_error();
+        self.advance();
+        let when_true = self.parse_ternary_expr();
+
+        self.expected_or_error(&TokenTypes::Colon, ":");
+        self.advance();
+
+        self.expect_expr_or_error();
+        self.advance();
+        let when_false = self.parse_ternary_expr();
+
+        Expression::Ternary {
+            condition: Box::new(condition),
+            when_true: Box::new(when_true),
+            when_false: Box::new(when_false),
+        }
+    }
+
+    /// parses an expression followed by a block, struct literals must be in parentheses
+    fn parse_condition(&mut self) -> Expression {
+        self.parse_expr_allowing_structs(false)
+    }
+
+    fn parse_expr_allowing_structs(&mut self, allowed: bool) -> Expression {
+        let previous = std::mem::replace(&mut self.struct_literals, allowed);
+        let expr = self.parse_expr();
+        self.struct_literals = previous;
+        expr
     }
 
     fn parse_or_expr(&mut self) -> Expression {
@@ -345,7 +516,7 @@ impl Parser {
     }
 
     fn parse_and_expr(&mut self) -> Expression {
-        let mut left = self.parse_comparison_expr();
+        let mut left = self.parse_bitwise_or_expr();
 
         while self.peek_type().eq(&TokenTypes::LogicalAnd) {
             self.advance();
@@ -353,7 +524,7 @@ impl Parser {
             let operator = self.current().to_owned();
             self.advance();
 
-            let right = self.parse_comparison_expr();
+            let right = self.parse_bitwise_or_expr();
 
             left = Expression::Logical {
                 left: Box::new(left),
@@ -365,6 +536,69 @@ impl Parser {
         left
     }
 
+    fn parse_bitwise_or_expr(&mut self) -> Expression {
+        let mut left = self.parse_bitwise_xor_expr();
+
+        while self.peek_type().eq(&TokenTypes::BitwiseOr) {
+            self.advance();
+
+            let operator = self.current().to_owned();
+            self.advance();
+
+            let right = self.parse_bitwise_xor_expr();
+
+            left = Expression::Binary {
+                left: Box::new(left),
+                operator: operator.token_type,
+                right: Box::new(right),
+            };
+        }
+
+        left
+    }
+
+    fn parse_bitwise_xor_expr(&mut self) -> Expression {
+        let mut left = self.parse_bitwise_and_expr();
+
+        while self.peek_type().eq(&TokenTypes::BitwiseXor) {
+            self.advance();
+
+            let operator = self.current().to_owned();
+            self.advance();
+
+            let right = self.parse_bitwise_and_expr();
+
+            left = Expression::Binary {
+                left: Box::new(left),
+                operator: operator.token_type,
+                right: Box::new(right),
+            };
+        }
+
+        left
+    }
+
+    fn parse_bitwise_and_expr(&mut self) -> Expression {
+        let mut left = self.parse_comparison_expr();
+
+        while self.peek_type().eq(&TokenTypes::BitwiseAnd) {
+            self.advance();
+
+            let operator = self.current().to_owned();
+            self.advance();
+
+            let right = self.parse_comparison_expr();
+
+            left = Expression::Binary {
+                left: Box::new(left),
+                operator: operator.token_type,
+                right: Box::new(right),
+            };
+        }
+
+        left
+    }
+
     fn parse_comparison_expr(&mut self) -> Expression {
         let mut left = self.parse_greater_smaller_expr();
 
@@ -389,7 +623,7 @@ impl Parser {
     }
 
     fn parse_greater_smaller_expr(&mut self) -> Expression {
-        let mut left = self.parse_additive_expr();
+        let mut left = self.parse_shift_expr();
 
         while self.peek_type().eq(&TokenTypes::LogicalSmallerThan)
             || self.peek_type().eq(&TokenTypes::LogicalSmallerOrEqualsThan)
@@ -401,7 +635,7 @@ impl Parser {
             let operator = self.current().to_owned();
             self.advance();
 
-            let right = self.parse_additive_expr();
+            let right = self.parse_shift_expr();
 
             left = Expression::Logical {
                 left: Box::new(left),
@@ -413,6 +647,29 @@ impl Parser {
         left
     }
 
+    fn parse_shift_expr(&mut self) -> Expression {
+        let mut left = self.parse_additive_expr();
+
+        while self.peek_type().eq(&TokenTypes::BitwiseShiftLeft)
+            || self.peek_type().eq(&TokenTypes::BitwiseShiftRight)
+        {
+            self.advance();
+
+            let operator = self.current().to_owned();
+            self.advance();
+
+            let right = self.parse_additive_expr();
+
+            left = Expression::Binary {
+                left: Box::new(left),
+                operator: operator.token_type,
+                right: Box::new(right),
+            };
+        }
+
+        left
+    }
+
     fn parse_additive_expr(&mut self) -> Expression {
         let mut left = self.parse_multiplicative_expr();
 
@@ -437,7 +694,7 @@ impl Parser {
     }
 
     fn parse_multiplicative_expr(&mut self) -> Expression {
-        let mut left = self.parse_unary_expr();
+        let mut left = self.parse_cast_expr();
 
         while self.peek_type().eq(&TokenTypes::BinaryMultiply)
             || self.peek_type().eq(&TokenTypes::BinaryDivision)
@@ -448,7 +705,7 @@ impl Parser {
             let operator = self.current().to_owned();
             self.advance();
 
-            let right = self.parse_primary_expr();
+            let right = self.parse_cast_expr();
 
             left = Expression::Binary {
                 left: Box::new(left),
@@ -460,6 +717,27 @@ impl Parser {
         left
     }
 
+    fn parse_cast_expr(&mut self) -> Expression {
+        let mut expression = self.parse_unary_expr();
+
+        while self.peek_type().eq(&TokenTypes::As) {
+            // as
+            self.advance();
+            // Type
+            self.advance();
+
+            // nothing can be cast to an optional, so 'x as bool ? a : b' is a ternary
+            let r#type = self.get_plain_type();
+
+            expression = Expression::Cast {
+                expression: Box::new(expression),
+                r#type,
+            };
+        }
+
+        expression
+    }
+
     fn parse_unary_expr(&mut self) -> Expression {
         match self.current_type() {
             TokenTypes::BinaryPlus => {
@@ -468,6 +746,7 @@ impl Parser {
             }
             TokenTypes::BinaryMinus => {
                 self.advance();
+                self.negated = self.current_type().eq(&TokenTypes::NumberLiteral);
                 Expression::Unary {
                     operator: TokenTypes::BinaryMinus,
                     operand: Box::new(self.parse_unary_expr()),
@@ -480,6 +759,13 @@ impl Parser {
                     operand: Box::new(self.parse_unary_expr()),
                 }
             }
+            TokenTypes::BitwiseNot => {
+                self.advance();
+                Expression::Unary {
+                    operator: TokenTypes::BitwiseNot,
+                    operand: Box::new(self.parse_unary_expr()),
+                }
+            }
             _ => self.parse_primary_expr(),
         }
     }
@@ -505,13 +791,13 @@ impl Parser {
                 if self.peek_expect(&TokenTypes::Comma) {
                     let peek = self.peek().unwrap().to_owned();
                     self.unexpected_token_error(&peek);
-                    exit(1)
+                    abort()
                 }
                 self.advance();
                 continue;
             }
 
-            expr = self.parse_expr();
+            expr = self.parse_expr_allowing_structs(true);
             if self.peek_expect(&TokenTypes::Comma)
                 || self.peek_expect(&TokenTypes::RightParenthesis)
             {
@@ -520,14 +806,14 @@ impl Parser {
             } else {
                 self.advance();
                 self.expected_error(",", self.current());
-                exit(1)
+                abort()
             }
         }
 
         if !self.current_type().eq(&TokenTypes::RightParenthesis) {
             self.expected_error(")", self.current());
         }
-        if arg_vec.len() == 0 {
+        if arg_vec.is_empty() {
             return Expression::Call {
                 name: name.token_value,
                 arguments: None,
@@ -536,7 +822,7 @@ impl Parser {
 
         Expression::Call {
             name: name.token_value,
-            arguments: Some(Box::new(arg_vec)),
+            arguments: Some(arg_vec),
         }
     }
 
@@ -557,13 +843,13 @@ impl Parser {
                 if self.peek_expect(&TokenTypes::Comma) {
                     let peek = self.peek().unwrap().to_owned();
                     self.unexpected_token_error(&peek);
-                    exit(1)
+                    abort()
                 }
                 self.advance();
                 continue;
             }
 
-            expr = self.parse_expr();
+            expr = self.parse_expr_allowing_structs(true);
             if self.peek_expect(&TokenTypes::Comma)
                 || self.peek_expect(&TokenTypes::RightSquareBracket)
             {
@@ -572,25 +858,78 @@ impl Parser {
             } else {
                 self.advance();
                 self.expected_error(",", self.current());
-                exit(1)
+                abort()
             }
         }
 
         if !self.current_type().eq(&TokenTypes::RightSquareBracket) {
             self.expected_error("]", self.current());
         }
-        if expr_vec.len() == 0 {
+        if expr_vec.is_empty() {
             return Expression::ArrayLiteral { elements: None };
         }
 
         Expression::ArrayLiteral {
-            elements: Some(Box::new(expr_vec)),
+            elements: Some(expr_vec),
+        }
+    }
+
+    /// if the current ( starts the parameters of a lambda, () or (name:
+    fn is_lambda(&mut self) -> bool {
+        match self.peek_type() {
+            TokenTypes::RightParenthesis => true,
+            TokenTypes::Identifier => self
+                .peek_nth(1)
+                .is_some_and(|t| t.token_type.eq(&TokenTypes::Colon)),
+            _ => false,
+        }
+    }
+
+    /// parse (x: i32): i32 => x * 2 or (x: i32): i32 => { ret x * 2; }
+    fn parse_lambda(&mut self) -> Expression {
+        // current (
+        let params = self.parse_params();
+        // current )
+
+        self.expected_or_error(&TokenTypes::Colon, ":");
+        self.advance();
+        self.advance();
+        let r#type = self.get_type();
+
+        self.expected_or_error(&TokenTypes::SmallFunction, "=>");
+        self.advance();
+
+        let body = if self.peek_expect(&TokenTypes::LeftCurlyBrace) {
+            self.advance();
+            // current }
+            self.parse_block(&Loop::No)
+        } else {
+            self.expect_expr_or_error();
+            self.advance();
+            let start = Start {
+                line: self.current().line_number,
+                column: self.current().column_number,
+            };
+            Some(vec![Statement::Return {
+                start,
+                expression: Some(self.parse_expr()),
+            }])
+        };
+
+        Expression::Lambda {
+            params: if params.is_empty() {
+                None
+            } else {
+                Some(params)
+            },
+            r#type,
+            body,
         }
     }
 
     fn parse_parentheses(&mut self) -> Expression {
         self.advance();
-        let expr = self.parse_expr();
+        let expr = self.parse_expr_allowing_structs(true);
         self.advance();
         let current = self.current().to_owned();
         if !self.current_type().eq(&TokenTypes::RightParenthesis) {
@@ -599,63 +938,100 @@ impl Parser {
         expr
     }
 
-    fn parse_array_access(&mut self) -> Expression {
-        let name = self.current().token_value.to_owned();
-        let mut index: Expression;
+    /// parse the [index] after expr, leaving current on the ]
+    fn parse_array_access(&mut self, expr: Expression) -> Expression {
+        // [
+        self.advance();
 
-        let mut arr_access: Option<ArrayAccess> = None;
+        self.expect_expr_or_error();
+        self.advance();
+        let index = Box::new(self.parse_expr_allowing_structs(true));
+        self.advance();
 
-        while self.peek_expect(&TokenTypes::LeftSquareBracket) {
-            self.advance();
+        if !self.current_type().eq(&TokenTypes::RightSquareBracket) {
+            self.expected_error("]", self.current());
+            abort()
+        }
 
-            self.expect_expr_or_error();
-            self.advance();
-            index = self.parse_expr();
-            self.advance();
+        Expression::ArrayAccess(match expr {
+            Expression::Identifier(name) => ArrayAccess::Access { name, index },
+            Expression::ArrayAccess(access) => ArrayAccess::NestedAccess {
+                access: Box::new(access),
+                index,
+            },
+            object => ArrayAccess::ObjectAccess {
+                object: Box::new(object),
+                index,
+            },
+        })
+    }
 
-            if !self.current_type().eq(&TokenTypes::RightSquareBracket) {
-                self.expected_error("]", self.current());
-                exit(1)
+    /// parse "text {expr} text", leaving current on the StringEnd
+    fn parse_interpolation(&mut self) -> Expression {
+        let mut parts: Vec<Expression> = Vec::new();
+        loop {
+            let text = self.current().token_value.to_owned();
+            if !text.is_empty() {
+                parts.push(Expression::Literal {
+                    r#type: LiteralTypes::String,
+                    value: text,
+                });
+            }
+            if self.current_type().eq(&TokenTypes::StringEnd) {
+                break;
             }
 
-            arr_access = match arr_access {
-                Some(acc) => Some(ArrayAccess::NestedAccess {
-                    access: Box::new(acc),
-                    index: Box::new(index),
-                }),
-                None => Some(ArrayAccess::Access {
-                    name: name.to_owned(),
-                    index: Box::new(index),
-                }),
-            };
-        }
+            self.advance();
+            if matches!(
+                self.current_type(),
+                TokenTypes::StringMiddle | TokenTypes::StringEnd
+            ) {
+                self.custom_error_current("Expected an expression between '{' and '}'");
+                abort()
+            }
+            // the braces end the expression, so struct literals are allowed even in conditions
+            parts.push(self.parse_expr_allowing_structs(true));
 
-        match arr_access {
-            Some(acc) => Expression::ArrayAccess(acc),
-            None => {
-                eprintln!("Expected Array access token, found internal error");
-                exit(1)
+            if !matches!(
+                self.peek_type(),
+                TokenTypes::StringMiddle | TokenTypes::StringEnd
+            ) {
+                let peek = self.peek().unwrap().to_owned();
+                self.expected_error("}", &peek);
+                abort()
             }
+            self.advance();
         }
+        Expression::Interpolation(parts)
     }
 
     fn parse_primary_expr(&mut self) -> Expression {
         let token = self.current().to_owned();
+        let negated = std::mem::take(&mut self.negated);
 
-        match token.token_type {
+        let mut expr = match token.token_type {
             TokenTypes::Identifier => self.parse_identifier(),
-            TokenTypes::NumberLiteral => Expression::Literal {
-                r#type: LiteralTypes::Numeric,
-                value: self.current().token_value.to_owned(),
-            },
+            TokenTypes::NumberLiteral => {
+                self.check_int_literal(&token, negated);
+                Expression::Literal {
+                    r#type: LiteralTypes::Numeric,
+                    value: self.current().token_value.to_owned(),
+                }
+            }
             TokenTypes::StringLiteral => Expression::Literal {
                 r#type: LiteralTypes::String,
                 value: self.current().token_value.to_owned(),
             },
+            TokenTypes::StringStart => self.parse_interpolation(),
+            TokenTypes::CharLiteral => Expression::Literal {
+                r#type: LiteralTypes::Character,
+                value: self.current().token_value.to_owned(),
+            },
             TokenTypes::True | TokenTypes::False => Expression::Literal {
                 r#type: LiteralTypes::Boolean,
                 value: self.current().token_value.to_owned(),
             },
+            TokenTypes::LeftParenthesis if self.is_lambda() => self.parse_lambda(),
             TokenTypes::LeftParenthesis => self.parse_parentheses(),
             TokenTypes::LeftSquareBracket => self.parse_square_brackets(),
             TokenTypes::Null => Expression::Literal {
@@ -664,23 +1040,64 @@ impl Parser {
             },
             TokenTypes::EOF => {
                 self.expected_error(";", &token);
-                exit(1)
+                abort()
             }
             _ => {
                 self.expected_error("Expression", &token);
-                exit(1)
+                abort()
+            }
+        };
+
+        // foo.bar.baz(), Enum.Variant() or foo.bar[0]
+        loop {
+            if self.peek_expect(&TokenTypes::LeftSquareBracket) {
+                expr = self.parse_array_access(expr);
+                continue;
+            }
+            if !self.peek_expect(&TokenTypes::Dot) {
+                break;
             }
+            self.advance();
+            self.expected_or_error(&TokenTypes::Identifier, "Identifier");
+            self.advance();
+
+            expr = match (self.peek_type().to_owned(), &expr) {
+                (TokenTypes::LeftCurlyBrace, Expression::Identifier(module))
+                    if self.struct_literals =>
+                {
+                    let name = format!("{}.{}", module, self.current().token_value);
+                    self.parse_struct_literal(name)
+                }
+                (TokenTypes::LeftParenthesis, _) => match self.parse_func_call() {
+                    Expression::Call { name, arguments } => Expression::MethodCall {
+                        object: Box::new(expr),
+                        name,
+                        arguments,
+                    },
+                    _ => abort(),
+                },
+                _ => Expression::FieldAccess {
+                    object: Box::new(expr),
+                    field: self.current().token_value.to_owned(),
+                },
+            };
         }
+
+        expr
     }
 
-    fn make_array_type(&mut self) -> VariableTypes {
-        self.advance();
-        match self.current_type() {
+    fn get_generic_type(&mut self) -> VariableTypes {
+        // Type
+        // peek < | unknown
+        let r#type = match self.current().token_type {
             TokenTypes::Int => VariableTypes::Int,
             TokenTypes::Str => VariableTypes::Str,
+            TokenTypes::Chr => VariableTypes::Chr,
             TokenTypes::Boo => VariableTypes::Boo,
             TokenTypes::Null => VariableTypes::Nul,
             TokenTypes::Flo => VariableTypes::Flo,
+            TokenTypes::Identifier => self.get_named_type(),
+            TokenTypes::Function => self.get_function_type(),
             TokenTypes::Arr => {
                 if !self.peek_expect(&TokenTypes::LogicalSmallerThan) {
                     report(
@@ -688,194 +1105,386 @@ impl Parser {
                         self.current().column_number,
                         String::from("Arr type must be generic, Arr<Type>"),
                     );
-                    exit(1)
-                }
-
-                self.advance();
-                let r#type = self.make_array_type();
-                self.expected_or_error(&TokenTypes::LogicalGreaterThan, ">");
-                self.advance();
-                VariableTypes::Arr(Box::new(r#type))
-            }
-            _ => {
-                self.unexpected_token_error(self.current());
-                exit(1)
-            }
-        }
-    }
-
-    fn get_generic_type(&mut self) -> VariableTypes {
-        // Type
-        // peek < | unknown
-        match self.current().token_type {
-            TokenTypes::Int => VariableTypes::Int,
-            TokenTypes::Str => VariableTypes::Str,
-            TokenTypes::Boo => VariableTypes::Boo,
-            TokenTypes::Null => VariableTypes::Nul,
-            TokenTypes::Flo => VariableTypes::Flo,
-            TokenTypes::Arr => {
-                if !self.peek_expect(&TokenTypes::LogicalSmallerThan) {
-                    report(
-                        self.current().line_number,
-                        self.current().column_number,
-                        String::from("Arr type must be generic, Arr<Type>"),
-                    );
-                    exit(1)
+                    abort()
                 }
                 self.advance();
                 self.advance();
 
                 let r#type = self.get_generic_type();
 
-                self.expected_or_error(&TokenTypes::LogicalGreaterThan, ">");
-                self.advance();
+                self.close_generic();
 
                 VariableTypes::Arr(Box::new(r#type))
             }
             _ => {
                 self.unexpected_token_error(self.current());
-                exit(1)
+                abort()
             }
+        };
+
+        self.get_optional_type(r#type)
+    }
+
+    /// moves to the '>' closing a generic type, 'vec<vec<i32>>' ends with a '>>' token
+    fn close_generic(&mut self) {
+        if self.peek_type().eq(&TokenTypes::BitwiseShiftRight) {
+            // the first half closes this type and the second one is left for the outer type
+            self.peek_nth(0);
+            let shift = self.lookahead.front_mut().unwrap();
+            let mut first = Token::new(
+                TokenTypes::LogicalGreaterThan,
+                String::from(">"),
+                shift.line_number,
+                shift.column_number,
+            );
+            first.span = Span {
+                end: shift.span.start + 1,
+                end_line: shift.line_number,
+                end_column: shift.column_number,
+                ..shift.span
+            };
+            // the trivia before '>>' is before the first '>'
+            first.trivia = std::mem::take(&mut shift.trivia);
+            if !shift.text.is_empty() {
+                first.text = String::from(">");
+                shift.text = String::from(">");
+            }
+            shift.token_type = TokenTypes::LogicalGreaterThan;
+            shift.token_value = String::from(">");
+            shift.column_number += 1;
+            shift.span.start += 1;
+            self.current_token = first;
+            return;
         }
+        self.expected_or_error(&TokenTypes::LogicalGreaterThan, ">");
+        self.advance();
     }
 
     fn get_type(&mut self) -> VariableTypes {
+        let r#type = self.get_plain_type();
+        self.get_optional_type(r#type)
+    }
+
+    /// a type without the '?' that makes it optional
+    fn get_plain_type(&mut self) -> VariableTypes {
         match self.current_type() {
             TokenTypes::Int => VariableTypes::Int,
             TokenTypes::Str => VariableTypes::Str,
+            TokenTypes::Chr => VariableTypes::Chr,
             TokenTypes::Boo => VariableTypes::Boo,
             TokenTypes::Null => VariableTypes::Nul,
             TokenTypes::Flo => VariableTypes::Flo,
+            TokenTypes::Identifier => self.get_named_type(),
+            TokenTypes::Function => self.get_function_type(),
             TokenTypes::Arr => {
                 self.expected_or_error(&TokenTypes::LogicalSmallerThan, "<");
                 self.advance();
                 self.advance();
                 let r#type = self.get_generic_type();
-                self.expected_or_error(&TokenTypes::LogicalGreaterThan, ">");
-                self.advance();
+                self.close_generic();
                 VariableTypes::Arr(Box::new(r#type))
             }
             _ => {
-                dbg!(self.current());
                 self.expected_error("Type", self.current());
-                exit(1)
+                abort()
+            }
+        }
+    }
+
+    /// func(i32, str): bool
+    fn get_function_type(&mut self) -> VariableTypes {
+        self.expected_or_error(&TokenTypes::LeftParenthesis, "(");
+        self.advance();
+
+        let mut params: Vec<VariableTypes> = Vec::new();
+        if !self.peek_expect(&TokenTypes::RightParenthesis) {
+            loop {
+                self.advance();
+                params.push(self.get_type());
+
+                self.advance();
+                match self.current_type() {
+                    TokenTypes::Comma => {}
+                    TokenTypes::RightParenthesis => break,
+                    _ => {
+                        self.expected_error(", or )", self.current());
+                        abort()
+                    }
+                }
             }
+        } else {
+            self.advance();
+        }
+
+        self.expected_or_error(&TokenTypes::Colon, ":");
+        self.advance();
+        self.advance();
+
+        VariableTypes::Func {
+            params,
+            r#type: Box::new(self.get_type()),
         }
     }
 
+    /// a struct or enum, Name or module.Name
+    fn get_named_type(&mut self) -> VariableTypes {
+        let name = self.current().token_value.to_owned();
+        if !self.peek_expect(&TokenTypes::Dot) {
+            return VariableTypes::Obj(name);
+        }
+        self.advance();
+        self.expected_or_error(&TokenTypes::Identifier, "Identifier");
+        self.advance();
+
+        VariableTypes::Obj(format!("{}.{}", name, self.current().token_value))
+    }
+
+    /// wraps the type if it is followed by '?', e.g. i32?
+    fn get_optional_type(&mut self, r#type: VariableTypes) -> VariableTypes {
+        if !self.peek_expect(&TokenTypes::QuestionMark) {
+            return r#type;
+        }
+        self.advance();
+
+        if r#type.eq(&VariableTypes::Nul) {
+            report(
+                self.current().line_number,
+                self.current().column_number,
+                String::from("null is already optional, remove this '?'"),
+            );
+            abort()
+        }
+        if self.peek_expect(&TokenTypes::QuestionMark) {
+            let peek = self.peek().unwrap().to_owned();
+            self.unexpected_token_error(&peek);
+            abort()
+        }
+
+        VariableTypes::Optional(Box::new(r#type))
+    }
+
     fn determine_var_type(&mut self, var_dec: &mut Statement) {
         match var_dec {
-            Statement::VariableDeclaration { r#type, .. } => match self.peek_type() {
-                TokenTypes::Int => {
-                    self.advance();
-                    self.advance();
-                    *r#type = Some(VariableTypes::Int);
-                }
-                TokenTypes::Str => {
-                    self.advance();
-                    self.advance();
-                    *r#type = Some(VariableTypes::Str);
-                }
-                TokenTypes::Boo => {
-                    self.advance();
-                    self.advance();
-                    *r#type = Some(VariableTypes::Boo);
-                }
-                TokenTypes::Null => {
-                    self.advance();
-                    self.advance();
-                    *r#type = Some(VariableTypes::Nul);
-                }
-                TokenTypes::Flo => {
-                    self.advance();
-                    self.advance();
-                    *r#type = Some(VariableTypes::Flo);
-                }
-                TokenTypes::Arr => {
-                    *r#type = Some(self.make_array_type());
-                    self.advance();
-                }
-                _ => {
+            Statement::VariableDeclaration { r#type, .. } => {
+                if !self.peek_is_type() {
                     let def = &self.current().to_owned();
-                    let peek = &self.peek().unwrap_or_else(|| &def).to_owned();
+                    let peek = &self.peek().unwrap_or(def).to_owned();
                     self.expected_error("Type", peek);
-                    exit(1)
+                    abort()
                 }
-            },
+                // current :
+                self.advance();
+                *r#type = Some(self.get_type());
+                self.advance();
+            }
             _ => {
                 eprintln!("Unknown error at variable declaration");
-                exit(1)
+                abort()
             }
         }
     }
 
+    /// if self.peek is a type return true
+    fn peek_is_type(&mut self) -> bool {
+        matches!(
+            self.peek_type(),
+            TokenTypes::Int
+                | TokenTypes::Str
+                | TokenTypes::Chr
+                | TokenTypes::Boo
+                | TokenTypes::Null
+                | TokenTypes::Flo
+                | TokenTypes::Arr
+                | TokenTypes::Identifier
+                | TokenTypes::Function
+        )
+    }
+
     fn parse_identifier(&mut self) -> Expression {
         match self.current_type() {
-            TokenTypes::Identifier => match self.peek_type() {
+            TokenTypes::Identifier => match self.peek_type().to_owned() {
                 TokenTypes::LeftParenthesis => self.parse_func_call(),
-                TokenTypes::LeftSquareBracket => self.parse_array_access(),
+                TokenTypes::LeftCurlyBrace if self.struct_literals => {
+                    let name = self.current().token_value.to_owned();
+                    self.parse_struct_literal(name)
+                }
                 _ => Expression::Identifier(self.current().token_value.to_owned()),
             },
             _ => {
                 self.expected_error("Identifier", self.current());
-                exit(1);
+                abort();
+            }
+        }
+    }
+
+    /// parse Name { field = value, ... } or module.Name { ... }
+    fn parse_struct_literal(&mut self, name: String) -> Expression {
+        // {
+        self.advance();
+        // field | }
+        self.advance();
+
+        let mut fields: Vec<FieldValue> = Vec::new();
+
+        while !self.current_type().eq(&TokenTypes::RightCurlyBrace) {
+            if !self.current_type().eq(&TokenTypes::Identifier) {
+                self.expected_error("Identifier", self.current());
+                abort()
+            }
+            let field = self.current().token_value.to_owned();
+
+            self.expected_or_error(&TokenTypes::Assign, "=");
+            self.advance();
+            self.expect_expr_or_error();
+            self.advance();
+
+            let value = self.parse_expr_allowing_structs(true);
+            fields.push(FieldValue { name: field, value });
+
+            if self.peek_expect(&TokenTypes::Comma) {
+                self.advance();
+                self.advance();
+            } else if self.peek_expect(&TokenTypes::RightCurlyBrace) {
+                self.advance();
+            } else {
+                let peek = self.peek().unwrap().to_owned();
+                self.expected_error(", or }", &peek);
+                abort()
             }
         }
+
+        Expression::StructLiteral {
+            name,
+            fields: if fields.is_empty() {
+                None
+            } else {
+                Some(fields)
+            },
+        }
     }
 
     fn is_binary_operator(&self, token_type: &TokenTypes) -> bool {
-        match token_type {
+        matches!(
+            token_type,
             TokenTypes::BinaryPlus
-            | TokenTypes::BinaryMinus
-            | TokenTypes::BinaryDivision
-            | TokenTypes::BinaryMultiply
-            | TokenTypes::BinaryRest
-            | TokenTypes::LogicalOr
-            | TokenTypes::LogicalAnd
-            | TokenTypes::LogicalEquals
-            | TokenTypes::LogicalDifferent => true,
-            _ => false,
-        }
+                | TokenTypes::BinaryMinus
+                | TokenTypes::BinaryDivision
+                | TokenTypes::BinaryMultiply
+                | TokenTypes::BinaryRest
+                | TokenTypes::BitwiseAnd
+                | TokenTypes::BitwiseOr
+                | TokenTypes::BitwiseXor
+                | TokenTypes::BitwiseShiftLeft
+                | TokenTypes::BitwiseShiftRight
+                | TokenTypes::LogicalOr
+                | TokenTypes::LogicalAnd
+                | TokenTypes::LogicalEquals
+                | TokenTypes::LogicalDifferent
+        )
     }
 
     fn parse_call_statement(&mut self) -> Statement {
+        let start = Start {
+            line: self.current().line_number,
+            column: self.current().column_number,
+        };
         let call = self.parse_func_call();
 
         if !self.peek_expect(&TokenTypes::Semicolon) {
             let peek = self.peek().unwrap().to_owned();
             self.expected_error(";", &peek);
-            exit(1)
+            abort()
         }
 
         self.advance();
 
-        Statement::FunctionCall(call)
+        Statement::FunctionCall { start, call }
     }
 
     fn parse_var_mutation(&mut self) -> Statement {
+        let start = Start {
+            line: self.current().line_number,
+            column: self.current().column_number,
+        };
         let name = self.current().token_value.to_owned();
         self.advance();
 
         let operator = self.current().to_owned();
-        self.advance();
-
-        let expr = self.parse_expr();
+        let expr = self.parse_assigned_value(&operator.token_type);
 
         if !self.peek_expect(&TokenTypes::Semicolon) {
             let peek = self.peek().unwrap().to_owned();
             self.expected_error(";", &peek);
-            exit(1)
+            abort()
         }
         self.advance();
         self.advance();
 
         Statement::VariableAlteration {
+            start,
             name,
             value: expr,
             operator: operator.token_type,
         }
     }
 
+    /// the value after the assignment operator, 'x++' adds 1 and has no value to parse
+    fn parse_assigned_value(&mut self, operator: &TokenTypes) -> Expression {
+        if let TokenTypes::BinaryIncrement | TokenTypes::BinaryDecrement = operator {
+            return Expression::Literal {
+                r#type: LiteralTypes::Numeric,
+                value: String::from("1"),
+            };
+        }
+        self.expect_expr_or_error();
+        self.advance();
+        self.parse_expr()
+    }
+
+    fn parse_place_mutation(&mut self) -> Statement {
+        let start = Start {
+            line: self.current().line_number,
+            column: self.current().column_number,
+        };
+        let target = self.parse_primary_expr();
+
+        // foo.bar() or xs.push(1)
+        if let Expression::MethodCall { .. } = target {
+            self.expected_or_error(&TokenTypes::Semicolon, ";");
+            self.advance();
+            self.advance();
+            return Statement::FunctionCall {
+                start,
+                call: target,
+            };
+        }
+
+        let peek = self.peek().unwrap().to_owned();
+        if !self.is_assign_operator(&peek.token_type) {
+            self.expected_error("=", &peek);
+            abort()
+        }
+        self.advance();
+
+        let operator = self.current().to_owned();
+        let expr = self.parse_assigned_value(&operator.token_type);
+
+        if !self.peek_expect(&TokenTypes::Semicolon) {
+            let peek = self.peek().unwrap().to_owned();
+            self.expected_error(";", &peek);
+            abort()
+        }
+        self.advance();
+        self.advance();
+
+        Statement::PlaceAlteration {
+            start,
+            target,
+            operator: operator.token_type,
+            value: expr,
+        }
+    }
+
     fn parse_params(&mut self) -> Vec<FuncParam> {
         // (
         self.advance();
@@ -894,7 +1503,7 @@ impl Parser {
         {
             if !self.current_type().eq(&TokenTypes::Identifier) {
                 self.expected_error("Identifier", self.current());
-                exit(1)
+                abort()
             }
 
             let name = self.current().to_owned().token_value;
@@ -917,13 +1526,13 @@ impl Parser {
                 self.advance();
             } else {
                 self.expected_error(", or )", self.current());
-                exit(1)
+                abort()
             }
         }
 
         if !self.current_type().eq(&TokenTypes::RightParenthesis) {
             self.expected_error(")", self.current());
-            exit(1)
+            abort()
         }
 
         params
@@ -931,6 +1540,7 @@ impl Parser {
 
     fn parse_function_statement(&mut self) -> Statement {
         let func_tk = self.current().to_owned();
+        let doc = self.doc.take();
 
         self.expected_or_error(&TokenTypes::Identifier, "Identifier");
         self.advance();
@@ -966,26 +1576,392 @@ impl Parser {
                 column: func_tk.column_number,
             },
             name: name.token_value,
-            params: if param_vec.len() > 0 {
-                Some(param_vec)
-            } else {
+            params: if param_vec.is_empty() {
                 None
+            } else {
+                Some(param_vec)
             },
             r#type,
-            body: match body_block {
-                Some(b) => Some(Box::new(b)),
-                _ => None,
+            body: body_block,
+            doc,
+        }
+    }
+
+    /// parse obj Name { field: Type, ... };
+    fn parse_struct_statement(&mut self) -> Statement {
+        let obj_tk = self.current().to_owned();
+        let doc = self.doc.take();
+
+        self.expected_or_error(&TokenTypes::Identifier, "Identifier");
+        self.advance();
+        let name = self.current().token_value.to_owned();
+
+        self.expected_or_error(&TokenTypes::LeftCurlyBrace, "{");
+        self.advance();
+        // field | }
+        self.advance();
+
+        let mut fields: Vec<StructField> = Vec::new();
+
+        while !self.current_type().eq(&TokenTypes::RightCurlyBrace) {
+            let field_doc = self.parse_doc_comment();
+            if !self.current_type().eq(&TokenTypes::Identifier) {
+                self.expected_error("Identifier", self.current());
+                abort()
+            }
+            let field = self.current().token_value.to_owned();
+
+            self.expected_or_error(&TokenTypes::Colon, ":");
+            self.advance();
+            self.advance();
+
+            let r#type = self.get_type();
+            fields.push(StructField {
+                name: field,
+                r#type,
+                doc: field_doc,
+            });
+
+            if self.peek_expect(&TokenTypes::Comma) {
+                self.advance();
+                self.advance();
+            } else if self.peek_expect(&TokenTypes::RightCurlyBrace) {
+                self.advance();
+            } else {
+                let peek = self.peek().unwrap().to_owned();
+                self.expected_error(", or }", &peek);
+                abort()
+            }
+        }
+
+        if fields.is_empty() {
+            report(
+                obj_tk.line_number,
+                obj_tk.column_number,
+                String::from("Structs must have at least one field"),
+            );
+            abort()
+        }
+
+        self.expected_or_error(&TokenTypes::Semicolon, ";");
+        self.advance();
+
+        Statement::StructDeclaration {
+            start: Start {
+                line: obj_tk.line_number,
+                column: obj_tk.column_number,
             },
+            name,
+            fields,
+            doc,
+        }
+    }
+
+    /// joins the lines of consecutive /// comments, current is the token after them
+    fn parse_doc_comment(&mut self) -> Option<String> {
+        let mut lines: Vec<String> = Vec::new();
+        while self.current_type().eq(&TokenTypes::DocComment) {
+            lines.push(self.current().token_value.to_owned());
+            self.advance();
+        }
+        if lines.is_empty() {
+            None
+        } else {
+            Some(lines.join("\n"))
+        }
+    }
+
+    /// parse use name from 'path';
+    fn parse_use_statement(&mut self) -> Statement {
+        let use_tk = self.current().to_owned();
+
+        self.expected_or_error(&TokenTypes::Identifier, "Identifier");
+        self.advance();
+        let name = self.current().token_value.to_owned();
+
+        self.expected_or_error(&TokenTypes::From, "from");
+        self.advance();
+        self.expected_or_error(&TokenTypes::StringLiteral, "StringLiteral");
+        self.advance();
+        let path = self.current().token_value.to_owned();
+
+        self.expected_or_error(&TokenTypes::Semicolon, ";");
+        self.advance();
+
+        Statement::Use {
+            start: Start {
+                line: use_tk.line_number,
+                column: use_tk.column_number,
+            },
+            name,
+            path,
+            module: None,
+        }
+    }
+
+    /// parse enum Name { Variant, Variant(Type, ...), ... };
+    fn parse_enum_statement(&mut self) -> Statement {
+        let enum_tk = self.current().to_owned();
+        let doc = self.doc.take();
+
+        self.expected_or_error(&TokenTypes::Identifier, "Identifier");
+        self.advance();
+        let name = self.current().token_value.to_owned();
+
+        self.expected_or_error(&TokenTypes::LeftCurlyBrace, "{");
+        self.advance();
+        // variant | }
+        self.advance();
+
+        let mut variants: Vec<EnumVariant> = Vec::new();
+
+        while !self.current_type().eq(&TokenTypes::RightCurlyBrace) {
+            let variant_doc = self.parse_doc_comment();
+            if !self.current_type().eq(&TokenTypes::Identifier) {
+                self.expected_error("Identifier", self.current());
+                abort()
+            }
+            let variant = self.current().token_value.to_owned();
+
+            let mut payload: Vec<VariableTypes> = Vec::new();
+            if self.peek_expect(&TokenTypes::LeftParenthesis) {
+                self.advance();
+                loop {
+                    if !self.peek_is_type() {
+                        let peek = self.peek().unwrap().to_owned();
+                        self.expected_error("Type", &peek);
+                        abort()
+                    }
+                    self.advance();
+                    payload.push(self.get_type());
+
+                    self.advance();
+                    match self.current_type() {
+                        TokenTypes::Comma => {}
+                        TokenTypes::RightParenthesis => break,
+                        _ => {
+                            self.expected_error(", or )", self.current());
+                            abort()
+                        }
+                    }
+                }
+            }
+
+            variants.push(EnumVariant {
+                name: variant,
+                payload: if payload.is_empty() {
+                    None
+                } else {
+                    Some(payload)
+                },
+                doc: variant_doc,
+            });
+
+            if self.peek_expect(&TokenTypes::Comma) {
+                self.advance();
+                self.advance();
+            } else if self.peek_expect(&TokenTypes::RightCurlyBrace) {
+                self.advance();
+            } else {
+                let peek = self.peek().unwrap().to_owned();
+                self.expected_error(", or }", &peek);
+                abort()
+            }
+        }
+
+        if variants.is_empty() {
+            report(
+                enum_tk.line_number,
+                enum_tk.column_number,
+                String::from("Enums must have at least one variant"),
+            );
+            abort()
+        }
+
+        self.expected_or_error(&TokenTypes::Semicolon, ";");
+        self.advance();
+
+        Statement::EnumDeclaration {
+            start: Start {
+                line: enum_tk.line_number,
+                column: enum_tk.column_number,
+            },
+            name,
+            variants,
+            doc,
+        }
+    }
+
+    /// parse switch Expr { case Pattern { ... }; casenot { ... }; };
+    fn parse_switch_stmt(&mut self, is_loop: &Loop) -> Statement {
+        let initial = self.current().to_owned();
+
+        self.expect_expr_or_error();
+        self.advance();
+
+        let subject = self.parse_condition();
+
+        self.expected_or_error(&TokenTypes::LeftCurlyBrace, "{");
+        self.advance();
+        // case | casenot | }
+        self.advance();
+
+        let mut cases: Vec<SwitchCase> = Vec::new();
+        let mut has_casenot = false;
+
+        while !self.current_type().eq(&TokenTypes::RightCurlyBrace) {
+            let start = Start {
+                line: self.current().line_number,
+                column: self.current().column_number,
+            };
+            if has_casenot {
+                report(
+                    start.line,
+                    start.column,
+                    String::from("casenot must be the last case of a switch"),
+                );
+                abort()
+            }
+
+            let patterns = match self.current_type() {
+                TokenTypes::Case => {
+                    self.advance();
+                    self.parse_patterns()
+                }
+                TokenTypes::CaseNot => {
+                    has_casenot = true;
+                    self.expected_or_error(&TokenTypes::LeftCurlyBrace, "{");
+                    self.advance();
+                    Vec::new()
+                }
+                _ => {
+                    self.expected_error("case or casenot", self.current());
+                    abort()
+                }
+            };
+            // curr {
+            let block = self.parse_block(is_loop);
+            // curr }
+
+            self.expected_or_error(&TokenTypes::Semicolon, ";");
+            self.advance();
+            self.advance();
+
+            cases.push(SwitchCase {
+                start,
+                patterns,
+                block,
+            });
+        }
+
+        self.expected_or_error(&TokenTypes::Semicolon, ";");
+        self.advance();
+        self.advance();
+
+        Statement::Switch {
+            start: Start {
+                line: initial.line_number,
+                column: initial.column_number,
+            },
+            subject,
+            cases,
         }
     }
 
+    /// parse 1, -2 | 'a', 'b' | Enum.Variant(binding, ...) up to the {
+    fn parse_patterns(&mut self) -> Vec<Pattern> {
+        let mut patterns: Vec<Pattern> = Vec::new();
+
+        loop {
+            let pattern = match self.current_type() {
+                TokenTypes::NumberLiteral => Pattern::Number(self.current().token_value.to_owned()),
+                TokenTypes::BinaryMinus => {
+                    self.expected_or_error(&TokenTypes::NumberLiteral, "NumericLiteral");
+                    self.advance();
+                    Pattern::Number(format!("-{}", self.current().token_value))
+                }
+                TokenTypes::StringLiteral => Pattern::String(self.current().token_value.to_owned()),
+                TokenTypes::Identifier => {
+                    let mut r#enum = self.current().token_value.to_owned();
+                    self.expected_or_error(&TokenTypes::Dot, ".");
+                    self.advance();
+                    self.expected_or_error(&TokenTypes::Identifier, "Identifier");
+                    self.advance();
+                    let mut variant = self.current().token_value.to_owned();
+
+                    // 'module.Enum.Variant'
+                    if self.peek_expect(&TokenTypes::Dot) {
+                        self.advance();
+                        self.expected_or_error(&TokenTypes::Identifier, "Identifier");
+                        self.advance();
+                        r#enum = format!("{}.{}", r#enum, variant);
+                        variant = self.current().token_value.to_owned();
+                    }
+
+                    let mut bindings: Vec<String> = Vec::new();
+                    if self.peek_expect(&TokenTypes::LeftParenthesis) {
+                        self.advance();
+                        loop {
+                            self.expected_or_error(&TokenTypes::Identifier, "Identifier");
+                            self.advance();
+                            bindings.push(self.current().token_value.to_owned());
+
+                            self.advance();
+                            match self.current_type() {
+                                TokenTypes::Comma => {}
+                                TokenTypes::RightParenthesis => break,
+                                _ => {
+                                    self.expected_error(", or )", self.current());
+                                    abort()
+                                }
+                            }
+                        }
+                    }
+
+                    Pattern::Variant {
+                        r#enum,
+                        variant,
+                        bindings: if bindings.is_empty() {
+                            None
+                        } else {
+                            Some(bindings)
+                        },
+                    }
+                }
+                _ => {
+                    self.expected_error("Pattern", self.current());
+                    abort()
+                }
+            };
+            patterns.push(pattern);
+
+            match self.peek_type() {
+                TokenTypes::Comma => {
+                    self.advance();
+                    self.advance();
+                }
+                TokenTypes::LeftCurlyBrace => {
+                    self.advance();
+                    break;
+                }
+                _ => {
+                    let peek = self.peek().unwrap().to_owned();
+                    self.expected_error("{", &peek);
+                    abort()
+                }
+            }
+        }
+
+        patterns
+    }
+
     fn parse_if_stmt(&mut self, is_loop: &Loop) -> Statement {
         let first = self.current().to_owned();
 
         self.expect_expr_or_error();
         self.advance();
 
-        let expression = self.parse_expr();
+        let expression = self.parse_condition();
 
         self.expected_or_error(&TokenTypes::LeftCurlyBrace, "{");
         self.advance();
@@ -1004,10 +1980,7 @@ impl Parser {
                         column: first.column_number,
                     },
                     condition: expression,
-                    block: match block {
-                        Some(b) => Some(Box::new(b)),
-                        None => None,
-                    },
+                    block,
                     alternate: None,
                 }
             }
@@ -1019,10 +1992,7 @@ impl Parser {
                         column: first.column_number,
                     },
                     condition: expression,
-                    block: match block {
-                        Some(b) => Some(Box::new(b)),
-                        None => None,
-                    },
+                    block,
                     alternate: Some(Box::new(self.parse_elseif_stmt(is_loop))),
                 }
             }
@@ -1034,17 +2004,14 @@ impl Parser {
                         column: first.column_number,
                     },
                     condition: expression,
-                    block: match block {
-                        Some(b) => Some(Box::new(b)),
-                        None => None,
-                    },
+                    block,
                     alternate: Some(Box::new(self.parse_else_stmt(is_loop))),
                 }
             }
             _ => {
                 let peek = self.peek().unwrap().to_owned();
                 self.expected_error("; or elseif or else", &peek);
-                exit(1)
+                abort()
             }
         }
     }
@@ -1055,7 +2022,7 @@ impl Parser {
         self.expect_expr_or_error();
         self.advance();
 
-        let expression = self.parse_expr();
+        let expression = self.parse_condition();
 
         self.expected_or_error(&TokenTypes::LeftCurlyBrace, "{");
         self.advance();
@@ -1074,10 +2041,7 @@ impl Parser {
                         column: first.column_number,
                     },
                     condition: expression,
-                    block: match block {
-                        Some(b) => Some(Box::new(b)),
-                        None => None,
-                    },
+                    block,
                     alternate: None,
                 }
             }
@@ -1089,10 +2053,7 @@ impl Parser {
                         column: first.column_number,
                     },
                     condition: expression,
-                    block: match block {
-                        Some(b) => Some(Box::new(b)),
-                        None => None,
-                    },
+                    block,
                     alternate: Some(Box::new(self.parse_elseif_stmt(is_loop))),
                 }
             }
@@ -1104,17 +2065,14 @@ impl Parser {
                         column: first.column_number,
                     },
                     condition: expression,
-                    block: match block {
-                        Some(b) => Some(Box::new(b)),
-                        None => None,
-                    },
+                    block,
                     alternate: Some(Box::new(self.parse_else_stmt(is_loop))),
                 }
             }
             _ => {
                 let peek = self.peek().unwrap().to_owned();
                 self.expected_error("; or elseif or else", &peek);
-                exit(1)
+                abort()
             }
         }
     }
@@ -1136,10 +2094,7 @@ impl Parser {
                         line: first.line_number,
                         column: first.column_number,
                     },
-                    block: match block {
-                        Some(b) => Some(Box::new(b)),
-                        None => None,
-                    },
+                    block,
                 }
             }
             TokenTypes::If => {
@@ -1148,7 +2103,7 @@ impl Parser {
                     self.current().column_number,
                     String::from("If statements cannot go after else"),
                 );
-                exit(1)
+                abort()
             }
             TokenTypes::ElseIf => {
                 report(
@@ -1156,12 +2111,12 @@ impl Parser {
                     self.current().column_number,
                     String::from("ElseIf statements cannot go after else"),
                 );
-                exit(1)
+                abort()
             }
             _ => {
                 let peek = self.peek().unwrap().to_owned();
                 self.expected_error(";", &peek);
-                exit(1)
+                abort()
             }
         }
     }
@@ -1172,7 +2127,7 @@ impl Parser {
         self.expect_expr_or_error();
         self.advance();
 
-        let test = self.parse_expr();
+        let test = self.parse_condition();
 
         self.expected_or_error(&TokenTypes::LeftCurlyBrace, "{");
         self.advance();
@@ -1190,47 +2145,50 @@ impl Parser {
                 column: initial.column_number,
             },
             test,
-            block: match block {
-                Some(b) => Some(Box::new(b)),
-                None => None,
-            },
+            block,
         }
     }
 
     /// if self.current if valid expr return true
     fn is_expr(&mut self) -> bool {
-        match self.current_type() {
+        matches!(
+            self.current_type(),
             TokenTypes::Identifier
-            | TokenTypes::NumberLiteral
-            | TokenTypes::StringLiteral
-            | TokenTypes::BinaryPlus
-            | TokenTypes::BinaryMinus
-            | TokenTypes::LogicalNot
-            | TokenTypes::True
-            | TokenTypes::False
-            | TokenTypes::Null
-            | TokenTypes::LeftSquareBracket
-            | TokenTypes::LeftParenthesis => true,
-            _ => false,
-        }
+                | TokenTypes::NumberLiteral
+                | TokenTypes::StringLiteral
+                | TokenTypes::StringStart
+                | TokenTypes::CharLiteral
+                | TokenTypes::BinaryPlus
+                | TokenTypes::BinaryMinus
+                | TokenTypes::LogicalNot
+                | TokenTypes::BitwiseNot
+                | TokenTypes::True
+                | TokenTypes::False
+                | TokenTypes::Null
+                | TokenTypes::LeftSquareBracket
+                | TokenTypes::LeftParenthesis
+        )
     }
 
     /// if self.peek is valid expr return true
     fn peek_is_expr(&mut self) -> bool {
-        match self.peek_type() {
+        matches!(
+            self.peek_type(),
             TokenTypes::Identifier
-            | TokenTypes::NumberLiteral
-            | TokenTypes::StringLiteral
-            | TokenTypes::BinaryPlus
-            | TokenTypes::BinaryMinus
-            | TokenTypes::LogicalNot
-            | TokenTypes::True
-            | TokenTypes::False
-            | TokenTypes::Null
-            | TokenTypes::LeftSquareBracket
-            | TokenTypes::LeftParenthesis => true,
-            _ => false,
-        }
+                | TokenTypes::NumberLiteral
+                | TokenTypes::StringLiteral
+                | TokenTypes::StringStart
+                | TokenTypes::CharLiteral
+                | TokenTypes::BinaryPlus
+                | TokenTypes::BinaryMinus
+                | TokenTypes::LogicalNot
+                | TokenTypes::BitwiseNot
+                | TokenTypes::True
+                | TokenTypes::False
+                | TokenTypes::Null
+                | TokenTypes::LeftSquareBracket
+                | TokenTypes::LeftParenthesis
+        )
     }
 
     fn parse_func_return(&mut self) -> Statement {
@@ -1261,7 +2219,7 @@ impl Parser {
         } else {
             let peek = self.peek().unwrap().to_owned();
             self.expected_error("Expression or ;", &peek);
-            exit(1)
+            abort()
         }
     }
 
@@ -1271,13 +2229,22 @@ impl Parser {
             column: self.current().column_number,
         };
 
+        // for x in xs {
+        if self.peek_type().eq(&TokenTypes::Identifier)
+            && self
+                .peek_nth(1)
+                .is_some_and(|t| t.token_type.eq(&TokenTypes::In))
+        {
+            return self.parse_for_in_loop(start, is_loop);
+        }
+
         if self.peek_expect(&TokenTypes::ConstantVariable) {
             report(
                 self.current().line_number,
                 self.current().column_number,
                 String::from("Immutable variables cannot be used inside a loop variablechange 'let' to 'mut'")
             );
-            exit(1);
+            abort();
         }
         self.advance();
 
@@ -1289,7 +2256,7 @@ impl Parser {
             variable = None;
         } else {
             self.unexpected_token_error(self.current());
-            exit(1)
+            abort()
         }
         // curr ;
 
@@ -1297,12 +2264,12 @@ impl Parser {
 
         if self.peek_is_expr() {
             self.advance();
-            test = Some(self.parse_expr());
+            test = Some(self.parse_condition());
         } else if self.current_type().eq(&TokenTypes::Semicolon) {
             test = None;
         } else {
             self.unexpected_token_error(self.current());
-            exit(1)
+            abort()
         }
         self.advance();
         // curr test; >i += 1<;
@@ -1320,12 +2287,12 @@ impl Parser {
             variable_update = None;
         } else {
             self.unexpected_token_error(self.current());
-            exit(1)
+            abort()
         }
 
         if !self.current_type().eq(&TokenTypes::LeftCurlyBrace) {
             self.expected_error("{", self.current());
-            exit(1)
+            abort()
         }
 
         let block = self.parse_block(is_loop);
@@ -1335,22 +2302,53 @@ impl Parser {
 
         Statement::For {
             start,
-            variable: match variable {
-                Some(v) => Some(Box::new(v)),
-                None => None,
-            },
-            test: match test {
-                Some(v) => Some(v),
-                None => None,
-            },
-            variable_update: match variable_update {
-                Some(v) => Some(Box::new(v)),
-                None => None,
-            },
-            block: match block {
-                Some(b) => Some(Box::new(b)),
-                None => None,
-            },
+            variable: variable.map(Box::new),
+            test,
+            variable_update: variable_update.map(Box::new),
+            block,
+        }
+    }
+
+    fn parse_for_in_loop(&mut self, start: Start, is_loop: &Loop) -> Statement {
+        self.advance();
+        let name = self.current().token_value.to_owned();
+        self.advance();
+        // curr in
+
+        self.expect_expr_or_error();
+        self.advance();
+        let mut values = self.parse_condition();
+
+        if matches!(
+            self.peek_type(),
+            TokenTypes::Range | TokenTypes::RangeInclusive
+        ) {
+            self.advance();
+            let inclusive = self.current_type().eq(&TokenTypes::RangeInclusive);
+            self.expect_expr_or_error();
+            self.advance();
+            values = Expression::Range {
+                from: Box::new(values),
+                to: Box::new(self.parse_condition()),
+                inclusive,
+            };
+        }
+
+        self.expected_or_error(&TokenTypes::LeftCurlyBrace, "{");
+        self.advance();
+
+        let block = self.parse_block(is_loop);
+        // curr }
+
+        self.expected_or_error(&TokenTypes::Semicolon, ";");
+        self.advance();
+        self.advance();
+
+        Statement::ForIn {
+            start,
+            name,
+            values,
+            block,
         }
     }
 
@@ -1360,7 +2358,7 @@ impl Parser {
             _ => {
                 let peek = self.peek().unwrap().to_owned();
                 self.expected_error(";", &peek);
-                exit(1)
+                abort()
             }
         }
         match self.current_type() {
@@ -1388,7 +2386,7 @@ impl Parser {
             }
             _ => {
                 self.expected_error("brk or cnt", self.current());
-                exit(1)
+                abort()
             }
         }
     }
@@ -1399,7 +2397,7 @@ impl Parser {
                 VarDecMutateOptions::Name => *name = Some(self.current().token_value.to_owned()),
                 VarDecMutateOptions::Value(val) => *value = Some(val),
             },
-            _ => exit(1),
+            _ => abort(),
         }
     }
 
@@ -1409,9 +2407,12 @@ impl Parser {
             TokenTypes::Identifier
             | TokenTypes::NumberLiteral
             | TokenTypes::StringLiteral
+            | TokenTypes::StringStart
+            | TokenTypes::CharLiteral
             | TokenTypes::BinaryPlus
             | TokenTypes::BinaryMinus
             | TokenTypes::LogicalNot
+            | TokenTypes::BitwiseNot
             | TokenTypes::True
             | TokenTypes::False
             | TokenTypes::Null
@@ -1437,9 +2438,9 @@ impl Parser {
     fn expected_or_error(&mut self, expected: &TokenTypes, expected_name: &str) {
         if !self.peek_expect(expected) {
             let def = self.current().to_owned();
-            let peek = self.peek().unwrap_or_else(|| &def).to_owned();
+            let peek = self.peek().unwrap_or(&def).to_owned();
             self.expected_error(expected_name, &peek);
-            exit(1)
+            abort()
         }
     }
 
@@ -1455,20 +2456,22 @@ impl Parser {
             self.mutate_var_declaration(var_dec, option);
         } else {
             self.advance();
-            self.expected_error(expected, &self.current());
+            self.expected_error(expected, self.current());
         }
     }
 
     fn is_assign_operator(&self, operator: &TokenTypes) -> bool {
-        match operator {
+        matches!(
+            operator,
             TokenTypes::Assign
-            | TokenTypes::AssignPlus
-            | TokenTypes::AssignMinus
-            | TokenTypes::AssignMultiply
-            | TokenTypes::AssignDivision
-            | TokenTypes::AssignRest => true,
-            _ => false,
-        }
+                | TokenTypes::AssignPlus
+                | TokenTypes::AssignMinus
+                | TokenTypes::AssignMultiply
+                | TokenTypes::AssignDivision
+                | TokenTypes::AssignRest
+                | TokenTypes::BinaryIncrement
+                | TokenTypes::BinaryDecrement
+        )
     }
 
     /// eprints a custom error related to self.current_token
@@ -1500,12 +2503,17 @@ impl Parser {
     fn advance(&mut self) {
         match self.current().token_type {
             TokenTypes::EOF => {}
-            _ => self.current_token = self.tokens.next().unwrap(),
+            _ => {
+                self.current_token = match self.lookahead.pop_front() {
+                    Some(token) => token,
+                    None => self.next_token().unwrap(),
+                }
+            }
         }
     }
 
     fn peek_type(&mut self) -> &TokenTypes {
-        match self.tokens.peek() {
+        match self.peek_nth(0) {
             Some(t) => &t.token_type,
             None => &TokenTypes::EOF,
         }
@@ -1516,13 +2524,36 @@ impl Parser {
         self.peek_type().eq(expected)
     }
 
+    /// an integer literal that does not fit in an i32, floats can be any size
+    fn check_int_literal(&self, token: &Token, negated: bool) {
+        let digits = token.token_value.replace('_', "");
+        if radix(&digits) == 10 && digits.contains(['.', 'e', 'E']) {
+            return;
+        }
+        let digits = match negated {
+            true => format!("-{}", digits),
+            false => digits,
+        };
+        if parse_int_literal(&digits).is_none() {
+            error(
+                token.line_number,
+                token.column_number,
+                format!(
+                    "Number literal '{}' does not fit in 32 bits",
+                    token.token_value
+                ),
+            );
+            abort();
+        }
+    }
+
     fn expected_error(&self, expected: &str, found: &Token) {
         error(
             found.line_number,
             found.column_number,
             format!("Expected '{}', found '{}'", expected, found.token_type),
         );
-        exit(1);
+        abort();
     }
 
     fn unknown_error(&self, token: &Token) {
@@ -1531,19 +2562,16 @@ impl Parser {
             token.column_number,
             format!("Unknown token '{}'", token.token_value),
         );
-        exit(1);
+        abort();
     }
 
     fn peek(&mut self) -> Option<&Token> {
-        self.tokens.peek()
+        self.peek_nth(0)
     }
 
     fn push_statement(&mut self, stmt: Statement) {
-        match &mut self.abstract_syntax_tree {
-            Statement::Program { body, .. } => {
-                body.push(stmt);
-            }
-            _ => {}
+        if let Statement::Program { body, .. } = &mut self.abstract_syntax_tree {
+            body.push(stmt);
         }
     }
 }
@@ -0,0 +1,605 @@
+use crate::frontend::types::{
+    ArrayAccess, EnumVariant, Expression, FuncParam, LiteralTypes, Pattern, Statement, StructField,
+    SwitchCase, TokenTypes, VarDeclarationKind,
+};
+
+/// the program as bline source in the style of docs/conventions, parsing it gives the same tree
+pub fn to_source(program: &Statement) -> String {
+    let mut printer = Printer {
+        out: String::new(),
+        indentation: 0,
+    };
+    printer.statement(program);
+    printer.out
+}
+
+/// how tightly an operator binds, following the table at the top of parser/mod.rs
+fn operator_precedence(operator: &TokenTypes) -> u8 {
+    match operator {
+        TokenTypes::LogicalOr => 3,
+        TokenTypes::LogicalAnd => 4,
+        TokenTypes::BitwiseOr => 5,
+        TokenTypes::BitwiseXor => 6,
+        TokenTypes::BitwiseAnd => 7,
+        TokenTypes::LogicalEquals | TokenTypes::LogicalDifferent => 8,
+        TokenTypes::LogicalSmallerThan
+        | TokenTypes::LogicalSmallerOrEqualsThan
+        | TokenTypes::LogicalGreaterThan
+        | TokenTypes::LogicalGreaterOrEqualsThan => 9,
+        TokenTypes::BitwiseShiftLeft | TokenTypes::BitwiseShiftRight => 10,
+        TokenTypes::BinaryPlus | TokenTypes::BinaryMinus => 11,
+        TokenTypes::BinaryMultiply | TokenTypes::BinaryDivision | TokenTypes::BinaryRest => 12,
+        _ => 15,
+    }
+}
+
+fn precedence(expression: &Expression) -> u8 {
+    match expression {
+        // a lambda with an expression body takes everything after the =>
+        Expression::Lambda { .. } => 1,
+        Expression::Ternary { .. } => 2,
+        Expression::Binary { operator, .. } | Expression::Logical { operator, .. } => {
+            operator_precedence(operator)
+        }
+        Expression::Cast { .. } => 13,
+        Expression::Unary { .. } => 14,
+        _ => 15,
+    }
+}
+
+/// str literals in double quotes, '{' and '}' are escaped so they don't start an interpolation
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '{' => quoted.push_str("\\{"),
+            '}' => quoted.push_str("\\}"),
+            c => quoted.push_str(&escape(c)),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn quote_char(c: char) -> String {
+    match c {
+        '\'' => String::from("'\\''"),
+        c => format!("'{}'", escape(c)),
+    }
+}
+
+fn escape(c: char) -> String {
+    match c {
+        '\\' => String::from("\\\\"),
+        '\n' => String::from("\\n"),
+        '\r' => String::from("\\r"),
+        '\t' => String::from("\\t"),
+        '\0' => String::from("\\0"),
+        c if c.is_ascii_control() => format!("\\x{:02X}", c as u32),
+        c if c.is_control() => format!("\\u{{{:X}}}", c as u32),
+        c => c.to_string(),
+    }
+}
+
+fn params(params: &Option<Vec<FuncParam>>) -> String {
+    let params: Vec<String> = params
+        .iter()
+        .flatten()
+        .map(|param| format!("{}: {}", param.name, param.r#type))
+        .collect();
+    params.join(", ")
+}
+
+fn pattern(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::Number(number) => number.to_owned(),
+        Pattern::String(string) => quote(string),
+        Pattern::Variant {
+            r#enum,
+            variant,
+            bindings: Some(bindings),
+        } => format!("{}.{}({})", r#enum, variant, bindings.join(", ")),
+        Pattern::Variant {
+            r#enum, variant, ..
+        } => format!("{}.{}", r#enum, variant),
+    }
+}
+
+struct Printer {
+    out: String,
+    indentation: usize,
+}
+
+impl Printer {
+    fn line(&mut self, text: &str) {
+        self.out.push_str(&"    ".repeat(self.indentation));
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn doc(&mut self, doc: &Option<String>) {
+        for line in doc.iter().flat_map(|doc| doc.split('\n')) {
+            if line.is_empty() {
+                self.line("///");
+            } else {
+                self.line(&format!("/// {}", line));
+            }
+        }
+    }
+
+    /// the statements of a block and the line closing it, the line opening it ends with '{'
+    fn block(&mut self, block: &Option<Vec<Statement>>, closing: &str) {
+        self.indentation += 1;
+        for statement in block.iter().flatten() {
+            self.statement(statement);
+        }
+        self.indentation -= 1;
+        self.line(closing);
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Program { body, .. } => {
+                let mut previous: Option<&Statement> = None;
+                for statement in body {
+                    // imports and global variables are kept together, everything else is spaced
+                    let grouped = matches!(
+                        (previous, statement),
+                        (Some(Statement::Use { .. }), Statement::Use { .. })
+                            | (
+                                Some(Statement::VariableDeclaration { .. }),
+                                Statement::VariableDeclaration { .. }
+                            )
+                    );
+                    if previous.is_some() && !grouped {
+                        self.out.push('\n');
+                    }
+                    self.statement(statement);
+                    previous = Some(statement);
+                }
+            }
+            // the linker adds modules for the 'use' statements, which are printed instead
+            Statement::Module { .. } => {}
+            Statement::Use { name, path, .. } => {
+                self.line(&format!("use {} from {};", name, quote(path)))
+            }
+            Statement::VariableDeclaration { doc, .. } => {
+                self.doc(doc);
+                let declaration = self.declaration(statement);
+                self.line(&format!("{};", declaration));
+            }
+            Statement::FunctionDeclaration {
+                name,
+                r#type,
+                params,
+                body,
+                doc,
+                ..
+            } => {
+                self.doc(doc);
+                self.line(&format!(
+                    "func {}({}): {} {{",
+                    name,
+                    self::params(params),
+                    r#type
+                ));
+                self.block(body, "};");
+            }
+            Statement::StructDeclaration {
+                name, fields, doc, ..
+            } => {
+                self.doc(doc);
+                self.line(&format!("obj {} {{", name));
+                self.indentation += 1;
+                for StructField { name, r#type, doc } in fields {
+                    self.doc(doc);
+                    self.line(&format!("{}: {},", name, r#type));
+                }
+                self.indentation -= 1;
+                self.line("};");
+            }
+            Statement::EnumDeclaration {
+                name,
+                variants,
+                doc,
+                ..
+            } => {
+                self.doc(doc);
+                self.line(&format!("enum {} {{", name));
+                self.indentation += 1;
+                for EnumVariant { name, payload, doc } in variants {
+                    self.doc(doc);
+                    match payload {
+                        Some(payload) => {
+                            let types: Vec<String> =
+                                payload.iter().map(|t| t.to_string()).collect();
+                            self.line(&format!("{}({}),", name, types.join(", ")));
+                        }
+                        None => self.line(&format!("{},", name)),
+                    }
+                }
+                self.indentation -= 1;
+                self.line("};");
+            }
+            Statement::If { .. } | Statement::ElseIf { .. } | Statement::Else { .. } => {
+                self.if_chain(statement, "")
+            }
+            Statement::While { test, block, .. } => {
+                let test = self.expression(test, false);
+                self.line(&format!("while {} {{", test));
+                self.block(block, "};");
+            }
+            Statement::For {
+                variable,
+                test,
+                variable_update,
+                block,
+                ..
+            } => {
+                let mut header = String::from("for ");
+                if let Some(variable) = variable {
+                    header.push_str(&self.declaration(variable));
+                }
+                header.push(';');
+                match test {
+                    Some(test) => header.push_str(&format!(" {};", self.expression(test, false))),
+                    None => header.push(';'),
+                }
+                if let Some(variable_update) = variable_update {
+                    header.push_str(&format!(" {};", self.alteration(variable_update)));
+                }
+                self.line(&format!("{} {{", header));
+                self.block(block, "};");
+            }
+            Statement::ForIn {
+                name,
+                values,
+                block,
+                ..
+            } => {
+                let values = self.expression(values, false);
+                self.line(&format!("for {} in {} {{", name, values));
+                self.block(block, "};");
+            }
+            Statement::Switch { subject, cases, .. } => {
+                let subject = self.expression(subject, false);
+                self.line(&format!("switch {} {{", subject));
+                self.indentation += 1;
+                for SwitchCase {
+                    patterns, block, ..
+                } in cases
+                {
+                    if patterns.is_empty() {
+                        self.line("casenot {");
+                    } else {
+                        let patterns: Vec<String> = patterns.iter().map(pattern).collect();
+                        self.line(&format!("case {} {{", patterns.join(", ")));
+                    }
+                    self.block(block, "};");
+                }
+                self.indentation -= 1;
+                self.line("};");
+            }
+            Statement::Break { .. } => self.line("brk;"),
+            Statement::Continue { .. } => self.line("cnt;"),
+            Statement::Return { expression, .. } => match expression {
+                Some(expression) => {
+                    let expression = self.expression(expression, true);
+                    self.line(&format!("ret {};", expression));
+                }
+                None => self.line("ret;"),
+            },
+            Statement::VariableAlteration { .. } | Statement::PlaceAlteration { .. } => {
+                let alteration = self.alteration(statement);
+                self.line(&format!("{};", alteration));
+            }
+            Statement::FunctionCall { call, .. } => {
+                let call = self.expression(call, true);
+                self.line(&format!("{};", call));
+            }
+        }
+    }
+
+    /// let name: type = value, without the ';'
+    fn declaration(&self, statement: &Statement) -> String {
+        let Statement::VariableDeclaration {
+            name,
+            kind,
+            r#type,
+            value,
+            ..
+        } = statement
+        else {
+            return String::new();
+        };
+        let mut declaration = match kind {
+            VarDeclarationKind::Immutable => format!("let {}", name.as_deref().unwrap_or("")),
+            VarDeclarationKind::Mutable => format!("mut {}", name.as_deref().unwrap_or("")),
+        };
+        if let Some(r#type) = r#type {
+            declaration.push_str(&format!(": {}", r#type));
+        }
+        if let Some(value) = value {
+            declaration.push_str(&format!(" = {}", self.expression(value, true)));
+        }
+        declaration
+    }
+
+    /// name += value or name++, without the ';'
+    fn alteration(&self, statement: &Statement) -> String {
+        let (target, operator, value) = match statement {
+            Statement::VariableAlteration {
+                name,
+                operator,
+                value,
+                ..
+            } => (name.to_owned(), operator, value),
+            Statement::PlaceAlteration {
+                target,
+                operator,
+                value,
+                ..
+            } => (self.expression(target, true), operator, value),
+            _ => return String::new(),
+        };
+        match operator {
+            TokenTypes::BinaryIncrement | TokenTypes::BinaryDecrement => {
+                format!("{}{}", target, operator)
+            }
+            _ => format!("{} {} {}", target, operator, self.expression(value, true)),
+        }
+    }
+
+    /// if, elseif and else are printed as one chain ending in '};'
+    fn if_chain(&mut self, statement: &Statement, before: &str) {
+        let (keyword, condition, block, alternate) = match statement {
+            Statement::If {
+                condition,
+                block,
+                alternate,
+                ..
+            } => ("if", Some(condition), block, alternate),
+            Statement::ElseIf {
+                condition,
+                block,
+                alternate,
+                ..
+            } => ("elseif", Some(condition), block, alternate),
+            Statement::Else { block, .. } => ("else", None, block, &None),
+            _ => return,
+        };
+        let header = match condition {
+            Some(condition) => format!(
+                "{}{} {} {{",
+                before,
+                keyword,
+                self.expression(condition, false)
+            ),
+            None => format!("{}{} {{", before, keyword),
+        };
+        self.line(&header);
+
+        match alternate {
+            Some(alternate) => {
+                self.indentation += 1;
+                for statement in block.iter().flatten() {
+                    self.statement(statement);
+                }
+                self.indentation -= 1;
+                self.if_chain(alternate, "} ");
+            }
+            None => self.block(block, "};"),
+        }
+    }
+
+    /// struct literals are not allowed in conditions unless they are in parentheses
+    fn expression(&self, expression: &Expression, structs: bool) -> String {
+        match expression {
+            Expression::Identifier(name) => name.to_owned(),
+            Expression::Binary {
+                operator,
+                left,
+                right,
+            }
+            | Expression::Logical {
+                operator,
+                left,
+                right,
+            } => {
+                let precedence = operator_precedence(operator);
+                format!(
+                    "{} {} {}",
+                    self.operand(left, precedence, structs),
+                    operator,
+                    self.operand(right, precedence + 1, structs)
+                )
+            }
+            Expression::Unary { operator, operand } => {
+                let operand = self.operand(operand, 14, structs);
+                // '- -x' would be lexed as '--'
+                if operand.starts_with('-') {
+                    format!("{}({})", operator, operand)
+                } else {
+                    format!("{}{}", operator, operand)
+                }
+            }
+            Expression::Literal { r#type, value } => match r#type {
+                LiteralTypes::String => quote(value),
+                LiteralTypes::Character => quote_char(value.chars().next().unwrap_or(' ')),
+                LiteralTypes::Numeric | LiteralTypes::Boolean | LiteralTypes::Null => {
+                    value.to_owned()
+                }
+            },
+            Expression::ArrayLiteral { elements } => format!("[{}]", self.list(elements)),
+            Expression::ArrayAccess(access) => self.array_access(access, structs),
+            Expression::Call { name, arguments } => format!("{}({})", name, self.list(arguments)),
+            Expression::Cast { expression, r#type } => {
+                format!("{} as {}", self.operand(expression, 13, structs), r#type)
+            }
+            Expression::StructLiteral { name, fields } if structs => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .flatten()
+                    .map(|field| {
+                        format!("{} = {}", field.name, self.expression(&field.value, true))
+                    })
+                    .collect();
+                if fields.is_empty() {
+                    format!("{} {{}}", name)
+                } else {
+                    format!("{} {{ {} }}", name, fields.join(", "))
+                }
+            }
+            Expression::FieldAccess { object, field } => {
+                format!("{}.{}", self.object(object, structs), field)
+            }
+            Expression::MethodCall {
+                object,
+                name,
+                arguments,
+            } => format!(
+                "{}.{}({})",
+                self.object(object, structs),
+                name,
+                self.list(arguments)
+            ),
+            Expression::Ternary {
+                condition,
+                when_true,
+                when_false,
+            } => format!(
+                "{} ? {} : {}",
+                self.operand(condition, 3, structs),
+                self.expression(when_true, structs),
+                self.expression(when_false, structs)
+            ),
+            Expression::Lambda {
+                params,
+                r#type,
+                body,
+            } if structs => {
+                let header = format!("({}): {} =>", self::params(params), r#type);
+                match body.as_deref() {
+                    Some(
+                        [Statement::Return {
+                            expression: Some(expression),
+                            ..
+                        }],
+                    ) => format!("{} {}", header, self.expression(expression, true)),
+                    Some(body) => {
+                        let mut printer = Printer {
+                            out: String::new(),
+                            indentation: self.indentation + 1,
+                        };
+                        for statement in body {
+                            printer.statement(statement);
+                        }
+                        format!(
+                            "{} {{\n{}{}}}",
+                            header,
+                            printer.out,
+                            "    ".repeat(self.indentation)
+                        )
+                    }
+                    None => format!("{} {{}}", header),
+                }
+            }
+            Expression::Interpolation(parts) => self.interpolation(parts),
+            Expression::Range {
+                from,
+                to,
+                inclusive,
+            } => format!(
+                "{}{}{}",
+                self.expression(from, structs),
+                if *inclusive { "..=" } else { ".." },
+                self.expression(to, structs)
+            ),
+            // a struct literal or lambda in a condition
+            Expression::StructLiteral { .. } | Expression::Lambda { .. } => {
+                format!("({})", self.expression(expression, true))
+            }
+        }
+    }
+
+    /// an operand that binds at least as tightly as precedence, in parentheses otherwise
+    fn operand(&self, expression: &Expression, precedence: u8, structs: bool) -> String {
+        if self::precedence(expression) < precedence {
+            format!("({})", self.expression(expression, true))
+        } else {
+            self.expression(expression, structs)
+        }
+    }
+
+    /// the left of a '.', a number would take the '.' as its decimal point
+    fn object(&self, object: &Expression, structs: bool) -> String {
+        match object {
+            Expression::Literal {
+                r#type: LiteralTypes::Numeric,
+                ..
+            } => format!("({})", self.expression(object, true)),
+            _ => self.operand(object, 15, structs),
+        }
+    }
+
+    fn list(&self, expressions: &Option<Vec<Expression>>) -> String {
+        let expressions: Vec<String> = expressions
+            .iter()
+            .flatten()
+            .map(|expression| self.expression(expression, true))
+            .collect();
+        expressions.join(", ")
+    }
+
+    fn array_access(&self, access: &ArrayAccess, structs: bool) -> String {
+        match access {
+            ArrayAccess::Access { name, index } => {
+                format!("{}[{}]", name, self.expression(index, true))
+            }
+            ArrayAccess::NestedAccess { access, index } => format!(
+                "{}[{}]",
+                self.array_access(access, structs),
+                self.expression(index, true)
+            ),
+            ArrayAccess::ObjectAccess { object, index } => format!(
+                "{}[{}]",
+                self.operand(object, 15, structs),
+                self.expression(index, true)
+            ),
+        }
+    }
+
+    /// str parts are written as text, unless that would merge them with the text before them
+    fn interpolation(&self, parts: &[Expression]) -> String {
+        let mut text = String::from("\"");
+        let mut after_text = false;
+        let has_expression = parts.iter().any(|part| {
+            !matches!(
+                part,
+                Expression::Literal {
+                    r#type: LiteralTypes::String,
+                    ..
+                }
+            )
+        });
+        for (i, part) in parts.iter().enumerate() {
+            match part {
+                Expression::Literal {
+                    r#type: LiteralTypes::String,
+                    value,
+                } if !after_text && !value.is_empty() && (has_expression || i > 0) => {
+                    let quoted = quote(value);
+                    text.push_str(&quoted[1..quoted.len() - 1]);
+                    after_text = true;
+                }
+                _ => {
+                    text.push_str(&format!("{{{}}}", self.expression(part, true)));
+                    after_text = false;
+                }
+            }
+        }
+        text.push('"');
+        text
+    }
+}
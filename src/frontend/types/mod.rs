@@ -1,5 +1,6 @@
 use core::fmt;
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum TokenTypes {
     // Assignment Operators
@@ -11,30 +12,38 @@ pub enum TokenTypes {
     AssignRest,     // %=
 
     // Binary Operators
-    BinaryPlus,     // +
-    BinaryMinus,    // -
-    BinaryDivision, // /
-    BinaryMultiply, // *
-    BinaryRest,     // %
-    // BinaryIncrement, // ++
-    // BinaryDecrement, // --
+    BinaryPlus,      // +
+    BinaryMinus,     // -
+    BinaryDivision,  // /
+    BinaryMultiply,  // *
+    BinaryRest,      // %
+    BinaryIncrement, // ++
+    BinaryDecrement, // --
+
+    // Bitwise Operators, Only On Integers
+    BitwiseAnd,        // &
+    BitwiseOr,         // |
+    BitwiseXor,        // ^
+    BitwiseNot,        // ~
+    BitwiseShiftLeft,  // <<
+    BitwiseShiftRight, // >>
 
     // Logical Operators
-    LogicalNot,       // !
-    LogicalAnd,       // &
-    LogicalOr,        // |
-    LogicalEquals,    // ==
-    LogicalDifferent, // !=
-    // LogicalTernary, // Condition ? True : False
+    LogicalNot,                 // !
+    LogicalAnd,                 // &&
+    LogicalOr,                  // ||
+    LogicalEquals,              // ==
+    LogicalDifferent,           // !=
     LogicalSmallerThan,         // <
     LogicalGreaterThan,         // >
     LogicalSmallerOrEqualsThan, // <=
     LogicalGreaterOrEqualsThan, // >=
-    // QuestionMark, // ? // Its Purpose Is To Check If A Var Is Empty, Such As Array == [] Or Var == Null
-    // E.g. If (?varname) {} Only Runs The Block If The Var Is Not Empty
+    QuestionMark, // ? // Marks A Type As Optional, E.g. i32? Can Also Hold Null, Or Condition ? True : False
 
     // Punctuation
     Dot,                // .
+    Range,              // ..
+    RangeInclusive,     // ..=
     LeftParenthesis,    // (
     RightParenthesis,   // )
     LeftSquareBracket,  // [
@@ -46,19 +55,23 @@ pub enum TokenTypes {
     Semicolon,          // ;
     Colon,              // :
     Comma,              // ,
-    // SmallFunction,      // =>
+    SmallFunction,      // =>
 
     // Types
-    Int, // 123456789
-    Flo, // 1.0 2.0 3.14
-    Str, // "hello, World!"
-    // Obj, // { Property = "value" }
-    Arr, // [0,1,2]
-    Boo, // True | False
+    Int,  // 123456789
+    Flo,  // 1.0 2.0 3.14
+    Str,  // "hello, World!"
+    Chr,  // 'a'
+    Obj,  // obj Name { property: Type }
+    Enum, // enum Name { Variant(Type) }
+    Arr,  // [0,1,2]
+    Boo,  // True | False
     // Nul, // Null
 
     // Special
-    Comment, // //
+    Comment,      // //
+    BlockComment, // /* */ Can Be Nested
+    DocComment,   // /// Documents The Declaration After It
 
     // Keywords
     Function,         // Func
@@ -69,22 +82,28 @@ pub enum TokenTypes {
     Else,             // Else
     While,            // While
     For,              // For
+    In,               // For Name In Values
     Return,           // Ret
     Break,            // Brk
     Continue,         // Cnt
-    // Use,      // Use Modname From 'mod/path'
-    // From,     // From 'mod/path'
-    // Switch,   // Switch
-    // Case,     // Incase
-    // CaseNot,  //incasenot
+    As,               // As
+    Use,              // Use Modname From 'mod/path'
+    From,             // From 'mod/path'
+    Switch,           // Switch
+    Case,             // Incase
+    CaseNot,          //incasenot
 
     // Literals
     Identifier, // any name
     NumberLiteral,
     StringLiteral,
-    True,  // True
-    False, // False
-    Null,  // Null
+    CharLiteral,
+    StringStart,  // "text before the first {
+    StringMiddle, // } text between two expressions {
+    StringEnd,    // } text after the last expression"
+    True,         // True
+    False,        // False
+    Null,         // Null
 
     // Unknown
     UNKNOWN, // any token that doesnt match anything
@@ -129,14 +148,38 @@ impl fmt::Display for TokenTypes {
             TokenTypes::BinaryRest => {
                 write!(f, "%")
             }
+            TokenTypes::BinaryIncrement => {
+                write!(f, "++")
+            }
+            TokenTypes::BinaryDecrement => {
+                write!(f, "--")
+            }
+            TokenTypes::BitwiseAnd => {
+                write!(f, "&")
+            }
+            TokenTypes::BitwiseOr => {
+                write!(f, "|")
+            }
+            TokenTypes::BitwiseXor => {
+                write!(f, "^")
+            }
+            TokenTypes::BitwiseNot => {
+                write!(f, "~")
+            }
+            TokenTypes::BitwiseShiftLeft => {
+                write!(f, "<<")
+            }
+            TokenTypes::BitwiseShiftRight => {
+                write!(f, ">>")
+            }
             TokenTypes::LogicalNot => {
                 write!(f, "!")
             }
             TokenTypes::LogicalAnd => {
-                write!(f, "&")
+                write!(f, "&&")
             }
             TokenTypes::LogicalOr => {
-                write!(f, "|")
+                write!(f, "||")
             }
             TokenTypes::LogicalEquals => {
                 write!(f, "==")
@@ -156,9 +199,18 @@ impl fmt::Display for TokenTypes {
             TokenTypes::LogicalGreaterOrEqualsThan => {
                 write!(f, ">=")
             }
+            TokenTypes::QuestionMark => {
+                write!(f, "?")
+            }
             TokenTypes::Dot => {
                 write!(f, ".")
             }
+            TokenTypes::Range => {
+                write!(f, "..")
+            }
+            TokenTypes::RangeInclusive => {
+                write!(f, "..=")
+            }
             TokenTypes::LeftParenthesis => {
                 write!(f, "(")
             }
@@ -192,6 +244,9 @@ impl fmt::Display for TokenTypes {
             TokenTypes::Comma => {
                 write!(f, ",")
             }
+            TokenTypes::SmallFunction => {
+                write!(f, "=>")
+            }
             TokenTypes::Int => {
                 write!(f, "i32")
             }
@@ -201,6 +256,15 @@ impl fmt::Display for TokenTypes {
             TokenTypes::Str => {
                 write!(f, "str")
             }
+            TokenTypes::Chr => {
+                write!(f, "char")
+            }
+            TokenTypes::Obj => {
+                write!(f, "obj")
+            }
+            TokenTypes::Enum => {
+                write!(f, "enum")
+            }
             TokenTypes::Arr => {
                 write!(f, "vec")
             }
@@ -213,6 +277,12 @@ impl fmt::Display for TokenTypes {
             TokenTypes::Comment => {
                 write!(f, "Comment")
             }
+            TokenTypes::BlockComment => {
+                write!(f, "BlockComment")
+            }
+            TokenTypes::DocComment => {
+                write!(f, "DocComment")
+            }
             TokenTypes::Function => {
                 write!(f, "func")
             }
@@ -237,6 +307,9 @@ impl fmt::Display for TokenTypes {
             TokenTypes::For => {
                 write!(f, "for")
             }
+            TokenTypes::In => {
+                write!(f, "in")
+            }
             TokenTypes::Return => {
                 write!(f, "ret")
             }
@@ -246,6 +319,24 @@ impl fmt::Display for TokenTypes {
             TokenTypes::Continue => {
                 write!(f, "cnt")
             }
+            TokenTypes::As => {
+                write!(f, "as")
+            }
+            TokenTypes::Use => {
+                write!(f, "use")
+            }
+            TokenTypes::From => {
+                write!(f, "from")
+            }
+            TokenTypes::Switch => {
+                write!(f, "switch")
+            }
+            TokenTypes::Case => {
+                write!(f, "case")
+            }
+            TokenTypes::CaseNot => {
+                write!(f, "casenot")
+            }
             TokenTypes::Identifier => {
                 write!(f, "Identifier")
             }
@@ -255,6 +346,18 @@ impl fmt::Display for TokenTypes {
             TokenTypes::StringLiteral => {
                 write!(f, "StringLiteral")
             }
+            TokenTypes::CharLiteral => {
+                write!(f, "CharLiteral")
+            }
+            TokenTypes::StringStart => {
+                write!(f, "StringStart")
+            }
+            TokenTypes::StringMiddle => {
+                write!(f, "StringMiddle")
+            }
+            TokenTypes::StringEnd => {
+                write!(f, "StringEnd")
+            }
             // TokenTypes::ArrayLiteral => {
             //     write!(f, "ArrayLiteral")
             // }
@@ -283,6 +386,51 @@ pub struct Token {
     pub token_type: TokenTypes,
     pub column_number: u32,
     pub line_number: u32,
+    pub span: Span,
+    // the source text and the trivia before the token, only kept by Lexer::lossless
+    pub text: String,
+    pub trivia: Vec<Trivia>,
+}
+
+impl Token {
+    /// the lexer sets the span when the token is added
+    pub fn new(token_type: TokenTypes, token_value: String, line: u32, column: u32) -> Self {
+        Token {
+            token_value,
+            token_type,
+            column_number: column,
+            line_number: line,
+            span: Span::default(),
+            text: String::new(),
+            trivia: Vec::new(),
+        }
+    }
+}
+
+/// where a token is in the source, start and end are byte offsets with the end excluded
+/// and end_line and end_column are the position of its last character
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+/// source text that is not part of any token
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub text: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TriviaKind {
+    Whitespace,   // spaces and tabs
+    NewLine,      // \n or \r\n
+    Comment,      // // until the end of the line
+    BlockComment, // /* */
 }
 
 #[derive(Debug, Clone)]
@@ -319,13 +467,56 @@ pub enum Expression {
         value: String,
     },
     ArrayLiteral {
-        elements: Option<Box<Vec<Expression>>>,
+        elements: Option<Vec<Expression>>,
     },
     ArrayAccess(ArrayAccess),
     Call {
         name: String,
-        arguments: Option<Box<Vec<Expression>>>,
+        arguments: Option<Vec<Expression>>,
+    },
+    Cast {
+        expression: Box<Expression>,
+        r#type: VariableTypes,
+    },
+    StructLiteral {
+        name: String,
+        fields: Option<Vec<FieldValue>>,
+    },
+    FieldAccess {
+        object: Box<Expression>,
+        field: String,
+    },
+    MethodCall {
+        object: Box<Expression>,
+        name: String,
+        arguments: Option<Vec<Expression>>,
+    },
+    // condition ? when_true : when_false
+    Ternary {
+        condition: Box<Expression>,
+        when_true: Box<Expression>,
+        when_false: Box<Expression>,
     },
+    // (x: i32): i32 => x * 2, an expression body is stored as a return statement
+    Lambda {
+        params: Option<Vec<FuncParam>>,
+        r#type: VariableTypes,
+        body: Option<Vec<Statement>>,
+    },
+    // "x = {x}", the text between the expressions is kept as str literals
+    Interpolation(Vec<Expression>),
+    // from..to or from..=to, only valid as the values of a for loop
+    Range {
+        from: Box<Expression>,
+        to: Box<Expression>,
+        inclusive: bool,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldValue {
+    pub name: String,
+    pub value: Expression,
 }
 
 #[derive(Debug, Clone)]
@@ -338,12 +529,18 @@ pub enum ArrayAccess {
         access: Box<ArrayAccess>,
         index: Box<Expression>,
     },
+    // "hello"[0], foo.bar[0] or foo()[0]
+    ObjectAccess {
+        object: Box<Expression>,
+        index: Box<Expression>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LiteralTypes {
     Numeric,
     String,
+    Character,
     // Array,
     Boolean,
     Null,
@@ -354,9 +551,37 @@ pub enum VariableTypes {
     Int,
     Flo,
     Str,
+    Chr,
     Nul,
     Boo,
     Arr(Box<VariableTypes>),
+    Optional(Box<VariableTypes>),
+    Obj(String),
+    // func(i32, str): bool
+    Func {
+        params: Vec<VariableTypes>,
+        r#type: Box<VariableTypes>,
+    },
+}
+
+impl fmt::Display for VariableTypes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VariableTypes::Int => write!(f, "i32"),
+            VariableTypes::Flo => write!(f, "f64"),
+            VariableTypes::Str => write!(f, "str"),
+            VariableTypes::Chr => write!(f, "char"),
+            VariableTypes::Nul => write!(f, "null"),
+            VariableTypes::Boo => write!(f, "bool"),
+            VariableTypes::Arr(r#type) => write!(f, "vec<{}>", r#type),
+            VariableTypes::Optional(r#type) => write!(f, "{}?", r#type),
+            VariableTypes::Obj(name) => write!(f, "{}", name),
+            VariableTypes::Func { params, r#type } => {
+                let params: Vec<String> = params.iter().map(|p| p.to_string()).collect();
+                write!(f, "func({}): {}", params.join(", "), r#type)
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -365,11 +590,59 @@ pub struct FuncParam {
     pub r#type: VariableTypes,
 }
 
+#[derive(Debug, Clone)]
+pub struct StructField {
+    pub name: String,
+    pub r#type: VariableTypes,
+    // text of the /// comments before the field
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EnumVariant {
+    pub name: String,
+    pub payload: Option<Vec<VariableTypes>>,
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Number(String),
+    String(String),
+    Variant {
+        r#enum: String,
+        variant: String,
+        bindings: Option<Vec<String>>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct SwitchCase {
+    pub start: Start,
+    // empty for casenot
+    pub patterns: Vec<Pattern>,
+    pub block: Option<Vec<Statement>>,
+}
+
 #[derive(Debug, Clone)]
 pub enum Statement {
     Program {
         start: Start,
-        body: Box<Vec<Statement>>,
+        body: Vec<Statement>,
+    },
+    // an imported file, name is unique per program and prefixes everything it defines
+    Module {
+        start: Start,
+        name: String,
+        file: String,
+        body: Vec<Statement>,
+    },
+    Use {
+        start: Start,
+        name: String,
+        path: String,
+        // name of the imported module, set once the program is linked
+        module: Option<String>,
     },
     VariableDeclaration {
         start: Start,
@@ -377,41 +650,68 @@ pub enum Statement {
         kind: VarDeclarationKind,
         r#type: Option<VariableTypes>,
         value: Option<Expression>,
+        // text of the /// comments before the declaration
+        doc: Option<String>,
     },
     FunctionDeclaration {
         start: Start,
         name: String,
         r#type: VariableTypes,
         params: Option<Vec<FuncParam>>,
-        body: Option<Box<Vec<Statement>>>,
+        body: Option<Vec<Statement>>,
+        doc: Option<String>,
+    },
+    StructDeclaration {
+        start: Start,
+        name: String,
+        fields: Vec<StructField>,
+        doc: Option<String>,
+    },
+    EnumDeclaration {
+        start: Start,
+        name: String,
+        variants: Vec<EnumVariant>,
+        doc: Option<String>,
     },
     If {
         start: Start,
         condition: Expression,
-        block: Option<Box<Vec<Statement>>>,
+        block: Option<Vec<Statement>>,
         alternate: Option<Box<Statement>>,
     },
     ElseIf {
         start: Start,
         condition: Expression,
-        block: Option<Box<Vec<Statement>>>,
+        block: Option<Vec<Statement>>,
         alternate: Option<Box<Statement>>,
     },
     Else {
         start: Start,
-        block: Option<Box<Vec<Statement>>>,
+        block: Option<Vec<Statement>>,
     },
     While {
         start: Start,
         test: Expression,
-        block: Option<Box<Vec<Statement>>>,
+        block: Option<Vec<Statement>>,
     },
     For {
         start: Start,
         variable: Option<Box<Statement>>,
         test: Option<Expression>,
         variable_update: Option<Box<Statement>>,
-        block: Option<Box<Vec<Statement>>>,
+        block: Option<Vec<Statement>>,
+    },
+    // for name in values, values is a vec or a range
+    ForIn {
+        start: Start,
+        name: String,
+        values: Expression,
+        block: Option<Vec<Statement>>,
+    },
+    Switch {
+        start: Start,
+        subject: Expression,
+        cases: Vec<SwitchCase>,
     },
     Break {
         start: Start,
@@ -424,9 +724,47 @@ pub enum Statement {
         expression: Option<Expression>,
     },
     VariableAlteration {
+        start: Start,
         name: String,
         operator: TokenTypes,
         value: Expression,
     },
-    FunctionCall(Expression),
+    FunctionCall {
+        start: Start,
+        call: Expression,
+    },
+    // foo.bar = 1, xs[0] += 1 or grid[i][j].x = 1
+    PlaceAlteration {
+        start: Start,
+        target: Expression,
+        operator: TokenTypes,
+        value: Expression,
+    },
+}
+
+impl Statement {
+    pub fn start(&self) -> &Start {
+        match self {
+            Statement::Program { start, .. }
+            | Statement::Module { start, .. }
+            | Statement::Use { start, .. }
+            | Statement::VariableDeclaration { start, .. }
+            | Statement::FunctionDeclaration { start, .. }
+            | Statement::StructDeclaration { start, .. }
+            | Statement::EnumDeclaration { start, .. }
+            | Statement::If { start, .. }
+            | Statement::ElseIf { start, .. }
+            | Statement::Else { start, .. }
+            | Statement::While { start, .. }
+            | Statement::For { start, .. }
+            | Statement::ForIn { start, .. }
+            | Statement::Switch { start, .. }
+            | Statement::Break { start }
+            | Statement::Continue { start }
+            | Statement::Return { start, .. }
+            | Statement::VariableAlteration { start, .. }
+            | Statement::FunctionCall { start, .. }
+            | Statement::PlaceAlteration { start, .. } => start,
+        }
+    }
 }
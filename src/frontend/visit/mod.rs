@@ -0,0 +1,1009 @@
+// a pass implements Visitor, VisitorMut or Fold and overrides the methods of the nodes it
+// cares about, the default of every method walks into the children of its node,
+// an override that still wants the children walked calls the function of its node,
+// walk_statement(self, statement) for visit_statement, fold_statement for fold_statement and so on
+
+use crate::frontend::types::{
+    ArrayAccess, EnumVariant, Expression, FieldValue, FuncParam, Pattern, Statement, StructField,
+    SwitchCase, VariableTypes,
+};
+
+/// a pass that reads the tree
+pub trait Visitor {
+    /// the statements of a program, module, function, lambda or any other block
+    fn visit_block(&mut self, block: &[Statement]) {
+        walk_block(self, block)
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        walk_statement(self, statement)
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        walk_expression(self, expression)
+    }
+
+    fn visit_array_access(&mut self, access: &ArrayAccess) {
+        walk_array_access(self, access)
+    }
+
+    fn visit_switch_case(&mut self, case: &SwitchCase) {
+        walk_switch_case(self, case)
+    }
+
+    fn visit_pattern(&mut self, _pattern: &Pattern) {}
+
+    fn visit_type(&mut self, r#type: &VariableTypes) {
+        walk_type(self, r#type)
+    }
+
+    fn visit_param(&mut self, param: &FuncParam) {
+        self.visit_type(&param.r#type)
+    }
+
+    fn visit_field(&mut self, field: &StructField) {
+        self.visit_type(&field.r#type)
+    }
+
+    fn visit_variant(&mut self, variant: &EnumVariant) {
+        walk_variant(self, variant)
+    }
+
+    fn visit_field_value(&mut self, field: &FieldValue) {
+        self.visit_expression(&field.value)
+    }
+}
+
+pub fn walk_block<V: Visitor + ?Sized>(visitor: &mut V, block: &[Statement]) {
+    for statement in block {
+        visitor.visit_statement(statement);
+    }
+}
+
+fn walk_optional_block<V: Visitor + ?Sized>(visitor: &mut V, block: &Option<Vec<Statement>>) {
+    if let Some(block) = block {
+        visitor.visit_block(block);
+    }
+}
+
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &Statement) {
+    match statement {
+        Statement::Program { body, .. } | Statement::Module { body, .. } => {
+            visitor.visit_block(body)
+        }
+        Statement::Use { .. } | Statement::Break { .. } | Statement::Continue { .. } => {}
+        Statement::VariableDeclaration { r#type, value, .. } => {
+            if let Some(r#type) = r#type {
+                visitor.visit_type(r#type);
+            }
+            if let Some(value) = value {
+                visitor.visit_expression(value);
+            }
+        }
+        Statement::FunctionDeclaration {
+            r#type,
+            params,
+            body,
+            ..
+        } => {
+            for param in params.iter().flatten() {
+                visitor.visit_param(param);
+            }
+            visitor.visit_type(r#type);
+            walk_optional_block(visitor, body);
+        }
+        Statement::StructDeclaration { fields, .. } => {
+            for field in fields {
+                visitor.visit_field(field);
+            }
+        }
+        Statement::EnumDeclaration { variants, .. } => {
+            for variant in variants {
+                visitor.visit_variant(variant);
+            }
+        }
+        Statement::If {
+            condition,
+            block,
+            alternate,
+            ..
+        }
+        | Statement::ElseIf {
+            condition,
+            block,
+            alternate,
+            ..
+        } => {
+            visitor.visit_expression(condition);
+            walk_optional_block(visitor, block);
+            if let Some(alternate) = alternate {
+                visitor.visit_statement(alternate);
+            }
+        }
+        Statement::Else { block, .. } => walk_optional_block(visitor, block),
+        Statement::While { test, block, .. } => {
+            visitor.visit_expression(test);
+            walk_optional_block(visitor, block);
+        }
+        Statement::For {
+            variable,
+            test,
+            variable_update,
+            block,
+            ..
+        } => {
+            if let Some(variable) = variable {
+                visitor.visit_statement(variable);
+            }
+            if let Some(test) = test {
+                visitor.visit_expression(test);
+            }
+            if let Some(variable_update) = variable_update {
+                visitor.visit_statement(variable_update);
+            }
+            walk_optional_block(visitor, block);
+        }
+        Statement::ForIn { values, block, .. } => {
+            visitor.visit_expression(values);
+            walk_optional_block(visitor, block);
+        }
+        Statement::Switch { subject, cases, .. } => {
+            visitor.visit_expression(subject);
+            for case in cases {
+                visitor.visit_switch_case(case);
+            }
+        }
+        Statement::Return { expression, .. } => {
+            if let Some(expression) = expression {
+                visitor.visit_expression(expression);
+            }
+        }
+        Statement::VariableAlteration { value, .. } => visitor.visit_expression(value),
+        Statement::FunctionCall { call, .. } => visitor.visit_expression(call),
+        Statement::PlaceAlteration { target, value, .. } => {
+            visitor.visit_expression(target);
+            visitor.visit_expression(value);
+        }
+    }
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &Expression) {
+    match expression {
+        Expression::Identifier(_) | Expression::Literal { .. } => {}
+        Expression::Binary { left, right, .. } | Expression::Logical { left, right, .. } => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(right);
+        }
+        Expression::Unary { operand, .. } => visitor.visit_expression(operand),
+        Expression::ArrayLiteral {
+            elements: arguments,
+        }
+        | Expression::Call { arguments, .. } => {
+            for argument in arguments.iter().flatten() {
+                visitor.visit_expression(argument);
+            }
+        }
+        Expression::ArrayAccess(access) => visitor.visit_array_access(access),
+        Expression::Cast { expression, r#type } => {
+            visitor.visit_expression(expression);
+            visitor.visit_type(r#type);
+        }
+        Expression::StructLiteral { fields, .. } => {
+            for field in fields.iter().flatten() {
+                visitor.visit_field_value(field);
+            }
+        }
+        Expression::FieldAccess { object, .. } => visitor.visit_expression(object),
+        Expression::MethodCall {
+            object, arguments, ..
+        } => {
+            visitor.visit_expression(object);
+            for argument in arguments.iter().flatten() {
+                visitor.visit_expression(argument);
+            }
+        }
+        Expression::Ternary {
+            condition,
+            when_true,
+            when_false,
+        } => {
+            visitor.visit_expression(condition);
+            visitor.visit_expression(when_true);
+            visitor.visit_expression(when_false);
+        }
+        Expression::Lambda {
+            params,
+            r#type,
+            body,
+        } => {
+            for param in params.iter().flatten() {
+                visitor.visit_param(param);
+            }
+            visitor.visit_type(r#type);
+            walk_optional_block(visitor, body);
+        }
+        Expression::Interpolation(parts) => {
+            for part in parts {
+                visitor.visit_expression(part);
+            }
+        }
+        Expression::Range { from, to, .. } => {
+            visitor.visit_expression(from);
+            visitor.visit_expression(to);
+        }
+    }
+}
+
+pub fn walk_array_access<V: Visitor + ?Sized>(visitor: &mut V, access: &ArrayAccess) {
+    match access {
+        ArrayAccess::Access { index, .. } => visitor.visit_expression(index),
+        ArrayAccess::NestedAccess { access, index } => {
+            visitor.visit_array_access(access);
+            visitor.visit_expression(index);
+        }
+        ArrayAccess::ObjectAccess { object, index } => {
+            visitor.visit_expression(object);
+            visitor.visit_expression(index);
+        }
+    }
+}
+
+pub fn walk_switch_case<V: Visitor + ?Sized>(visitor: &mut V, case: &SwitchCase) {
+    for pattern in &case.patterns {
+        visitor.visit_pattern(pattern);
+    }
+    walk_optional_block(visitor, &case.block);
+}
+
+pub fn walk_type<V: Visitor + ?Sized>(visitor: &mut V, r#type: &VariableTypes) {
+    match r#type {
+        VariableTypes::Arr(r#type) | VariableTypes::Optional(r#type) => visitor.visit_type(r#type),
+        VariableTypes::Func { params, r#type } => {
+            for param in params {
+                visitor.visit_type(param);
+            }
+            visitor.visit_type(r#type);
+        }
+        VariableTypes::Int
+        | VariableTypes::Flo
+        | VariableTypes::Str
+        | VariableTypes::Chr
+        | VariableTypes::Nul
+        | VariableTypes::Boo
+        | VariableTypes::Obj(_) => {}
+    }
+}
+
+pub fn walk_variant<V: Visitor + ?Sized>(visitor: &mut V, variant: &EnumVariant) {
+    for r#type in variant.payload.iter().flatten() {
+        visitor.visit_type(r#type);
+    }
+}
+
+/// a pass that changes the tree in place
+pub trait VisitorMut {
+    /// the statements of a block, they can be added to or removed here
+    fn visit_block_mut(&mut self, block: &mut Vec<Statement>) {
+        walk_block_mut(self, block)
+    }
+
+    fn visit_statement_mut(&mut self, statement: &mut Statement) {
+        walk_statement_mut(self, statement)
+    }
+
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        walk_expression_mut(self, expression)
+    }
+
+    fn visit_array_access_mut(&mut self, access: &mut ArrayAccess) {
+        walk_array_access_mut(self, access)
+    }
+
+    fn visit_switch_case_mut(&mut self, case: &mut SwitchCase) {
+        walk_switch_case_mut(self, case)
+    }
+
+    fn visit_pattern_mut(&mut self, _pattern: &mut Pattern) {}
+
+    fn visit_type_mut(&mut self, r#type: &mut VariableTypes) {
+        walk_type_mut(self, r#type)
+    }
+
+    fn visit_param_mut(&mut self, param: &mut FuncParam) {
+        self.visit_type_mut(&mut param.r#type)
+    }
+
+    fn visit_field_mut(&mut self, field: &mut StructField) {
+        self.visit_type_mut(&mut field.r#type)
+    }
+
+    fn visit_variant_mut(&mut self, variant: &mut EnumVariant) {
+        walk_variant_mut(self, variant)
+    }
+
+    fn visit_field_value_mut(&mut self, field: &mut FieldValue) {
+        self.visit_expression_mut(&mut field.value)
+    }
+}
+
+pub fn walk_block_mut<V: VisitorMut + ?Sized>(visitor: &mut V, block: &mut [Statement]) {
+    for statement in block {
+        visitor.visit_statement_mut(statement);
+    }
+}
+
+fn walk_optional_block_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    block: &mut Option<Vec<Statement>>,
+) {
+    if let Some(block) = block {
+        visitor.visit_block_mut(block);
+    }
+}
+
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut Statement) {
+    match statement {
+        Statement::Program { body, .. } | Statement::Module { body, .. } => {
+            visitor.visit_block_mut(body)
+        }
+        Statement::Use { .. } | Statement::Break { .. } | Statement::Continue { .. } => {}
+        Statement::VariableDeclaration { r#type, value, .. } => {
+            if let Some(r#type) = r#type {
+                visitor.visit_type_mut(r#type);
+            }
+            if let Some(value) = value {
+                visitor.visit_expression_mut(value);
+            }
+        }
+        Statement::FunctionDeclaration {
+            r#type,
+            params,
+            body,
+            ..
+        } => {
+            for param in params.iter_mut().flatten() {
+                visitor.visit_param_mut(param);
+            }
+            visitor.visit_type_mut(r#type);
+            walk_optional_block_mut(visitor, body);
+        }
+        Statement::StructDeclaration { fields, .. } => {
+            for field in fields {
+                visitor.visit_field_mut(field);
+            }
+        }
+        Statement::EnumDeclaration { variants, .. } => {
+            for variant in variants {
+                visitor.visit_variant_mut(variant);
+            }
+        }
+        Statement::If {
+            condition,
+            block,
+            alternate,
+            ..
+        }
+        | Statement::ElseIf {
+            condition,
+            block,
+            alternate,
+            ..
+        } => {
+            visitor.visit_expression_mut(condition);
+            walk_optional_block_mut(visitor, block);
+            if let Some(alternate) = alternate {
+                visitor.visit_statement_mut(alternate);
+            }
+        }
+        Statement::Else { block, .. } => walk_optional_block_mut(visitor, block),
+        Statement::While { test, block, .. } => {
+            visitor.visit_expression_mut(test);
+            walk_optional_block_mut(visitor, block);
+        }
+        Statement::For {
+            variable,
+            test,
+            variable_update,
+            block,
+            ..
+        } => {
+            if let Some(variable) = variable {
+                visitor.visit_statement_mut(variable);
+            }
+            if let Some(test) = test {
+                visitor.visit_expression_mut(test);
+            }
+            if let Some(variable_update) = variable_update {
+                visitor.visit_statement_mut(variable_update);
+            }
+            walk_optional_block_mut(visitor, block);
+        }
+        Statement::ForIn { values, block, .. } => {
+            visitor.visit_expression_mut(values);
+            walk_optional_block_mut(visitor, block);
+        }
+        Statement::Switch { subject, cases, .. } => {
+            visitor.visit_expression_mut(subject);
+            for case in cases {
+                visitor.visit_switch_case_mut(case);
+            }
+        }
+        Statement::Return { expression, .. } => {
+            if let Some(expression) = expression {
+                visitor.visit_expression_mut(expression);
+            }
+        }
+        Statement::VariableAlteration { value, .. } => visitor.visit_expression_mut(value),
+        Statement::FunctionCall { call, .. } => visitor.visit_expression_mut(call),
+        Statement::PlaceAlteration { target, value, .. } => {
+            visitor.visit_expression_mut(target);
+            visitor.visit_expression_mut(value);
+        }
+    }
+}
+
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expression: &mut Expression) {
+    match expression {
+        Expression::Identifier(_) | Expression::Literal { .. } => {}
+        Expression::Binary { left, right, .. } | Expression::Logical { left, right, .. } => {
+            visitor.visit_expression_mut(left);
+            visitor.visit_expression_mut(right);
+        }
+        Expression::Unary { operand, .. } => visitor.visit_expression_mut(operand),
+        Expression::ArrayLiteral {
+            elements: arguments,
+        }
+        | Expression::Call { arguments, .. } => {
+            for argument in arguments.iter_mut().flatten() {
+                visitor.visit_expression_mut(argument);
+            }
+        }
+        Expression::ArrayAccess(access) => visitor.visit_array_access_mut(access),
+        Expression::Cast { expression, r#type } => {
+            visitor.visit_expression_mut(expression);
+            visitor.visit_type_mut(r#type);
+        }
+        Expression::StructLiteral { fields, .. } => {
+            for field in fields.iter_mut().flatten() {
+                visitor.visit_field_value_mut(field);
+            }
+        }
+        Expression::FieldAccess { object, .. } => visitor.visit_expression_mut(object),
+        Expression::MethodCall {
+            object, arguments, ..
+        } => {
+            visitor.visit_expression_mut(object);
+            for argument in arguments.iter_mut().flatten() {
+                visitor.visit_expression_mut(argument);
+            }
+        }
+        Expression::Ternary {
+            condition,
+            when_true,
+            when_false,
+        } => {
+            visitor.visit_expression_mut(condition);
+            visitor.visit_expression_mut(when_true);
+            visitor.visit_expression_mut(when_false);
+        }
+        Expression::Lambda {
+            params,
+            r#type,
+            body,
+        } => {
+            for param in params.iter_mut().flatten() {
+                visitor.visit_param_mut(param);
+            }
+            visitor.visit_type_mut(r#type);
+            walk_optional_block_mut(visitor, body);
+        }
+        Expression::Interpolation(parts) => {
+            for part in parts {
+                visitor.visit_expression_mut(part);
+            }
+        }
+        Expression::Range { from, to, .. } => {
+            visitor.visit_expression_mut(from);
+            visitor.visit_expression_mut(to);
+        }
+    }
+}
+
+pub fn walk_array_access_mut<V: VisitorMut + ?Sized>(visitor: &mut V, access: &mut ArrayAccess) {
+    match access {
+        ArrayAccess::Access { index, .. } => visitor.visit_expression_mut(index),
+        ArrayAccess::NestedAccess { access, index } => {
+            visitor.visit_array_access_mut(access);
+            visitor.visit_expression_mut(index);
+        }
+        ArrayAccess::ObjectAccess { object, index } => {
+            visitor.visit_expression_mut(object);
+            visitor.visit_expression_mut(index);
+        }
+    }
+}
+
+pub fn walk_switch_case_mut<V: VisitorMut + ?Sized>(visitor: &mut V, case: &mut SwitchCase) {
+    for pattern in &mut case.patterns {
+        visitor.visit_pattern_mut(pattern);
+    }
+    walk_optional_block_mut(visitor, &mut case.block);
+}
+
+pub fn walk_type_mut<V: VisitorMut + ?Sized>(visitor: &mut V, r#type: &mut VariableTypes) {
+    match r#type {
+        VariableTypes::Arr(r#type) | VariableTypes::Optional(r#type) => {
+            visitor.visit_type_mut(r#type)
+        }
+        VariableTypes::Func { params, r#type } => {
+            for param in params {
+                visitor.visit_type_mut(param);
+            }
+            visitor.visit_type_mut(r#type);
+        }
+        VariableTypes::Int
+        | VariableTypes::Flo
+        | VariableTypes::Str
+        | VariableTypes::Chr
+        | VariableTypes::Nul
+        | VariableTypes::Boo
+        | VariableTypes::Obj(_) => {}
+    }
+}
+
+pub fn walk_variant_mut<V: VisitorMut + ?Sized>(visitor: &mut V, variant: &mut EnumVariant) {
+    for r#type in variant.payload.iter_mut().flatten() {
+        visitor.visit_type_mut(r#type);
+    }
+}
+
+/// a pass that takes the tree apart and builds a new one, a node can become a different kind
+pub trait Fold {
+    /// the statements of a block, the new block can have more or fewer of them
+    fn fold_block(&mut self, block: Vec<Statement>) -> Vec<Statement> {
+        fold_block(self, block)
+    }
+
+    fn fold_statement(&mut self, statement: Statement) -> Statement {
+        fold_statement(self, statement)
+    }
+
+    fn fold_expression(&mut self, expression: Expression) -> Expression {
+        fold_expression(self, expression)
+    }
+
+    fn fold_array_access(&mut self, access: ArrayAccess) -> ArrayAccess {
+        fold_array_access(self, access)
+    }
+
+    fn fold_switch_case(&mut self, case: SwitchCase) -> SwitchCase {
+        fold_switch_case(self, case)
+    }
+
+    fn fold_pattern(&mut self, pattern: Pattern) -> Pattern {
+        pattern
+    }
+
+    fn fold_type(&mut self, r#type: VariableTypes) -> VariableTypes {
+        fold_type(self, r#type)
+    }
+
+    fn fold_param(&mut self, param: FuncParam) -> FuncParam {
+        FuncParam {
+            r#type: self.fold_type(param.r#type),
+            ..param
+        }
+    }
+
+    fn fold_field(&mut self, field: StructField) -> StructField {
+        StructField {
+            r#type: self.fold_type(field.r#type),
+            ..field
+        }
+    }
+
+    fn fold_variant(&mut self, variant: EnumVariant) -> EnumVariant {
+        fold_variant(self, variant)
+    }
+
+    fn fold_field_value(&mut self, field: FieldValue) -> FieldValue {
+        FieldValue {
+            value: self.fold_expression(field.value),
+            ..field
+        }
+    }
+}
+
+pub fn fold_block<F: Fold + ?Sized>(folder: &mut F, block: Vec<Statement>) -> Vec<Statement> {
+    block
+        .into_iter()
+        .map(|statement| folder.fold_statement(statement))
+        .collect()
+}
+
+fn fold_optional_block<F: Fold + ?Sized>(
+    folder: &mut F,
+    block: Option<Vec<Statement>>,
+) -> Option<Vec<Statement>> {
+    block.map(|block| folder.fold_block(block))
+}
+
+fn fold_boxed_statement<F: Fold + ?Sized>(
+    folder: &mut F,
+    statement: Option<Box<Statement>>,
+) -> Option<Box<Statement>> {
+    statement.map(|statement| Box::new(folder.fold_statement(*statement)))
+}
+
+fn fold_boxed<F: Fold + ?Sized>(folder: &mut F, expression: Expression) -> Box<Expression> {
+    Box::new(folder.fold_expression(expression))
+}
+
+fn fold_expressions<F: Fold + ?Sized>(
+    folder: &mut F,
+    expressions: Option<Vec<Expression>>,
+) -> Option<Vec<Expression>> {
+    expressions.map(|expressions| {
+        expressions
+            .into_iter()
+            .map(|expression| folder.fold_expression(expression))
+            .collect()
+    })
+}
+
+fn fold_params<F: Fold + ?Sized>(
+    folder: &mut F,
+    params: Option<Vec<FuncParam>>,
+) -> Option<Vec<FuncParam>> {
+    params.map(|params| {
+        params
+            .into_iter()
+            .map(|param| folder.fold_param(param))
+            .collect()
+    })
+}
+
+pub fn fold_statement<F: Fold + ?Sized>(folder: &mut F, statement: Statement) -> Statement {
+    match statement {
+        Statement::Program { start, body } => Statement::Program {
+            start,
+            body: folder.fold_block(body),
+        },
+        Statement::Module {
+            start,
+            name,
+            file,
+            body,
+        } => Statement::Module {
+            start,
+            name,
+            file,
+            body: folder.fold_block(body),
+        },
+        Statement::Use { .. } | Statement::Break { .. } | Statement::Continue { .. } => statement,
+        Statement::VariableDeclaration {
+            start,
+            name,
+            kind,
+            r#type,
+            value,
+            doc,
+        } => Statement::VariableDeclaration {
+            start,
+            name,
+            kind,
+            r#type: r#type.map(|r#type| folder.fold_type(r#type)),
+            value: value.map(|value| folder.fold_expression(value)),
+            doc,
+        },
+        Statement::FunctionDeclaration {
+            start,
+            name,
+            r#type,
+            params,
+            body,
+            doc,
+        } => {
+            let params = fold_params(folder, params);
+            let r#type = folder.fold_type(r#type);
+            Statement::FunctionDeclaration {
+                start,
+                name,
+                r#type,
+                params,
+                body: fold_optional_block(folder, body),
+                doc,
+            }
+        }
+        Statement::StructDeclaration {
+            start,
+            name,
+            fields,
+            doc,
+        } => Statement::StructDeclaration {
+            start,
+            name,
+            fields: fields
+                .into_iter()
+                .map(|field| folder.fold_field(field))
+                .collect(),
+            doc,
+        },
+        Statement::EnumDeclaration {
+            start,
+            name,
+            variants,
+            doc,
+        } => Statement::EnumDeclaration {
+            start,
+            name,
+            variants: variants
+                .into_iter()
+                .map(|variant| folder.fold_variant(variant))
+                .collect(),
+            doc,
+        },
+        Statement::If {
+            start,
+            condition,
+            block,
+            alternate,
+        } => Statement::If {
+            start,
+            condition: folder.fold_expression(condition),
+            block: fold_optional_block(folder, block),
+            alternate: fold_boxed_statement(folder, alternate),
+        },
+        Statement::ElseIf {
+            start,
+            condition,
+            block,
+            alternate,
+        } => Statement::ElseIf {
+            start,
+            condition: folder.fold_expression(condition),
+            block: fold_optional_block(folder, block),
+            alternate: fold_boxed_statement(folder, alternate),
+        },
+        Statement::Else { start, block } => Statement::Else {
+            start,
+            block: fold_optional_block(folder, block),
+        },
+        Statement::While { start, test, block } => Statement::While {
+            start,
+            test: folder.fold_expression(test),
+            block: fold_optional_block(folder, block),
+        },
+        Statement::For {
+            start,
+            variable,
+            test,
+            variable_update,
+            block,
+        } => Statement::For {
+            start,
+            variable: fold_boxed_statement(folder, variable),
+            test: test.map(|test| folder.fold_expression(test)),
+            variable_update: fold_boxed_statement(folder, variable_update),
+            block: fold_optional_block(folder, block),
+        },
+        Statement::ForIn {
+            start,
+            name,
+            values,
+            block,
+        } => Statement::ForIn {
+            start,
+            name,
+            values: folder.fold_expression(values),
+            block: fold_optional_block(folder, block),
+        },
+        Statement::Switch {
+            start,
+            subject,
+            cases,
+        } => Statement::Switch {
+            start,
+            subject: folder.fold_expression(subject),
+            cases: cases
+                .into_iter()
+                .map(|case| folder.fold_switch_case(case))
+                .collect(),
+        },
+        Statement::Return { start, expression } => Statement::Return {
+            start,
+            expression: expression.map(|expression| folder.fold_expression(expression)),
+        },
+        Statement::VariableAlteration {
+            start,
+            name,
+            operator,
+            value,
+        } => Statement::VariableAlteration {
+            start,
+            name,
+            operator,
+            value: folder.fold_expression(value),
+        },
+        Statement::FunctionCall { start, call } => Statement::FunctionCall {
+            start,
+            call: folder.fold_expression(call),
+        },
+        Statement::PlaceAlteration {
+            start,
+            target,
+            operator,
+            value,
+        } => Statement::PlaceAlteration {
+            start,
+            target: folder.fold_expression(target),
+            operator,
+            value: folder.fold_expression(value),
+        },
+    }
+}
+
+pub fn fold_expression<F: Fold + ?Sized>(folder: &mut F, expression: Expression) -> Expression {
+    match expression {
+        Expression::Identifier(_) | Expression::Literal { .. } => expression,
+        Expression::Binary {
+            operator,
+            left,
+            right,
+        } => Expression::Binary {
+            operator,
+            left: fold_boxed(folder, *left),
+            right: fold_boxed(folder, *right),
+        },
+        Expression::Logical {
+            operator,
+            left,
+            right,
+        } => Expression::Logical {
+            operator,
+            left: fold_boxed(folder, *left),
+            right: fold_boxed(folder, *right),
+        },
+        Expression::Unary { operator, operand } => Expression::Unary {
+            operator,
+            operand: fold_boxed(folder, *operand),
+        },
+        Expression::ArrayLiteral { elements } => Expression::ArrayLiteral {
+            elements: fold_expressions(folder, elements),
+        },
+        Expression::ArrayAccess(access) => {
+            Expression::ArrayAccess(folder.fold_array_access(access))
+        }
+        Expression::Call { name, arguments } => Expression::Call {
+            name,
+            arguments: fold_expressions(folder, arguments),
+        },
+        Expression::Cast { expression, r#type } => Expression::Cast {
+            expression: fold_boxed(folder, *expression),
+            r#type: folder.fold_type(r#type),
+        },
+        Expression::StructLiteral { name, fields } => Expression::StructLiteral {
+            name,
+            fields: fields.map(|fields| {
+                fields
+                    .into_iter()
+                    .map(|field| folder.fold_field_value(field))
+                    .collect()
+            }),
+        },
+        Expression::FieldAccess { object, field } => Expression::FieldAccess {
+            object: fold_boxed(folder, *object),
+            field,
+        },
+        Expression::MethodCall {
+            object,
+            name,
+            arguments,
+        } => Expression::MethodCall {
+            object: fold_boxed(folder, *object),
+            name,
+            arguments: fold_expressions(folder, arguments),
+        },
+        Expression::Ternary {
+            condition,
+            when_true,
+            when_false,
+        } => Expression::Ternary {
+            condition: fold_boxed(folder, *condition),
+            when_true: fold_boxed(folder, *when_true),
+            when_false: fold_boxed(folder, *when_false),
+        },
+        Expression::Lambda {
+            params,
+            r#type,
+            body,
+        } => {
+            let params = fold_params(folder, params);
+            let r#type = folder.fold_type(r#type);
+            Expression::Lambda {
+                params,
+                r#type,
+                body: fold_optional_block(folder, body),
+            }
+        }
+        Expression::Interpolation(parts) => Expression::Interpolation(
+            parts
+                .into_iter()
+                .map(|part| folder.fold_expression(part))
+                .collect(),
+        ),
+        Expression::Range {
+            from,
+            to,
+            inclusive,
+        } => Expression::Range {
+            from: fold_boxed(folder, *from),
+            to: fold_boxed(folder, *to),
+            inclusive,
+        },
+    }
+}
+
+pub fn fold_array_access<F: Fold + ?Sized>(folder: &mut F, access: ArrayAccess) -> ArrayAccess {
+    match access {
+        ArrayAccess::Access { name, index } => ArrayAccess::Access {
+            name,
+            index: fold_boxed(folder, *index),
+        },
+        ArrayAccess::NestedAccess { access, index } => ArrayAccess::NestedAccess {
+            access: Box::new(folder.fold_array_access(*access)),
+            index: fold_boxed(folder, *index),
+        },
+        ArrayAccess::ObjectAccess { object, index } => ArrayAccess::ObjectAccess {
+            object: fold_boxed(folder, *object),
+            index: fold_boxed(folder, *index),
+        },
+    }
+}
+
+pub fn fold_switch_case<F: Fold + ?Sized>(folder: &mut F, case: SwitchCase) -> SwitchCase {
+    SwitchCase {
+        start: case.start,
+        patterns: case
+            .patterns
+            .into_iter()
+            .map(|pattern| folder.fold_pattern(pattern))
+            .collect(),
+        block: fold_optional_block(folder, case.block),
+    }
+}
+
+pub fn fold_type<F: Fold + ?Sized>(folder: &mut F, r#type: VariableTypes) -> VariableTypes {
+    match r#type {
+        VariableTypes::Arr(r#type) => VariableTypes::Arr(Box::new(folder.fold_type(*r#type))),
+        VariableTypes::Optional(r#type) => {
+            VariableTypes::Optional(Box::new(folder.fold_type(*r#type)))
+        }
+        VariableTypes::Func { params, r#type } => VariableTypes::Func {
+            params: params
+                .into_iter()
+                .map(|param| folder.fold_type(param))
+                .collect(),
+            r#type: Box::new(folder.fold_type(*r#type)),
+        },
+        VariableTypes::Int
+        | VariableTypes::Flo
+        | VariableTypes::Str
+        | VariableTypes::Chr
+        | VariableTypes::Nul
+        | VariableTypes::Boo
+        | VariableTypes::Obj(_) => r#type,
+    }
+}
+
+pub fn fold_variant<F: Fold + ?Sized>(folder: &mut F, variant: EnumVariant) -> EnumVariant {
+    EnumVariant {
+        payload: variant.payload.map(|payload| {
+            payload
+                .into_iter()
+                .map(|r#type| folder.fold_type(r#type))
+                .collect()
+        }),
+        ..variant
+    }
+}
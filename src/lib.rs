@@ -0,0 +1,162 @@
+// the bline compiler as a library, for build tools and tests that compile bline without running
+// the bline binary, compile_to_c, parse and check return the errors instead of printing them
+// and exiting, warnings are only printed by the binary
+//
+// frontend and backend are public for the binary, they change whenever the compiler does,
+// the functions below and the syntax tree in frontend::types are what embedders should use
+//
+// a compilation stops at its first fatal error by unwinding back to the library function that
+// started it, so a program built with panic = "abort" aborts there instead of getting the errors
+
+pub mod backend;
+pub mod frontend;
+
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::process::exit;
+
+use crate::backend::transpiler::Transpiler;
+use crate::frontend::lexer::Lexer;
+use crate::frontend::modules;
+use crate::frontend::parser::Parser;
+pub use crate::frontend::types::Statement;
+
+thread_local! {
+    // the imported file being compiled, errors in the file given to bline don't show it
+    static MODULE_FILE: RefCell<Option<String>> = const { RefCell::new(None) };
+    // the errors of the compile_to_c, parse or check running on this thread, None outside them
+    static COLLECTED: RefCell<Option<Vec<Diagnostic>>> = const { RefCell::new(None) };
+}
+
+/// the C code of a program, imports are looked for relative to the current directory
+pub fn compile_to_c(source: &str) -> Result<String, Vec<Diagnostic>> {
+    collect(|| {
+        let program = parse_program(source);
+        let program = modules::link(program, "<source>");
+
+        let mut transpiler_instance = Transpiler::new(program);
+        transpiler_instance.transpile_abstract_syntax_tree();
+        transpiler_instance.c_src_code
+    })
+}
+
+/// the syntax tree of a program, without the files it imports
+pub fn parse(source: &str) -> Result<Statement, Vec<Diagnostic>> {
+    collect(|| parse_program(source))
+}
+
+/// the errors compile_to_c would give, without keeping the C code
+pub fn check(source: &str) -> Result<(), Vec<Diagnostic>> {
+    compile_to_c(source).map(|_| ())
+}
+
+fn parse_program(source: &str) -> Statement {
+    let mut parser_instance = Parser::new(Lexer::new(source));
+    parser_instance.parse_tokens();
+    parser_instance.abstract_syntax_tree
+}
+
+// the payload abort unwinds with, so collect can tell it from a panic
+struct Aborted;
+
+/// runs a compilation with its errors collected, abort unwinds back to here instead of exiting
+fn collect<T>(compile: impl FnOnce() -> T) -> Result<T, Vec<Diagnostic>> {
+    let outer = COLLECTED.replace(Some(Vec::new()));
+    let module_file = MODULE_FILE.take();
+
+    let result = panic::catch_unwind(AssertUnwindSafe(compile));
+
+    let errors = COLLECTED.replace(outer).unwrap_or_default();
+    MODULE_FILE.set(module_file);
+    match result {
+        Ok(value) if errors.is_empty() => Ok(value),
+        Ok(_) => Err(errors),
+        Err(payload) if payload.is::<Aborted>() => Err(errors),
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+/// stops compiling after an error, the binary exits and the library returns the errors
+pub(crate) fn abort() -> ! {
+    if COLLECTED.with_borrow(|collected| collected.is_some()) {
+        panic::resume_unwind(Box::new(Aborted))
+    }
+    exit(1)
+}
+
+pub(crate) fn error(line: u32, column: u32, message: String) {
+    report(line, column, message);
+}
+
+fn report(line: u32, column: u32, message: String) {
+    diagnostic("Error", line, column, message);
+}
+
+// an error returned instead of reported, for code that lets its caller decide what to do
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub line: u32,
+    pub column: u32,
+    pub message: String,
+    // the imported file it is in, set for the errors compile_to_c and check return
+    pub file: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(line: u32, column: u32, message: String) -> Self {
+        Diagnostic {
+            line,
+            column,
+            message,
+            file: None,
+        }
+    }
+
+    /// prints it like error does, in its file when it has one
+    pub fn emit(&self) {
+        match &self.file {
+            Some(file) => {
+                let outer = MODULE_FILE.replace(Some(file.to_owned()));
+                report(self.line, self.column, self.message.to_owned());
+                MODULE_FILE.set(outer);
+            }
+            None => report(self.line, self.column, self.message.to_owned()),
+        }
+    }
+}
+
+// for code that still compiles but should be changed
+pub(crate) fn warning(line: u32, column: u32, message: String) {
+    diagnostic("Warning", line, column, message);
+}
+
+fn diagnostic(kind: &str, line: u32, column: u32, message: String) {
+    let file = MODULE_FILE.with_borrow(|file| file.to_owned());
+    let collecting = COLLECTED.with_borrow_mut(|collected| match collected {
+        // the library has no way to return warnings, so they are dropped
+        Some(errors) => {
+            if kind == "Error" {
+                errors.push(Diagnostic {
+                    file: file.to_owned(),
+                    ..Diagnostic::new(line, column, message.to_owned())
+                });
+            }
+            true
+        }
+        None => false,
+    });
+    if collecting {
+        return;
+    }
+    match file {
+        Some(file) => eprintln!(
+            "\n| {} at: {}, Ln {}, Col {}, {}",
+            kind, file, line, column, message
+        ),
+        None => eprintln!("\n| {} at: Ln {}, Col {}, {}", kind, line, column, message),
+    }
+}
+
+pub(crate) fn set_module_file(file: Option<String>) {
+    MODULE_FILE.set(file);
+}
@@ -1,14 +1,21 @@
 #ifndef STDLIB_H
 #define STDLIB_H
 
-#include <stdio.h>
+// this file shadows the system stdlib.h when compiled with -I ./src/lib/
+#include_next <stdlib.h>
+#include <ctype.h>
+#include <limits.h>
+#include <math.h>
 #include <stdarg.h>
+#include <stdbool.h>
+#include <stdio.h>
+#include <string.h>
 
 void println(const char *format, ...) {
     va_list args;
     va_start(args, format);
 
-    vprintf(format, args);  
+    vprintf(format, args);
 
     va_end(args);
 }
@@ -17,8 +24,394 @@ void println(const char *format, ...) {
 
 // void println(char *str)
 
-// char *concat(char *str1, char *str2)
-//
-// int compare(char *str1, char *str2)
+char *concat(const char *str1, const char *str2) {
+    size_t len1 = strlen(str1);
+    size_t len2 = strlen(str2);
+    char *result = malloc(len1 + len2 + 1);
+
+    memcpy(result, str1, len1);
+    memcpy(result + len1, str2, len2 + 1);
+
+    return result;
+}
+
+int compare(const char *str1, const char *str2) {
+    return strcmp(str1, str2);
+}
+
+double flo_rest(double x, double y) {
+    return x - y * (double)(long long)(x / y);
+}
+
+char *int_to_str(int x) {
+    char *result = malloc(12);
+    snprintf(result, 12, "%d", x);
+    return result;
+}
+
+// shortest representation that reads back as the same double
+// the shortest text that reads back as the same number, in either the
+// exponent or the fixed-point form
+char *flo_to_str(double x) {
+    if (isnan(x)) {
+        return "nan";
+    }
+    if (isinf(x)) {
+        return x > 0 ? "inf" : "-inf";
+    }
+    char *result = malloc(32);
+    for (int precision = 1; precision <= 17; precision++) {
+        snprintf(result, 32, "%.*g", precision, x);
+        if (strtod(result, NULL) == x) {
+            break;
+        }
+    }
+    char fixed[32];
+    for (int precision = 0; precision <= 17; precision++) {
+        if (snprintf(fixed, sizeof fixed, "%.*f", precision, x) >= (int)sizeof fixed) {
+            break;
+        }
+        if (strtod(fixed, NULL) == x) {
+            if (strchr(fixed, '.') != NULL) {
+                size_t length = strlen(fixed);
+                while (fixed[length - 1] == '0') {
+                    fixed[--length] = '\0';
+                }
+                if (fixed[length - 1] == '.') {
+                    fixed[--length] = '\0';
+                }
+            }
+            if (strlen(fixed) < strlen(result)) {
+                strcpy(result, fixed);
+            }
+            break;
+        }
+    }
+    return result;
+}
+
+char *bool_to_str(bool x) {
+    return x ? "true" : "false";
+}
+
+// numbers that are not unicode code points become U+FFFD
+int int_to_char(int x) {
+    if (x < 0 || x > 0x10FFFF || (x >= 0xD800 && x <= 0xDFFF)) {
+        return 0xFFFD;
+    }
+    return x;
+}
+
+// the UTF-8 bytes of the code point
+char *char_to_str(int c) {
+    char *result = malloc(5);
+    if (c < 0x80) {
+        result[0] = (char)c;
+        result[1] = '\0';
+    } else if (c < 0x800) {
+        result[0] = (char)(0xC0 | (c >> 6));
+        result[1] = (char)(0x80 | (c & 0x3F));
+        result[2] = '\0';
+    } else if (c < 0x10000) {
+        result[0] = (char)(0xE0 | (c >> 12));
+        result[1] = (char)(0x80 | ((c >> 6) & 0x3F));
+        result[2] = (char)(0x80 | (c & 0x3F));
+        result[3] = '\0';
+    } else {
+        result[0] = (char)(0xF0 | (c >> 18));
+        result[1] = (char)(0x80 | ((c >> 12) & 0x3F));
+        result[2] = (char)(0x80 | ((c >> 6) & 0x3F));
+        result[3] = (char)(0x80 | (c & 0x3F));
+        result[4] = '\0';
+    }
+    return result;
+}
+
+// truncates towards zero, saturating at the i32 limits, NaN becomes 0
+int flo_to_int(double x) {
+    if (x != x) {
+        return 0;
+    }
+    if (x >= (double)INT_MAX) {
+        return INT_MAX;
+    }
+    if (x <= (double)INT_MIN) {
+        return INT_MIN;
+    }
+    return (int)x;
+}
+
+// strings that are not numbers become 0, out of range values saturate
+int str_to_int(const char *str) {
+    char *end;
+    long result = strtol(str, &end, 10);
+    if (end == str) {
+        return 0;
+    }
+    if (result > INT_MAX) {
+        return INT_MAX;
+    }
+    if (result < INT_MIN) {
+        return INT_MIN;
+    }
+    return (int)result;
+}
+
+double str_to_flo(const char *str) {
+    char *end;
+    double result = strtod(str, &end);
+    if (end == str) {
+        return 0.0;
+    }
+    return result;
+}
+
+int str_len(const char *str) {
+    return (int)strlen(str);
+}
+
+char *str_upper(const char *str) {
+    size_t len = strlen(str);
+    char *result = malloc(len + 1);
+    for (size_t i = 0; i <= len; i++) {
+        result[i] = (char)toupper((unsigned char)str[i]);
+    }
+    return result;
+}
+
+char *str_lower(const char *str) {
+    size_t len = strlen(str);
+    char *result = malloc(len + 1);
+    for (size_t i = 0; i <= len; i++) {
+        result[i] = (char)tolower((unsigned char)str[i]);
+    }
+    return result;
+}
+
+// removes the whitespace at the start and end
+char *str_trim(const char *str) {
+    while (isspace((unsigned char)*str)) {
+        str++;
+    }
+    size_t len = strlen(str);
+    while (len > 0 && isspace((unsigned char)str[len - 1])) {
+        len--;
+    }
+    char *result = malloc(len + 1);
+    memcpy(result, str, len);
+    result[len] = '\0';
+    return result;
+}
+
+bool str_contains(const char *str, const char *part) {
+    return strstr(str, part) != NULL;
+}
+
+bool str_starts_with(const char *str, const char *start) {
+    return strncmp(str, start, strlen(start)) == 0;
+}
+
+bool str_ends_with(const char *str, const char *end) {
+    size_t len = strlen(str);
+    size_t end_len = strlen(end);
+    return end_len <= len && strcmp(str + len - end_len, end) == 0;
+}
+
+// index of the first occurrence of part, -1 if there is none
+int str_find(const char *str, const char *part) {
+    const char *found = strstr(str, part);
+    return found == NULL ? -1 : (int)(found - str);
+}
+
+// the text from index from up to, but not including, index to
+char *str_slice(const char *str, int from, int to) {
+    int len = (int)strlen(str);
+    if (from < 0 || to > len || from > to) {
+        fprintf(stderr, "\n| Runtime error: slice %d..%d out of bounds for str of length %d\n",
+                from, to, len);
+        exit(1);
+    }
+    // like indexing, neither end can be in the middle of a char
+    if (((unsigned char)str[from] & 0xC0) == 0x80 || ((unsigned char)str[to] & 0xC0) == 0x80) {
+        fprintf(stderr, "\n| Runtime error: slice %d..%d cuts a char of the str\n", from, to);
+        exit(1);
+    }
+    char *result = malloc(to - from + 1);
+    memcpy(result, str + from, to - from);
+    result[to - from] = '\0';
+    return result;
+}
+
+char *str_replace(const char *str, const char *old, const char *replacement) {
+    size_t old_len = strlen(old);
+    if (old_len == 0) {
+        return concat(str, "");
+    }
+    size_t new_len = strlen(replacement);
+    size_t count = 0;
+    for (const char *s = strstr(str, old); s != NULL; s = strstr(s + old_len, old)) {
+        count++;
+    }
+    char *result = malloc(strlen(str) + count * new_len - count * old_len + 1);
+    char *end = result;
+    const char *found;
+    while ((found = strstr(str, old)) != NULL) {
+        memcpy(end, str, found - str);
+        end += found - str;
+        memcpy(end, replacement, new_len);
+        end += new_len;
+        str = found + old_len;
+    }
+    strcpy(end, str);
+    return result;
+}
+
+typedef struct {
+    void *data;
+    int len;
+    int cap;
+} vec;
+
+// copies len elements of the given size into a new vec
+vec *vec_new(int len, size_t size, const void *elements) {
+    vec *v = malloc(sizeof(vec));
+    v->len = len;
+    v->cap = len;
+    v->data = NULL;
+    if (len > 0) {
+        v->data = malloc(len * size);
+        memcpy(v->data, elements, len * size);
+    }
+    return v;
+}
+
+void *vec_at(vec *v, int index, size_t size) {
+    if (index < 0 || index >= v->len) {
+        fprintf(stderr, "\n| Runtime error: index %d out of bounds for vec of length %d\n",
+                index, v->len);
+        exit(1);
+    }
+    return (char *)v->data + (size_t)index * size;
+}
+
+// copies the element to the end of the vec, growing it when it is full
+void vec_push(vec *v, const void *element, size_t size) {
+    if (v->len == v->cap) {
+        v->cap = v->cap == 0 ? 4 : v->cap * 2;
+        v->data = realloc(v->data, v->cap * size);
+    }
+    memcpy((char *)v->data + (size_t)v->len * size, element, size);
+    v->len++;
+}
+
+// the removed element stays readable until the next push
+void *vec_pop(vec *v, size_t size) {
+    if (v->len == 0) {
+        fprintf(stderr, "\n| Runtime error: cannot pop from an empty vec\n");
+        exit(1);
+    }
+    v->len--;
+    return (char *)v->data + (size_t)v->len * size;
+}
+
+// copies the elements from index from up to, but not including, index to
+vec *vec_slice(vec *v, int from, int to, size_t size) {
+    if (from < 0 || to > v->len || from > to) {
+        fprintf(stderr, "\n| Runtime error: slice %d..%d out of bounds for vec of length %d\n",
+                from, to, v->len);
+        exit(1);
+    }
+    return vec_new(to - from, size, (char *)v->data + (size_t)from * size);
+}
+
+void vec_clear(vec *v) {
+    v->len = 0;
+}
+
+// splits the text at every separator, the separator cannot be empty
+vec *str_split(const char *str, const char *separator) {
+    size_t separator_len = strlen(separator);
+    if (separator_len == 0) {
+        fprintf(stderr, "\n| Runtime error: cannot split a str with an empty separator\n");
+        exit(1);
+    }
+    vec *result = vec_new(0, sizeof(char *), NULL);
+    const char *found;
+    while ((found = strstr(str, separator)) != NULL) {
+        char *part = malloc(found - str + 1);
+        memcpy(part, str, found - str);
+        part[found - str] = '\0';
+        vec_push(result, &part, sizeof(char *));
+        str = found + separator_len;
+    }
+    char *last = concat(str, "");
+    vec_push(result, &last, sizeof(char *));
+    return result;
+}
+
+// the code point of the UTF-8 character starting at str, its length in bytes is stored in len,
+// invalid bytes are read one at a time as U+FFFD
+int utf8_decode(const char *str, int *len) {
+    const unsigned char *s = (const unsigned char *)str;
+    int c;
+    if (s[0] < 0x80) {
+        *len = 1;
+        return s[0];
+    } else if (s[0] >= 0xC2 && s[0] < 0xE0) {
+        *len = 2;
+        c = s[0] & 0x1F;
+    } else if (s[0] >= 0xE0 && s[0] < 0xF0) {
+        *len = 3;
+        c = s[0] & 0x0F;
+    } else if (s[0] >= 0xF0 && s[0] < 0xF5) {
+        *len = 4;
+        c = s[0] & 0x07;
+    } else {
+        *len = 1;
+        return 0xFFFD;
+    }
+    for (int i = 1; i < *len; i++) {
+        if ((s[i] & 0xC0) != 0x80) {
+            *len = 1;
+            return 0xFFFD;
+        }
+        c = (c << 6) | (s[i] & 0x3F);
+    }
+    return int_to_char(c);
+}
+
+// the char starting at the byte index, which cannot be in the middle of a char
+int str_char_at(const char *str, int index) {
+    int len = (int)strlen(str);
+    if (index < 0 || index >= len) {
+        fprintf(stderr, "\n| Runtime error: index %d out of bounds for str of length %d\n",
+                index, len);
+        exit(1);
+    }
+    if (((unsigned char)str[index] & 0xC0) == 0x80) {
+        fprintf(stderr, "\n| Runtime error: index %d is inside a char of the str\n", index);
+        exit(1);
+    }
+    int char_len;
+    return utf8_decode(str + index, &char_len);
+}
+
+vec *str_chars(const char *str) {
+    vec *result = vec_new(0, sizeof(int), NULL);
+    while (*str != '\0') {
+        int len;
+        int c = utf8_decode(str, &len);
+        vec_push(result, &c, sizeof(int));
+        str += len;
+    }
+    return result;
+}
+
+// copies the variables a closure captured so they outlive the function that created it
+void *env_new(const void *data, size_t size) {
+    void *env = malloc(size);
+    memcpy(env, data, size);
+    return env;
+}
 
 #endif
@@ -1,13 +1,16 @@
-mod backend;
-mod frontend;
-
 use std::fs::File;
 use std::io::Write;
 use std::{env, fs, io::stdout, process::exit};
 
-use crate::backend::transpiler::Transpiler;
-use crate::frontend::lexer::Lexer;
-use crate::frontend::parser::Parser;
+use bline::backend::transpiler::Transpiler;
+use bline::frontend::dump;
+use bline::frontend::formatter;
+use bline::frontend::json;
+use bline::frontend::lexer::Lexer;
+use bline::frontend::modules;
+use bline::frontend::parser::Parser;
+use bline::frontend::printer;
+use bline::Diagnostic;
 
 // fn repl() {
 //     print!("Welcome to bline's repl, type '\\leave' to exit\n");
@@ -22,18 +25,89 @@ use crate::frontend::parser::Parser;
 //     }
 // }
 
-pub fn error(line: u32, column: u32, message: String) {
-    report(line, column, message);
+fn make_c_file(code: String) {
+    let mut file = File::create("prototype01.c").expect("well we fucked up");
+
+    let _ = file.write_all(&code.into_bytes());
 }
 
-fn report(line: u32, column: u32, message: String) {
-    eprintln!("\n| Error at: Ln {}, Col {}, {}", line, column, message);
+// bline lex and bline parse, for seeing what the front end made of a file
+fn dump_file(command: &str, filename: Option<&String>) -> ! {
+    let Some(filename) = filename else {
+        eprintln!("Err: bline {} needs a file", command);
+        exit(1)
+    };
+    let source = match fs::read_to_string(filename) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("{err}");
+            exit(127)
+        }
+    };
+
+    if command == "lex" {
+        match dump::tokens(Lexer::new(&source)) {
+            Ok(tokens) => print!("{}", tokens),
+            Err(diagnostic) => {
+                diagnostic.emit();
+                exit(1)
+            }
+        }
+    } else {
+        let mut parser_instance = Parser::new(Lexer::new(&source));
+        parser_instance.parse_tokens();
+        print!("{}", dump::tree(&parser_instance.abstract_syntax_tree));
+    }
+    exit(0)
 }
 
-fn make_c_file(code: String) {
-    let mut file = File::create("prototype01.c").expect("well we fucked up");
+// bline fmt, --check leaves the files as they are and fails if any of them would change
+fn format_files(args: &[String]) -> ! {
+    let mut check = false;
+    for flag in args.iter().filter(|arg| arg.starts_with("--")) {
+        match flag.as_str() {
+            "--check" => check = true,
+            _ => {
+                eprintln!("Err: Unknown flag '{}'", flag);
+                exit(1)
+            }
+        }
+    }
+    let filenames: Vec<&String> = args.iter().filter(|arg| !arg.starts_with("--")).collect();
+    if filenames.is_empty() {
+        eprintln!("Err: bline fmt needs a file");
+        exit(1)
+    }
 
-    let _ = file.write_all(&code.into_bytes());
+    let mut unformatted = false;
+    for filename in filenames {
+        let source = match fs::read_to_string(filename) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("{err}");
+                exit(127)
+            }
+        };
+        let formatted = match formatter::format(&source) {
+            Ok(formatted) => formatted,
+            Err(diagnostic) => {
+                let file = Some(filename.clone());
+                Diagnostic { file, ..diagnostic }.emit();
+                exit(1)
+            }
+        };
+        if formatted == source {
+            continue;
+        }
+        if check {
+            print!("{}", formatter::diff(filename, &source, &formatted));
+            unformatted = true;
+        } else if let Err(err) = fs::write(filename, formatted) {
+            eprintln!("{err}");
+            exit(1)
+        }
+    }
+    exit(if unformatted { 1 } else { 0 })
 }
 
 fn main() {
@@ -47,11 +121,42 @@ fn main() {
         println!(
             "This is the interpreter for bline, a programming language developed By: Vinícios"
         );
+        println!("usage: bline <file>        compile the file to prototype01.c");
+        println!("       bline lex <file>    print the tokens of the file");
+        println!("       bline parse <file>  print the syntax tree of the file");
+        println!("       bline fmt <files>   format the files like docs/conventions");
+        println!("       bline fmt --check <files>  print what formatting would change");
+        println!("       bline <file> --emit=ast-json  print the syntax tree as JSON");
+        println!("       bline <file>.json   compile a syntax tree written as JSON");
+        println!("       bline <file> --emit=source  print the syntax tree as bline source");
         stdout().flush().expect("Failed to flush");
         exit(0)
     }
 
-    let filename: &str = &args[1];
+    if args[1] == "lex" || args[1] == "parse" {
+        dump_file(&args[1], args.get(2));
+    }
+
+    if args[1] == "fmt" {
+        format_files(&args[2..]);
+    }
+
+    let mut emit = "c";
+    for flag in args[1..].iter().filter(|arg| arg.starts_with("--")) {
+        match flag.as_str() {
+            "--emit=ast-json" => emit = "ast-json",
+            "--emit=source" => emit = "source",
+            "--emit=c" => emit = "c",
+            _ => {
+                eprintln!("Err: Unknown flag '{}'", flag);
+                exit(1)
+            }
+        }
+    }
+    let Some(filename) = args[1..].iter().find(|arg| !arg.starts_with("--")) else {
+        eprintln!("Err: No file to compile");
+        exit(1)
+    };
 
     // match Path::new(filename).extension() {
     //     Some(ext) => {
@@ -69,13 +174,36 @@ fn main() {
 
     match fs::read_to_string(filename) {
         Ok(result) => {
-            let mut lexer_instance = Lexer::new(&result);
-            lexer_instance.scan_source_code();
-
-            let mut parser_instance = Parser::new(lexer_instance.token_list);
-            parser_instance.parse_tokens();
-
-            let mut transpiler_instance = Transpiler::new(parser_instance.abstract_syntax_tree);
+            // the JSON of --emit=ast-json, from bline or from a tool that generates programs
+            let program = if filename.ends_with(".json") {
+                match json::from_json(&result) {
+                    Ok(program) => program,
+                    Err(diagnostic) => {
+                        diagnostic.emit();
+                        exit(1)
+                    }
+                }
+            } else {
+                let mut parser_instance = Parser::new(Lexer::new(&result));
+                parser_instance.parse_tokens();
+                parser_instance.abstract_syntax_tree
+            };
+
+            match emit {
+                "ast-json" => {
+                    print!("{}", json::to_json(&program));
+                    exit(0)
+                }
+                "source" => {
+                    print!("{}", printer::to_source(&program));
+                    exit(0)
+                }
+                _ => {}
+            }
+
+            let program = modules::link(program, filename);
+
+            let mut transpiler_instance = Transpiler::new(program);
             transpiler_instance.transpile_abstract_syntax_tree();
 
             make_c_file(transpiler_instance.c_src_code);
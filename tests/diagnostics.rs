@@ -0,0 +1,264 @@
+use bline::{frontend::json::from_json, Diagnostic};
+
+fn errors(source: &str) -> Vec<Diagnostic> {
+    match bline::check(source) {
+        Ok(()) => panic!("expected errors from {:?}", source),
+        Err(errors) => errors,
+    }
+}
+
+fn in_main(body: &str) -> String {
+    format!(
+        "obj Box {{\n    xs: vec<i32>,\n}};\n\nfunc main(): i32 {{\n{}\n    ret 0;\n}};\n",
+        body
+    )
+}
+
+/// the JSON of a program whose only statement is 'let x: i32 = <value>;'
+fn json_with_value(value: &str) -> String {
+    format!(
+        r#"{{"version": 2, "program": {{"kind": "Program", "start": {{"line": 1, "column": 0}}, "body": [
+            {{"kind": "VariableDeclaration", "start": {{"line": 1, "column": 1}}, "name": "x",
+              "mutable": false, "type": {{"kind": "i32"}}, "value": {}}}
+        ]}}}}"#,
+        value
+    )
+}
+
+fn json_error(text: &str) -> String {
+    match from_json(text) {
+        Ok(program) => panic!("expected an error, read {:?}", program),
+        Err(error) => error.message,
+    }
+}
+
+#[test]
+fn crlf_counts_as_one_line() {
+    let errors = errors("func main(): i32 {\r\n\r\n    ret y;\r\n};\r\n");
+    assert_eq!((errors[0].line, errors[0].column), (3, 5));
+}
+
+#[test]
+fn identifier_at_the_end_of_the_file() {
+    for source in ["x", "a.b", "func", "let"] {
+        assert_eq!(errors(source).len(), 1, "{:?}", source);
+    }
+}
+
+#[test]
+fn index_any_expression() {
+    let source = in_main(
+        "    let a: char = \"hello\"[0];\n    mut b: Box = Box { xs = [1, 2] };\n    b.xs[0] = 3;\n    let c: i32 = b.xs[1] + [4, 5][0];\n    let d: char = \"a,b\".split(\",\")[1][0];",
+    );
+    if let Err(errors) = bline::check(&source) {
+        panic!("{:?}", errors);
+    }
+}
+
+#[test]
+fn interpolation_lists_every_type() {
+    let errors = errors(&in_main(
+        "    let xs: vec<i32> = [1];\n    let s: str = \"{xs}\";",
+    ));
+    assert!(errors[0]
+        .message
+        .contains("only 'i32', 'f64', 'bool', 'char' and 'str'"));
+}
+
+#[test]
+fn single_quotes_for_a_str_give_a_hint() {
+    for body in ["    println('b');", "    let s: str = 'a';"] {
+        let errors = errors(&in_main(body));
+        assert!(
+            errors[0]
+                .message
+                .ends_with("single quotes make a 'char', use double quotes for a 'str'"),
+            "{}",
+            errors[0].message
+        );
+    }
+}
+
+#[test]
+fn index_an_immutable_field() {
+    let errors = errors(&in_main(
+        "    let b: Box = Box { xs = [1] };\n    b.xs[0] = 3;",
+    ));
+    assert!(errors[0]
+        .message
+        .contains("Cannot mutate immutable variable 'b'"));
+}
+
+#[test]
+fn doc_comments_cannot_continue_in_c() {
+    let c = bline::compile_to_c("/// trailing \\ \nfunc main(): i32 {\n    ret 0;\n};\n").unwrap();
+    assert!(c.contains("// trailing\n"));
+    assert!(!c.contains('\\'));
+}
+
+#[test]
+fn println_needs_a_format_and_printable_values() {
+    for (body, message) in [
+        ("    println();", "needs a format 'str' as its first argument"),
+        ("    println(1);", "The format of 'println' must be of type 'str', found 'i32'"),
+        (
+            "    println(\"%d\", [1]);",
+            "Argument 2 of 'println' must be 'i32', 'f64', 'bool', 'char' or 'str', found 'vec<i32>'",
+        ),
+    ] {
+        let errors = errors(&in_main(body));
+        assert!(errors[0].message.contains(message), "{}", errors[0].message);
+    }
+}
+
+#[test]
+fn println_optionals_must_be_narrowed() {
+    let errors = errors(&in_main("    let m: i32? = null;\n    println(\"%d\", m);"));
+    assert!(errors[0]
+        .message
+        .contains("Argument 2 of 'println' is 'i32?', compare it with null first"));
+    let narrowed =
+        in_main("    let m: i32? = 1;\n    if m != null {\n        println(\"%d\", m);\n    };");
+    if let Err(errors) = bline::check(&narrowed) {
+        panic!("{:?}", errors);
+    }
+}
+
+#[test]
+fn names_from_c_and_the_runtime_can_be_used() {
+    let c = bline::compile_to_c(
+        "obj P {\n    int: i32,\n};\n\nfunc concat(a: i32): i32 {\n    ret a;\n};\n\nfunc main(): i32 {\n    let double: P = P { int = concat(1) };\n    let s: str = \"a\" + \"b\";\n    ret double.int;\n};\n",
+    )
+    .unwrap();
+    assert!(c.contains("int bl_concat(int bl_a)"));
+    assert!(c.contains("bl_P bl_double = ((bl_P){.bl_int = bl_concat(1)});"));
+    assert!(c.contains("concat(\"a\", \"b\")"));
+    assert!(c.contains("int main(void)"));
+}
+
+#[test]
+fn json_names_must_be_identifiers() {
+    let value = |name: &str| format!(r#"{{"kind": "Identifier", "name": "{}"}}"#, name);
+    for name in ["y; abort()", "while", "", "1x", "a.b"] {
+        let message = json_error(&json_with_value(&value(name)));
+        assert_eq!(message, format!("'{}' is not a valid name", name));
+    }
+    assert!(from_json(&json_with_value(&value("_y1"))).is_ok());
+}
+
+#[test]
+fn json_types_can_be_qualified() {
+    let program = json_with_value(r#"{"kind": "Literal", "type": "Numeric", "value": "1"}"#);
+    let with_type = |name: &str| {
+        program.replace(
+            r#"{"kind": "i32"}"#,
+            &format!(r#"{{"kind": "obj", "name": "{}"}}"#, name),
+        )
+    };
+    assert!(from_json(&with_type("geo.Point")).is_ok());
+    assert_eq!(
+        json_error(&with_type("geo.Point.x")),
+        "'geo.Point.x' is not a valid name"
+    );
+}
+
+#[test]
+fn json_literals_must_be_what_the_lexer_reads() {
+    let literal = |r#type: &str, value: &str| {
+        format!(
+            r#"{{"kind": "Literal", "type": "{}", "value": "{}"}}"#,
+            r#type, value
+        )
+    };
+    for (r#type, value) in [
+        ("Numeric", "1); abort("),
+        ("Numeric", "0x"),
+        ("Numeric", "-1"),
+        ("Boolean", "1 || 1"),
+        ("Character", "ab"),
+    ] {
+        let message = json_error(&json_with_value(&literal(r#type, value)));
+        assert!(
+            message.starts_with(&format!("'{}' is not", value)),
+            "{}",
+            message
+        );
+    }
+    for value in ["0xFF", "1_000", "1.5e3"] {
+        assert!(from_json(&json_with_value(&literal("Numeric", value))).is_ok());
+    }
+}
+
+#[test]
+fn json_nesting_is_limited() {
+    let message = json_error(&"[".repeat(100_000));
+    assert_eq!(message, "Arrays and objects can only be nested 256 deep");
+    let message = json_error(&"{\"a\": ".repeat(300));
+    assert_eq!(message, "Arrays and objects can only be nested 256 deep");
+}
+
+#[test]
+fn json_docs_cannot_hold_control_characters() {
+    let program = json_with_value(r#"{"kind": "Literal", "type": "Numeric", "value": "1"}"#);
+    let with_doc = |doc: &str| {
+        program.replace(
+            r#""name": "x","#,
+            &format!(r#""name": "x", "doc": "{}","#, doc),
+        )
+    };
+    for doc in [r"a\rint y;", r"a\u000b"] {
+        assert!(json_error(&with_doc(doc)).ends_with("is not a valid doc comment"));
+    }
+    assert!(from_json(&with_doc(r"a\tb\nc")).is_ok());
+}
+
+#[test]
+fn json_declarations_need_what_the_parser_needs() {
+    let literal = r#"{"kind": "Literal", "type": "Numeric", "value": "1"}"#;
+    let value = &format!(r#", "value": {}"#, literal);
+    let program = json_with_value(literal);
+    for (field, removed) in [
+        ("name", r#" "name": "x","#),
+        ("type", r#", "type": {"kind": "i32"}"#),
+        ("value", value),
+    ] {
+        let message = json_error(&program.replace(removed, ""));
+        assert_eq!(message, format!("Missing field '{}'", field));
+    }
+    let mutable = program.replace(r#""mutable": false"#, r#""mutable": true"#);
+    assert!(from_json(&mutable.replace(value, "")).is_ok());
+}
+
+#[test]
+fn constants_are_computed_when_compiling() {
+    for (value, message) in [
+        ("2147483647 + 1", "Constant does not fit in 'i32'"),
+        ("1 / (2 - 2)", "Division by zero in a constant"),
+        ("SIZE % 0", "Division by zero in a constant"),
+    ] {
+        let source = format!("let SIZE: i32 = 4;\nlet X: i32 = {};\n", value);
+        assert_eq!(errors(&source)[0].message, message, "{}", value);
+    }
+    let c = bline::compile_to_c(
+        "let SIZE: i32 = 4 * 1024;\n\nfunc main(): i32 {\n    ret SIZE << 1;\n};\n",
+    )
+    .unwrap();
+    assert!(c.contains("return ((int)((unsigned)4096 << (1 & 31)));"));
+}
+
+#[test]
+fn integer_literals_must_fit_in_32_bits() {
+    let errors = errors(&in_main("    let x: i32 = 3 + 99999999999;"));
+    assert_eq!(
+        (errors[0].line, errors[0].column, errors[0].message.as_str()),
+        (
+            6,
+            22,
+            "Number literal '99999999999' does not fit in 32 bits"
+        )
+    );
+    let lowest = in_main("    let x: i32 = -2147483648;\n    let y: i32 = -0x8000_0000;");
+    if let Err(errors) = bline::check(&lowest) {
+        panic!("{:?}", errors);
+    }
+}
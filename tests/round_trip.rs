@@ -0,0 +1,99 @@
+use bline::frontend::{
+    formatter::format,
+    json::to_json,
+    lexer::{source_text, Lexer},
+    printer::to_source,
+    types::Token,
+};
+
+const PROGRAMS: [(&str, &str); 9] = [
+    ("casts", include_str!("programs/casts.bline")),
+    ("comments", include_str!("programs/comments.bline")),
+    ("enums", include_str!("programs/enums.bline")),
+    ("indexing", include_str!("programs/indexing.bline")),
+    ("lambdas", include_str!("programs/lambdas.bline")),
+    ("loops", include_str!("programs/loops.bline")),
+    ("optionals", include_str!("programs/optionals.bline")),
+    ("strings", include_str!("programs/strings.bline")),
+    ("structs", include_str!("programs/structs.bline")),
+];
+
+/// the tree as JSON without the positions, which change when the source is printed again
+fn tree(source: &str, name: &str) -> String {
+    let program = bline::parse(source).unwrap_or_else(|errors| panic!("{}: {:?}", name, errors));
+    to_json(&program)
+        .lines()
+        .map(|line| match line.find("\"start\": {") {
+            Some(start) => {
+                let end = start + line[start..].find('}').unwrap();
+                format!("{}{}", &line[..start], &line[end + 1..])
+            }
+            None => line.to_owned(),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[test]
+fn programs_compile() {
+    for (name, source) in PROGRAMS {
+        if let Err(errors) = bline::compile_to_c(source) {
+            panic!("{}: {:?}", name, errors);
+        }
+    }
+}
+
+#[test]
+fn printed_source_parses_to_the_same_tree() {
+    for (name, source) in PROGRAMS {
+        let printed = to_source(&bline::parse(source).unwrap());
+        assert_eq!(tree(source, name), tree(&printed, name), "{}", name);
+    }
+}
+
+#[test]
+fn printing_printed_source_changes_nothing() {
+    for (name, source) in PROGRAMS {
+        let printed = to_source(&bline::parse(source).unwrap());
+        let again = to_source(&bline::parse(&printed).unwrap());
+        assert_eq!(printed, again, "{}", name);
+    }
+}
+
+#[test]
+fn lossless_tokens_give_the_source_back() {
+    let mut sources: Vec<String> = PROGRAMS.iter().map(|(_, s)| s.to_string()).collect();
+    sources.push(String::from(
+        "func  main():i32{\r\n\t// tabs and CRLF\r\n    ret 0 ; /* end */ };",
+    ));
+    sources.push(String::from("let x: str = \"{1 + 2} \\{ é }\";\r\r\n\n"));
+
+    for source in sources {
+        let tokens = Lexer::lossless(&source)
+            .collect::<Result<Vec<Token>, _>>()
+            .unwrap();
+        assert_eq!(source_text(&tokens), source);
+    }
+}
+
+#[test]
+fn formatting_twice_changes_nothing() {
+    for (name, source) in PROGRAMS {
+        let formatted = format(source).unwrap();
+        assert_eq!(format(&formatted).unwrap(), formatted, "{}", name);
+        assert_eq!(tree(source, name), tree(&formatted, name), "{}", name);
+    }
+}
+
+#[test]
+fn formatted_programs_are_left_alone() {
+    for (name, source) in PROGRAMS {
+        assert_eq!(format(source).unwrap(), source, "{}", name);
+    }
+}
+
+#[test]
+fn formatting_keeps_a_space_between_a_number_and_a_method() {
+    let source = "func main(): i32 {\n    let s: str = 0.1 .len() as str;\n    ret 1 .len();\n};\n";
+    assert_eq!(format(source).unwrap(), source);
+}
@@ -0,0 +1,118 @@
+use bline::frontend::{
+    json::to_json,
+    types::{Expression, Statement},
+    visit::{fold_expression, walk_expression, walk_expression_mut, Fold, Visitor, VisitorMut},
+};
+
+/// 'xx' is read in every kind of place an expression can be, renaming it to 'yy' in the source
+/// renames exactly the identifiers a pass reaches
+const SOURCE: &str = r#"obj P {
+    f: vec<i32>,
+};
+
+let K: i32 = xx * 2;
+
+func g(a: i32): i32 {
+    ret xx;
+};
+
+func main(): i32 {
+    let a: i32 = xx + -xx * (xx as i32);
+    mut b: vec<i32> = [xx, xx][xx];
+    b[xx] = xx;
+    xx.f[xx] += xx;
+    let c: P = P { f = [xx] };
+    let d: i32 = xx ? xx : xx;
+    let e: func(i32): i32 = (n: i32): i32 => xx;
+    let h: func(i32): null = (n: i32): null => {
+        g(xx);
+    };
+    let s: str = "{xx} and {xx.len()}";
+    if xx {
+        g(xx);
+    } elseif !xx {
+        g(xx, ~xx);
+    } else {
+        ret xx;
+    };
+    while xx {
+        brk;
+    };
+    for mut i: i32 = xx; i < xx; i += xx; {
+        g(xx);
+    };
+    for v in xx..=xx {
+        g(xx.y(xx));
+    };
+    switch xx {
+        case 1 {
+            g(xx);
+        };
+        casenot {
+            g(xx);
+        };
+    };
+    ret xx || xx && xx;
+};
+"#;
+
+fn parse(source: &str) -> Statement {
+    bline::parse(source).unwrap_or_else(|errors| panic!("{:?}", errors))
+}
+
+struct Count(usize);
+
+impl Visitor for Count {
+    fn visit_expression(&mut self, expression: &Expression) {
+        if matches!(expression, Expression::Identifier(name) if name == "xx") {
+            self.0 += 1;
+        }
+        walk_expression(self, expression)
+    }
+}
+
+struct Rename;
+
+impl VisitorMut for Rename {
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        match expression {
+            Expression::Identifier(name) if name == "xx" => *name = String::from("yy"),
+            _ => walk_expression_mut(self, expression),
+        }
+    }
+}
+
+impl Fold for Rename {
+    fn fold_expression(&mut self, expression: Expression) -> Expression {
+        match expression {
+            Expression::Identifier(name) if name == "xx" => Expression::Identifier("yy".into()),
+            _ => fold_expression(self, expression),
+        }
+    }
+}
+
+#[test]
+fn visitor_reaches_every_expression() {
+    let mut count = Count(0);
+    count.visit_statement(&parse(SOURCE));
+    assert_eq!(count.0, SOURCE.matches("xx").count());
+}
+
+#[test]
+fn visitor_mut_reaches_every_expression() {
+    let mut program = parse(SOURCE);
+    Rename.visit_statement_mut(&mut program);
+    assert_eq!(
+        to_json(&program),
+        to_json(&parse(&SOURCE.replace("xx", "yy")))
+    );
+}
+
+#[test]
+fn fold_reaches_every_expression() {
+    let program = Rename.fold_statement(parse(SOURCE));
+    assert_eq!(
+        to_json(&program),
+        to_json(&parse(&SOURCE.replace("xx", "yy")))
+    );
+}